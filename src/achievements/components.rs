@@ -0,0 +1,11 @@
+use bevy::prelude::Component;
+
+/// Shows an unlocked achievement on top of any screen
+#[derive(Component)]
+pub struct Toast;
+
+#[derive(Component)]
+pub struct ButtonReturn;
+
+#[derive(Component)]
+pub struct AchievementsButton;
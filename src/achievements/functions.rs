@@ -0,0 +1,33 @@
+use crate::{
+    enums::Goal,
+    resources::{Campaign, Profile},
+};
+
+/// Whether the player reached the goal of an achievement
+pub fn is_reached(goal: Goal, profile: &Profile, campaign: &Campaign) -> bool {
+    let record = profile.get_achievements();
+    match goal {
+        Goal::Solve(count) => profile.count_solved() >= count,
+        Goal::SolveCampaign => campaign
+            .stages
+            .iter()
+            .flat_map(|stage| stage.levels.iter())
+            .all(|level| profile.get_level_points(&level.id).is_some()),
+        Goal::Points(points) => profile.get_points() >= points,
+        Goal::FlawlessStage => campaign.stages.iter().any(|stage| {
+            !stage.levels.is_empty()
+                && stage.levels.iter().all(|level| {
+                    profile
+                        .get_record(&level.id)
+                        .and_then(|record| record.mistakes)
+                        == Some(0)
+                })
+        }),
+        Goal::HiddenCells(hidden) => record.largest_board >= hidden,
+        Goal::NoHints(count) => record.no_hint_streak >= count,
+        Goal::NoMistakes(count) => record.no_mistake_streak >= count,
+        Goal::Hardcore(count) => profile.count_hardcore() >= count,
+        Goal::DailyStreak(days) => profile.get_daily().best_streak >= days,
+        Goal::EndlessScore(score) => profile.get_endless().best_score >= score,
+    }
+}
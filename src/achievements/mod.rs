@@ -0,0 +1,32 @@
+mod components;
+mod functions;
+mod resources;
+mod setup;
+mod systems;
+
+use self::{components::AchievementsButton, resources::Toasts, setup::setup, systems::*};
+use crate::{
+    cleanup_system, components::RootComponent, states::AppState, systems::menu_button_hovered,
+};
+use bevy::prelude::{App, ParallelSystemDescriptorCoercion, SystemSet};
+use interactable::InteractLabel;
+
+const STATE: AppState = AppState::Achievements;
+
+pub fn prepare_achievements(app: &mut App) {
+    // Achievements are unlocked on every screen
+    app.init_resource::<Toasts>()
+        .add_system(unlock_system)
+        .add_system(toast_system.after(unlock_system))
+        .add_system_set(SystemSet::on_enter(STATE).with_system(setup))
+        .add_system_set(
+            SystemSet::on_update(STATE)
+                .with_system(return_button_click_system.after(InteractLabel::Interact))
+                .with_system(
+                    menu_button_hovered::<AchievementsButton>.after(InteractLabel::Interact),
+                )
+                .with_system(hotkey_system)
+                .with_system(window_resize_system),
+        )
+        .add_system_set(SystemSet::on_exit(STATE).with_system(cleanup_system::<RootComponent>));
+}
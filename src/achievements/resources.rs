@@ -0,0 +1,9 @@
+use std::collections::VecDeque;
+
+/// Achievements that were unlocked and wait for their toast, by id
+#[derive(Default)]
+pub struct Toasts {
+    pub queue: VecDeque<String>,
+    /// When the toast that is shown disappears, in seconds since startup
+    pub hide_at: Option<f64>,
+}
@@ -0,0 +1,140 @@
+use super::components::{AchievementsButton, ButtonReturn};
+use crate::{
+    assets::LocaleAsset,
+    bundles::MenuButtonBundle,
+    components::RootComponent,
+    constants::{RADIUS, Z_INDEX_TEXT},
+    functions::rescale_board,
+    resources::{Achievements, GameColors, LocaleAssets, Profile, TextSettings},
+};
+use bevy::{
+    hierarchy::BuildChildren,
+    math::{Vec2, Vec3},
+    prelude::{
+        default, shape::Quad, Assets, Commands, Mesh, Res, ResMut, SpatialBundle, Transform,
+    },
+    sprite::ColorMesh2dBundle,
+    text::{Text, Text2dBundle},
+    window::Windows,
+};
+
+/// Size of the boxes of the achievements
+const ACHIEVEMENT_BOX: (f32, f32) = (540., 150.);
+
+type StandardResources<'a> = (
+    Res<'a, GameColors>,
+    Res<'a, LocaleAssets>,
+    Res<'a, Profile>,
+    Res<'a, TextSettings>,
+);
+pub fn setup(
+    mut commands: Commands,
+    wnds: Res<Windows>,
+    (game_colors, locale, profile, text_settings): StandardResources,
+    achievements: Res<Achievements>,
+    (mut meshes, locales): (ResMut<Assets<Mesh>>, Res<Assets<LocaleAsset>>),
+) {
+    let get = |key: &str| {
+        locale
+            .get_string(key, &locales, &profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone()
+    };
+    let unlocked = achievements
+        .entries
+        .iter()
+        .filter(|entry| profile.has_achievement(&entry.id))
+        .count();
+    let mut children = vec![commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::from_section(
+                format!(
+                    "{} {}/{}",
+                    get("achievements-title"),
+                    unlocked,
+                    achievements.entries.len()
+                ),
+                text_settings.style_cell_large.clone(),
+            )
+            .with_alignment(text_settings.alignment),
+            transform: Transform::from_xyz(0., 5. * RADIUS, Z_INDEX_TEXT),
+            ..default()
+        })
+        .id()];
+
+    // Three columns, filled row by row. The locked ones are grayed out.
+    for (i, entry) in achievements.entries.iter().enumerate() {
+        let x = (i % 3) as f32 * 560. - 560.;
+        let y = 3.6 * RADIUS - (i / 3) as f32 * 1.7 * RADIUS;
+        let material = match profile.has_achievement(&entry.id) {
+            true => game_colors.yellow_light.clone(),
+            false => game_colors.menu_button.clone(),
+        };
+        let name = get(&format!("ach-{}", entry.id));
+        let info = get(&format!("ach-{}-info", entry.id));
+        let achievement = commands
+            .spawn_bundle(ColorMesh2dBundle {
+                mesh: meshes
+                    .add(Mesh::from(Quad::new(Vec2::new(
+                        ACHIEVEMENT_BOX.0,
+                        ACHIEVEMENT_BOX.1,
+                    ))))
+                    .into(),
+                material,
+                transform: Transform::from_xyz(x, y, 0.9),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn_bundle(Text2dBundle {
+                    text: Text::from_section(name, text_settings.style_menu_dark.clone())
+                        .with_alignment(text_settings.alignment),
+                    transform: Transform::from_xyz(0., 30., 10.)
+                        .with_scale(Vec3::new(0.75, 0.75, 1.)),
+                    ..default()
+                });
+                parent.spawn_bundle(Text2dBundle {
+                    text: Text::from_section(info, text_settings.style_menu_dark.clone())
+                        .with_alignment(text_settings.alignment),
+                    transform: Transform::from_xyz(0., -35., 10.)
+                        .with_scale(Vec3::new(0.45, 0.45, 1.)),
+                    ..default()
+                });
+            })
+            .id();
+        children.push(achievement);
+    }
+
+    children.push(
+        commands
+            .spawn_bundle(MenuButtonBundle::new(
+                Transform::from_xyz(0., -4.5 * RADIUS, 0.9),
+                (270., 170.),
+                game_colors.menu_button.clone(),
+                &mut meshes,
+            ))
+            .with_children(|parent| {
+                parent.spawn_bundle(Text2dBundle {
+                    text: Text::from_section(get("return"), text_settings.style_menu_dark.clone())
+                        .with_alignment(text_settings.alignment),
+                    transform: Transform::from_xyz(0., -10., 10.)
+                        .with_scale(Vec3::new(0.75, 0.75, 1.)),
+                    ..default()
+                });
+            })
+            .insert(ButtonReturn)
+            .insert(AchievementsButton)
+            .id(),
+    );
+
+    let mut root_transform = Transform::identity();
+    for wnd in wnds.iter() {
+        // TODO: Remove hard-coded width/height
+        rescale_board(10, 6, 1, wnd.width(), wnd.height(), &mut root_transform);
+    }
+
+    commands
+        .spawn()
+        .push_children(&children)
+        .insert_bundle(SpatialBundle::from_transform(root_transform))
+        .insert(RootComponent);
+}
@@ -0,0 +1,174 @@
+use super::{
+    components::{ButtonReturn, Toast},
+    functions::is_reached,
+    resources::Toasts,
+};
+use crate::{
+    assets::LocaleAsset,
+    components::RootComponent,
+    constants::Z_INDEX_UI,
+    functions::{rescale_board, switch_state},
+    resources::{
+        Achievements, Campaign, GameColors, LoadState, LocaleAssets, Profile, Profiles,
+        TextSettings,
+    },
+    states::AppState,
+};
+use bevy::{
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+    input::Input,
+    math::{Vec2, Vec3},
+    prelude::{
+        default, shape::Quad, Assets, Commands, DetectChanges, Entity, EventReader, KeyCode, Local,
+        Mesh, Query, Res, ResMut, State, Transform, With,
+    },
+    sprite::ColorMesh2dBundle,
+    text::{Text, Text2dBundle},
+    time::Time,
+    window::{WindowResized, Windows},
+};
+use interactable::components::ReleasedLeft;
+
+/// Size of the toast, before it's scaled to the window
+const TOAST_SIZE: (f32, f32) = (800., 140.);
+/// Seconds a toast is shown
+const TOAST_SECONDS: f64 = 3.;
+
+/// Unlocks the achievements whose goal is reached after the profile changed.
+/// The ones reached before the game started or by another profile are unlocked without a toast.
+pub fn unlock_system(
+    mut profile: ResMut<Profile>,
+    (achievements, campaign, profiles): (Res<Achievements>, Res<Campaign>, Res<Profiles>),
+    mut toasts: ResMut<Toasts>,
+    mut started: Local<bool>,
+) {
+    if *started && !profile.is_changed() {
+        return;
+    }
+    let silent = !*started || profiles.is_changed();
+    *started = true;
+    let reached: Vec<String> = achievements
+        .entries
+        .iter()
+        .filter(|entry| {
+            !profile.has_achievement(&entry.id) && is_reached(entry.goal, &profile, &campaign)
+        })
+        .map(|entry| entry.id.clone())
+        .collect();
+    for id in reached {
+        profile.unlock_achievement(&id);
+        if !silent {
+            toasts.queue.push_back(id);
+        }
+    }
+}
+
+type ToastResources<'a> = (
+    Option<Res<'a, GameColors>>,
+    Option<Res<'a, LocaleAssets>>,
+    Option<Res<'a, TextSettings>>,
+);
+/// Shows the toasts of the unlocked achievements one after another
+pub fn toast_system(
+    mut commands: Commands,
+    toast_query: Query<Entity, With<Toast>>,
+    mut toasts: ResMut<Toasts>,
+    (time, wnds): (Res<Time>, Res<Windows>),
+    (game_colors, locale, text_settings): ToastResources,
+    (profile, locales, mut meshes): (Res<Profile>, Res<Assets<LocaleAsset>>, ResMut<Assets<Mesh>>),
+) {
+    let now = time.seconds_since_startup();
+    match toasts.hide_at {
+        Some(hide_at) if now < hide_at => return,
+        Some(_) => {
+            for entity in toast_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            toasts.hide_at = None;
+        }
+        None => (),
+    }
+    // The assets are missing while they are loaded
+    let (game_colors, locale, text_settings) = match (game_colors, locale, text_settings) {
+        (Some(game_colors), Some(locale), Some(text_settings)) => {
+            (game_colors, locale, text_settings)
+        }
+        _ => return,
+    };
+    let id = match toasts.queue.pop_front() {
+        Some(id) => id,
+        None => return,
+    };
+    let get = |key: &str| {
+        locale
+            .get_string(key, &locales, &profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone()
+    };
+
+    // At the top of the window, scaled like the overlay
+    let mut transform = Transform::from_xyz(0., 0., Z_INDEX_UI + 10.);
+    for wnd in wnds.iter() {
+        let s = (wnd.width() / 1920.).min(wnd.height() / 1080.);
+        transform.scale = Vec3::new(s, s, 1.);
+        transform.translation.y = wnd.height() / 2. - (TOAST_SIZE.1 / 2. + 20.) * s;
+    }
+    commands
+        .spawn_bundle(ColorMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(Quad::new(Vec2::new(TOAST_SIZE.0, TOAST_SIZE.1))))
+                .into(),
+            material: game_colors.yellow_light.clone(),
+            transform,
+            ..default()
+        })
+        .insert(Toast)
+        .with_children(|parent| {
+            parent.spawn_bundle(Text2dBundle {
+                text: Text::from_section(
+                    format!(
+                        "{}\n{}",
+                        get("achievement-unlocked"),
+                        get(&format!("ach-{}", id))
+                    ),
+                    text_settings.style_menu_dark.clone(),
+                )
+                .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(0., 0., 1.).with_scale(Vec3::new(0.75, 0.75, 1.)),
+                ..default()
+            });
+        });
+    toasts.hide_at = Some(now + TOAST_SECONDS);
+}
+
+pub fn return_button_click_system(
+    return_query: Query<&ButtonReturn, With<ReleasedLeft>>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if !return_query.is_empty() {
+        switch_state(Some(AppState::Home), &mut app_state, &mut load_state);
+    }
+}
+
+pub fn hotkey_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        keys.clear_just_pressed(KeyCode::Escape);
+        switch_state(Some(AppState::Home), &mut app_state, &mut load_state);
+    }
+}
+
+/// On resizing the window, the gallery is resized too
+pub fn window_resize_system(
+    mut ev_window_resize: EventReader<WindowResized>,
+    mut root_query: Query<&mut Transform, With<RootComponent>>,
+) {
+    for ev in ev_window_resize.iter() {
+        if let Ok(mut root) = root_query.get_single_mut() {
+            // TODO: Remove hard-coded width/height
+            rescale_board(10, 6, 1, ev.width, ev.height, &mut root);
+        }
+    }
+}
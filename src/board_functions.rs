@@ -9,6 +9,17 @@ pub fn get_neighbours(
     w: usize,
     h: usize,
 ) -> Vec<(Option<CellType>, bool)> {
+    get_neighbour_positions(x, y, w, h)
+        .iter()
+        .map(|pos| match pos {
+            Some((x, y)) => cells[*y][*x],
+            None => (None, false),
+        })
+        .collect()
+}
+
+/// Get a ordered list of neighbouring positions, None if outside of the board
+pub fn get_neighbour_positions(x: i32, y: i32, w: usize, h: usize) -> Vec<Option<(usize, usize)>> {
     let pos = if x % 2 == 0 {
         [
             (x, y - 1),
@@ -31,9 +42,9 @@ pub fn get_neighbours(
     pos.iter()
         .map(|(x, y)| {
             if *x < 0 || *x >= w as i32 || *y < 0 || *y >= h as i32 {
-                (None, false)
+                None
             } else {
-                cells[*y as usize][*x as usize]
+                Some((*x as usize, *y as usize))
             }
         })
         .collect()
@@ -48,8 +59,22 @@ pub fn get_column(
     cells: &[Vec<(Option<CellType>, bool)>],
     dir: HintDirection,
 ) -> Vec<(Option<CellType>, bool)> {
+    get_column_positions(x, y, w, h, dir)
+        .iter()
+        .map(|(x, y)| cells[*y][*x])
+        .collect()
+}
+
+/// Get a ordered list of positions in same column (or diagonal)
+pub fn get_column_positions(
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+    dir: HintDirection,
+) -> Vec<(usize, usize)> {
     match dir {
-        HintDirection::Down => (y..h).into_iter().map(|dy| cells[dy][x]).collect(),
+        HintDirection::Down => (y..h).into_iter().map(|dy| (x, dy)).collect(),
         HintDirection::LeftDown => {
             let mut pts = VecDeque::new();
             let mut dx = x;
@@ -59,14 +84,14 @@ pub fn get_column(
                     dy += 1;
                 }
                 dx -= 1;
-                pts.push_front(cells[dy][dx]);
+                pts.push_front((dx, dy));
             }
-            pts.push_back(cells[y][x]);
+            pts.push_back((x, y));
             pts.into()
         }
         HintDirection::RightDown => {
             let mut pts = VecDeque::new();
-            pts.push_back(cells[y][x]);
+            pts.push_back((x, y));
             let mut dx = x;
             let mut dy = y;
             while dx < w - 1 && (dy < h - 1 || dx % 2 == 1) {
@@ -74,11 +99,11 @@ pub fn get_column(
                     dy += 1;
                 }
                 dx += 1;
-                pts.push_back(cells[dy][dx]);
+                pts.push_back((dx, dy));
             }
             pts.into()
         }
-        HintDirection::Up => (0..=y).into_iter().map(|dy| cells[dy][x]).collect(),
+        HintDirection::Up => (0..=y).into_iter().map(|dy| (x, dy)).collect(),
         HintDirection::LeftUp => {
             let mut pts = VecDeque::new();
             let mut dx = x;
@@ -88,14 +113,14 @@ pub fn get_column(
                     dy -= 1;
                 }
                 dx -= 1;
-                pts.push_front(cells[dy][dx]);
+                pts.push_front((dx, dy));
             }
-            pts.push_back(cells[y][x]);
+            pts.push_back((x, y));
             pts.into()
         }
         HintDirection::RightUp => {
             let mut pts = VecDeque::new();
-            pts.push_back(cells[y][x]);
+            pts.push_back((x, y));
             let mut dx = x;
             let mut dy = y;
             while dx < w - 1 && (dy > 0 || dx % 2 == 0) {
@@ -103,7 +128,7 @@ pub fn get_column(
                     dy -= 1;
                 }
                 dx += 1;
-                pts.push_back(cells[dy][dx]);
+                pts.push_back((dx, dy));
             }
             pts.into()
         }
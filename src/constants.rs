@@ -1,6 +1,35 @@
 use bevy::prelude::{Color, Transform};
 
-pub const UNLOCK_POINTS: [u16; 6] = [0, 40, 100, 150, 210, 310];
+/// Directory of the level files and the campaign manifest listing them
+pub const LEVEL_DIR: &str = "assets/levels";
+pub const CAMPAIGN_FILE: &str = "campaign.ron";
+/// Directory of the level packs in the user directory and the file describing a pack
+pub const CUSTOM_DIR: &str = "levels";
+pub const PACK_FILE: &str = "pack.ron";
+/// Pack that the editor saves its levels to
+pub const EDITOR_PACK: &str = "editor";
+/// Prefix of the ids of levels in packs, their results don't count for the campaign
+pub const CUSTOM_PREFIX: &str = "custom/";
+/// Stage and level id of generated boards, they belong to neither the campaign nor a pack
+pub const GENERATED_IDS: (u8, u8) = (u8::MAX, 0);
+/// Definitions of the achievements and the most the gallery can show
+pub const ACHIEVEMENTS_FILE: &str = "assets/achievements.ron";
+pub const MAX_ACHIEVEMENTS: usize = 12;
+
+/// Name of the directory of the player's files in the platform's data directory
+pub const USER_DIR: &str = "honeycombs";
+/// Files of a player, they were stored next to the executable and then
+/// directly in the user directory before there were several profiles
+pub const USER_FILES: [&str; 3] = ["settings.ron", "progress.ron", "replays.ron"];
+/// The list of profiles and the directory with a subdirectory for each of them
+pub const PROFILES_FILE: &str = "profiles.ron";
+pub const PROFILES_DIR: &str = "profiles";
+/// Limits of the profile screen, so that the list fits on it
+pub const MAX_PROFILES: usize = 6;
+pub const MAX_PROFILE_NAME: usize = 16;
+
+/// Mistakes allowed in the hardcore mode, the mode button cycles through them
+pub const HARDCORE_LIMITS: [u16; 4] = [0, 1, 3, 5];
 
 /// Hex radius (i.e. circumcircle)
 pub const RADIUS: f32 = 100.0;
@@ -27,6 +56,7 @@ impl GameColor {
     pub const BLUE_DARK: Color = Color::rgb(0.0, 0.439, 0.894); // #0070e4
     pub const BLUE_MEDIUM: Color = Color::rgb(0.0, 0.533, 0.91); // #0088e8
     pub const BLUE_LIGHT: Color = Color::rgb(0.0, 0.627, 0.941); // #00a0f0
+    pub const HIGHLIGHT: Color = Color::rgba(1.0, 1.0, 1.0, 0.6);
     pub const ALPHA_0: Color = Color::rgba(0.5, 0.5, 0.5, 0.);
     pub const ALPHA_1: Color = Color::rgba(0.5, 0.5, 0.5, 0.1);
     pub const ALPHA_2: Color = Color::rgba(0.5, 0.5, 0.5, 0.2);
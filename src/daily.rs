@@ -0,0 +1,80 @@
+use crate::{
+    functions::get_user_path,
+    generator::{generate, GeneratorSettings},
+    storage::write_atomic,
+    structs::BoardConfig,
+};
+use std::{
+    io,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Every daily puzzle has this size and mix, only the seed changes
+const DAILY_SETTINGS: GeneratorSettings = GeneratorSettings {
+    width: 9,
+    height: 7,
+    blue: 40,
+    holes: 15,
+};
+/// Mixed into the day, so the daily puzzles differ from other seeded ones
+const DAILY_SALT: u64 = 0x686f_6e65_7963_6f6d;
+/// File the result summary is written to, in the user directory
+const SUMMARY_FILE: &str = "daily.txt";
+
+/// The current day as days since 1970-01-01. It is the UTC day,
+/// so that everyone gets the same puzzle at the same time.
+pub fn today() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| (d.as_secs() / 86400) as u32)
+}
+
+/// The day as year-month-day
+pub fn format_date(day: u32) -> String {
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = day as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// The puzzle of the day, the same on every computer
+pub fn daily_board(day: u32) -> BoardConfig {
+    generate(DAILY_SALT ^ day as u64, &DAILY_SETTINGS)
+}
+
+/// Writes the result summary to a file in the user directory, from where it can be shared
+pub fn write_summary(summary: &str) -> io::Result<()> {
+    write_atomic(&get_user_path().join(SUMMARY_FILE), summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::board_to_string, solver::Solver};
+
+    #[test]
+    fn daily_board_is_fixed_per_day() {
+        let day = 19000;
+        let board = daily_board(day);
+        assert_eq!(
+            board_to_string(board.clone()),
+            board_to_string(daily_board(day))
+        );
+        assert_ne!(board.cells, daily_board(day + 1).cells);
+        assert_eq!((board.width, board.height), (9, 7));
+        assert!(Solver::new(&board).solve());
+    }
+
+    #[test]
+    fn date_of_day() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(19000), "2022-01-08");
+    }
+}
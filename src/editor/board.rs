@@ -1,23 +1,46 @@
 use super::{
     components::{EditorCell, EmptyCell, NumberCell, UnsetCell},
-    functions::{row_empty, spawn_cell_common},
+    constants::{CANVAS_MAX_HEIGHT, CANVAS_MAX_WIDTH},
+    functions::{apply_axial_offset, axial_offset, row_empty, spawn_cell_common, with_symmetry},
+    history::{Edit, History},
+    resources::{Clipboard, Field, Paint, Symmetry},
 };
 use crate::{
-    components::RootComponent,
+    board_functions::get_neighbour_positions,
+    components::{ColumnHint, RootComponent},
     enums::{CellType, HintType},
-    functions::calc_dimensions,
+    functions::{calc_dimensions, spawn_hint},
     resources::{CellMeshes, GameColors, TextSettings},
-    structs::BoardConfig,
+    solver::same_column,
+    structs::{BoardConfig, CellGrid, LevelMeta, LevelText},
 };
 use bevy::{
     hierarchy::BuildChildren,
     prelude::{Commands, SpatialBundle, Transform, Visibility},
 };
 
+/// Edge of the canvas
+#[derive(Clone, Copy)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
 pub struct Board {
     pub cells: Vec<Vec<(Option<CellType>, bool)>>,
+    pub hints: Vec<ColumnHint>,
     pub width: usize,
     pub height: usize,
+    /// Position of the initial top left cell, moves when the canvas grows or shrinks at the top or left
+    pub origin: (i32, i32),
+    pub history: History,
+    /// Offset of the level text from its default position below the board
+    pub text_anchor: (i32, i32),
+    /// Level text, an empty text is not saved
+    pub text: LevelText,
+    pub meta: LevelMeta,
 }
 
 impl Board {
@@ -29,10 +52,42 @@ impl Board {
         game_colors: &GameColors,
         text_settings: &TextSettings,
     ) -> Self {
-        let cells = &config.cells;
-        let _hints = &config.hints;
-        let width = config.width;
-        let height = config.height;
+        let board = Self {
+            cells: config.cells.clone(),
+            hints: config.hints.clone(),
+            width: config.width,
+            height: config.height,
+            origin: (0, 0),
+            history: History::default(),
+            text_anchor: config.text.as_ref().map_or((0, 0), |(x, y, _)| (*x, *y)),
+            text: config
+                .text
+                .as_ref()
+                .map_or(LevelText::Key(String::new()), |(_, _, text)| text.clone()),
+            meta: config.meta.clone(),
+        };
+        board.spawn(
+            commands,
+            root_transform,
+            cell_meshes,
+            game_colors,
+            text_settings,
+        );
+        board
+    }
+
+    /// Spawns the entities of the board under a new root
+    pub fn spawn(
+        &self,
+        commands: &mut Commands,
+        root_transform: Transform,
+        cell_meshes: &CellMeshes,
+        game_colors: &GameColors,
+        text_settings: &TextSettings,
+    ) {
+        let cells = &self.cells;
+        let width = self.width;
+        let height = self.height;
 
         let mut cell_entities = Vec::new();
 
@@ -98,12 +153,8 @@ impl Board {
                     cell_type: *ct,
                     text_entity,
                 });
-                if let Some(CellType::NumberCell(ht)) = ct {
-                    commands.entity(cell).insert(NumberCell {
-                        count: 0,
-                        label: text_entity,
-                        special_hint: ht != &HintType::None,
-                    });
+                if let Some(CellType::NumberCell(_)) = ct {
+                    commands.entity(cell).insert(NumberCell);
                     commands
                         .entity(text_entity)
                         .insert(Visibility { is_visible: true });
@@ -111,20 +162,384 @@ impl Board {
                 cell_entities.push(cell);
             }
         }
+        let hint_entities: Vec<_> = self
+            .hints
+            .iter()
+            .map(|hint| {
+                spawn_hint(
+                    commands,
+                    *hint,
+                    cells,
+                    text_settings,
+                    (w, h),
+                    (width, height),
+                )
+                .0
+            })
+            .collect();
         commands
             .spawn()
             .push_children(&cell_entities)
+            .push_children(&hint_entities)
             .insert_bundle(SpatialBundle::from_transform(root_transform))
             .insert(RootComponent);
+    }
+
+    /// Changes a cell and records the change in the history
+    pub fn set_cell(&mut self, x: usize, y: usize, entry: (Option<CellType>, bool)) {
+        let before = self.cells[y][x];
+        if before != entry {
+            self.cells[y][x] = entry;
+            self.history.record(Edit::Cell {
+                x,
+                y,
+                before,
+                after: entry,
+            });
+        }
+    }
+
+    /// Paints the given cells and their mirror images
+    pub fn paint(&mut self, positions: &[(usize, usize)], paint: Paint, symmetry: Symmetry) {
+        for (x, y) in with_symmetry(positions, self.width, self.height, symmetry) {
+            if x < self.width && y < self.height {
+                self.set_cell(x, y, paint.apply(self.cells[y][x]));
+            }
+        }
+    }
+
+    /// The connected cells that look like the given one, used for flood filling
+    pub fn region(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        // Special hints are equal no matter how they are resolved
+        let kind = |(ct, hidden): (Option<CellType>, bool)| {
+            let ct = match ct {
+                Some(CellType::NumberCell(ht)) if ht != HintType::None => {
+                    Some(CellType::NumberCell(HintType::Some))
+                }
+                _ => ct,
+            };
+            (ct, hidden)
+        };
+        let target = kind(self.cells[y][x]);
+        let mut visited = vec![vec![false; self.width]; self.height];
+        visited[y][x] = true;
+        let mut region = vec![(x, y)];
+        let mut i = 0;
+        while i < region.len() {
+            let (x, y) = region[i];
+            for (nx, ny) in get_neighbour_positions(x as i32, y as i32, self.width, self.height)
+                .into_iter()
+                .flatten()
+            {
+                if !visited[ny][nx] && kind(self.cells[ny][nx]) == target {
+                    visited[ny][nx] = true;
+                    region.push((nx, ny));
+                }
+            }
+            i += 1;
+        }
+        region
+    }
 
-        Self {
-            cells: cells.clone(),
-            width,
-            height,
+    /// Copies the set cells and the column hints at the given positions.
+    /// The first position is the anchor that is placed at the cursor when pasting.
+    pub fn copy(&self, positions: &[(usize, usize)]) -> Clipboard {
+        let mut clipboard = Clipboard::default();
+        let anchor = match positions.first() {
+            Some(anchor) => *anchor,
+            None => return clipboard,
+        };
+        for &(x, y) in positions {
+            let offset = axial_offset(anchor, (x, y));
+            if self.cells[y][x].0.is_some() {
+                clipboard.cells.push((offset, self.cells[y][x]));
+            }
+            for hint in self.hints.iter().filter(|h| h.x == x && h.y == y) {
+                clipboard.hints.push((offset, hint.dir, hint.hint_type));
+            }
+        }
+        clipboard
+    }
+
+    /// Erases the given cells and their column hints.
+    /// Returns whether hints were removed, which requires respawning the board.
+    pub fn cut(&mut self, positions: &[(usize, usize)]) -> bool {
+        self.paint(positions, Paint::Erase, Symmetry::None);
+        let hints: Vec<_> = self
+            .hints
+            .iter()
+            .filter(|h| positions.contains(&(h.x, h.y)))
+            .copied()
+            .collect();
+        for hint in &hints {
+            self.remove_hint(*hint);
+        }
+        !hints.is_empty()
+    }
+
+    /// Pastes the clipboard with its anchor at the given cell, cells outside the canvas are dropped.
+    /// Returns whether hints were added, which requires respawning the board.
+    pub fn paste(&mut self, clipboard: &Clipboard, at: (usize, usize)) -> bool {
+        for (offset, entry) in &clipboard.cells {
+            if let Some((x, y)) = apply_axial_offset(at, *offset, self.width, self.height) {
+                self.set_cell(x, y, *entry);
+            }
+        }
+        let mut hints_added = false;
+        for (offset, dir, hint_type) in &clipboard.hints {
+            if let Some((x, y)) = apply_axial_offset(at, *offset, self.width, self.height) {
+                self.add_hint(ColumnHint {
+                    x,
+                    y,
+                    dir: *dir,
+                    hint_type: *hint_type,
+                });
+                hints_added = true;
+            }
         }
+        hints_added
     }
-    pub fn trim(&self) -> Vec<Vec<(Option<CellType>, bool)>> {
+
+    /// Adds a column hint, replacing a hint of the same column
+    pub fn add_hint(&mut self, hint: ColumnHint) {
+        if let Some(old) = self.hints.iter().find(|h| same_column(h, &hint)).copied() {
+            self.remove_hint(old);
+        }
+        self.hints.push(hint);
+        self.history.record(Edit::AddHint(hint));
+    }
+
+    pub fn remove_hint(&mut self, hint: ColumnHint) {
+        if let Some(i) = self.hints.iter().position(|h| same_column(h, &hint)) {
+            let removed = self.hints.remove(i);
+            self.history.record(Edit::RemoveHint(removed));
+        }
+    }
+
+    /// Reverts the last batch of edits. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo() {
+            Some(edits) => {
+                edits.iter().for_each(|edit| self.apply(edit));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Repeats the last undone batch of edits. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo() {
+            Some(edits) => {
+                edits.iter().for_each(|edit| self.apply(edit));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Applies an edit without recording it
+    fn apply(&mut self, edit: &Edit) {
+        match edit {
+            Edit::Cell { x, y, after, .. } => self.cells[*y][*x] = *after,
+            Edit::AddHint(hint) => self.hints.push(*hint),
+            Edit::RemoveHint(hint) => self.hints.retain(|h| !same_column(h, hint)),
+            Edit::Canvas { after, offset, .. } => {
+                self.cells = after.cells.clone();
+                self.hints = after.hints.clone();
+                self.width = after.width;
+                self.height = after.height;
+                self.origin.0 += offset.0;
+                self.origin.1 += offset.1;
+            }
+        }
+    }
+
+    /// Grows or shrinks the canvas at one edge.
+    /// Columns are added and removed in pairs to keep the hex grid aligned.
+    /// Returns false if the canvas can't be resized any further.
+    pub fn resize(&mut self, edge: Edge, grow: bool) -> bool {
+        let before = self.to_config();
+        let offset = match (edge, grow) {
+            (Edge::Top, true) if self.height < CANVAS_MAX_HEIGHT => {
+                self.cells.insert(0, vec![(None, false); self.width]);
+                (0, 1)
+            }
+            (Edge::Top, false) if self.height > 1 => {
+                self.cells.remove(0);
+                (0, -1)
+            }
+            (Edge::Bottom, true) if self.height < CANVAS_MAX_HEIGHT => {
+                self.cells.push(vec![(None, false); self.width]);
+                (0, 0)
+            }
+            (Edge::Bottom, false) if self.height > 1 => {
+                self.cells.pop();
+                (0, 0)
+            }
+            (Edge::Left, true) if self.width + 2 <= CANVAS_MAX_WIDTH => {
+                for row in &mut self.cells {
+                    row.splice(0..0, [(None, false); 2]);
+                }
+                (2, 0)
+            }
+            (Edge::Left, false) if self.width > 2 => {
+                for row in &mut self.cells {
+                    row.drain(0..2);
+                }
+                (-2, 0)
+            }
+            (Edge::Right, true) if self.width + 2 <= CANVAS_MAX_WIDTH => {
+                for row in &mut self.cells {
+                    row.extend([(None, false); 2]);
+                }
+                (0, 0)
+            }
+            (Edge::Right, false) if self.width > 2 => {
+                for row in &mut self.cells {
+                    row.truncate(row.len() - 2);
+                }
+                (0, 0)
+            }
+            _ => return false,
+        };
+        self.height = self.cells.len();
+        self.width = self.cells[0].len();
+        let (width, height) = (self.width as i32, self.height as i32);
+        // Move hints along and drop those whose cell was removed
+        self.hints = self
+            .hints
+            .iter()
+            .filter_map(|hint| {
+                let x = hint.x as i32 + offset.0;
+                let y = hint.y as i32 + offset.1;
+                if x < 0 || y < 0 || x >= width || y >= height {
+                    return None;
+                }
+                let mut hint = *hint;
+                hint.x = x as usize;
+                hint.y = y as usize;
+                Some(hint)
+            })
+            .collect();
+        self.origin.0 += offset.0;
+        self.origin.1 += offset.1;
+        self.history.record(Edit::Canvas {
+            before: Box::new(before),
+            after: Box::new(self.to_config()),
+            offset,
+        });
+        true
+    }
+
+    pub fn to_config(&self) -> BoardConfig {
+        BoardConfig {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.clone(),
+            hints: self.hints.clone(),
+            text: self.level_text(),
+            meta: self.meta.clone(),
+        }
+    }
+
+    /// The level text as stored in a level file
+    pub fn level_text(&self) -> Option<(i32, i32, LevelText)> {
+        let (x, y) = self.text_anchor;
+        match &self.text {
+            LevelText::Key(s) | LevelText::Inline(s) if s.is_empty() => None,
+            text => Some((x, y, text.clone())),
+        }
+    }
+
+    /// Switches between a locale key and inline text, keeping what was typed
+    pub fn toggle_text_type(&mut self) {
+        self.text = match &self.text {
+            LevelText::Key(s) => LevelText::Inline(s.clone()),
+            LevelText::Inline(s) => LevelText::Key(s.clone()),
+        };
+    }
+
+    /// Current value of a field of the properties panel
+    pub fn field(&self, field: Field) -> String {
+        match field {
+            Field::Text => match &self.text {
+                LevelText::Key(s) | LevelText::Inline(s) => s.clone(),
+            },
+            Field::Title => self.meta.title.clone().unwrap_or_default(),
+            Field::Author => self.meta.author.clone().unwrap_or_default(),
+            Field::Difficulty => self
+                .meta
+                .difficulty
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Sets a field of the properties panel, empty values are unset.
+    /// Returns false if the value is invalid.
+    pub fn set_field(&mut self, field: Field, value: String) -> bool {
+        let optional = |value: String| match value.is_empty() {
+            true => None,
+            false => Some(value),
+        };
+        match field {
+            Field::Text => match &mut self.text {
+                LevelText::Key(s) | LevelText::Inline(s) => *s = value,
+            },
+            Field::Title => self.meta.title = optional(value),
+            Field::Author => self.meta.author = optional(value),
+            Field::Difficulty => match optional(value).map(|d| d.parse()) {
+                Some(Ok(d)) => self.meta.difficulty = Some(d),
+                Some(Err(_)) => return false,
+                None => self.meta.difficulty = None,
+            },
+        }
+        true
+    }
+
+    /// Top left and bottom right corner of the set cells
+    pub fn bounds(&self) -> Option<((usize, usize), (usize, usize))> {
+        let mut bounds: Option<((usize, usize), (usize, usize))> = None;
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, (ct, _)) in row.iter().enumerate() {
+                if ct.is_none() {
+                    continue;
+                }
+                bounds = Some(match bounds {
+                    Some(((x0, y0), (x1, y1))) => ((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y))),
+                    None => ((x, y), (x, y)),
+                });
+            }
+        }
+        bounds
+    }
+
+    /// Applies the result of the minimal reveal solver.
+    /// Every suggestion becomes a separate batch in the history.
+    pub fn apply_suggestions(&mut self, design: &BoardConfig) {
+        self.history.commit();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.cells[y][x] != design.cells[y][x] {
+                    self.set_cell(x, y, design.cells[y][x]);
+                    self.history.commit();
+                }
+            }
+        }
+        for hint in &design.hints {
+            if !self.hints.iter().any(|h| same_column(h, hint)) {
+                self.add_hint(*hint);
+                self.history.commit();
+            }
+        }
+    }
+
+    /// Removes empty rows and columns around the level.
+    /// Hints are moved along with their cells.
+    pub fn trim(&self) -> (CellGrid, Vec<ColumnHint>) {
         let mut cells = self.cells.clone();
+        let mut hints = self.hints.clone();
         // Check rows
         loop {
             if cells.len() < 2 || !row_empty(&cells[0]) {
@@ -132,6 +547,7 @@ impl Board {
             }
             // Remove from top
             cells.remove(0);
+            hints.iter_mut().for_each(|hint| hint.y -= 1);
         }
         loop {
             if cells.len() < 2 || !row_empty(&cells[cells.len() - 1]) {
@@ -152,6 +568,7 @@ impl Board {
             for row in &mut cells {
                 *row = row.clone().into_iter().skip(2).collect();
             }
+            hints.iter_mut().for_each(|hint| hint.x -= 2);
         }
         loop {
             if cells[0].len() < 2
@@ -170,6 +587,7 @@ impl Board {
             for row in &mut cells {
                 *row = row.clone().into_iter().skip(1).collect();
             }
+            hints.iter_mut().for_each(|hint| hint.x -= 1);
             if row_empty(&(&cells[0]).iter().step_by(2).copied().collect()) {
                 // Pull up every 2n
                 let h = cells.len() - 1;
@@ -179,6 +597,10 @@ impl Board {
                     }
                     cells[h][x] = (None, false);
                 }
+                hints
+                    .iter_mut()
+                    .filter(|hint| hint.x % 2 == 0)
+                    .for_each(|hint| hint.y -= 1);
             } else {
                 // Insert new empty row, needed in some cases
                 cells.push(vec![(None, false); cells[0].len()]);
@@ -190,6 +612,10 @@ impl Board {
                     }
                     cells[0][x] = (None, false);
                 }
+                hints
+                    .iter_mut()
+                    .filter(|hint| hint.x % 2 == 1)
+                    .for_each(|hint| hint.y += 1);
             }
 
             // Remove from bottom if necessary
@@ -197,6 +623,6 @@ impl Board {
                 cells.remove(cells.len() - 1);
             }
         }
-        cells
+        (cells, hints)
     }
 }
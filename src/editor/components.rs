@@ -1,4 +1,7 @@
-use super::board::Board;
+use super::{
+    board::Board,
+    resources::{Field, Paint, Symmetry, Tool},
+};
 use crate::{components::Cell, enums::CellType, resources::GameColors};
 use bevy::{
     prelude::{Commands, Component, Entity, EventWriter, Handle, Query},
@@ -104,18 +107,15 @@ impl EditorCell {
         };
         // TODO: Does it really make sense to click here?
         cell.click(commands, None, c1, c2, color_query);
-        board.cells[cell.y as usize][cell.x as usize].1 = self.hidden;
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        board.set_cell(x, y, (board.cells[y][x].0, self.hidden));
         ev_cell_update.send(CellUpdateEvent);
     }
 }
 
 /// Component for the NumberCell type
 #[derive(Debug, Component)]
-pub struct NumberCell {
-    pub count: u8,
-    pub label: Entity,
-    pub special_hint: bool,
-}
+pub struct NumberCell;
 
 /// Component for the EmptyCell type
 #[derive(Debug, Component)]
@@ -125,3 +125,39 @@ pub struct EmptyCell;
 pub struct UnsetCell;
 
 pub struct CellUpdateEvent;
+
+/// Respawns all entities of the board, e.g. after an undo
+pub struct BoardRebuildEvent;
+
+/// Background of the toolbar, blocks painting on the cells below it
+#[derive(Component)]
+pub struct Toolbar;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ToolAction {
+    Paint(Paint),
+    Tool(Tool),
+    Symmetry(Symmetry),
+    /// Opens or closes the properties panel
+    Properties,
+}
+
+#[derive(Component)]
+pub struct ToolButton(pub ToolAction);
+
+/// Marks the cells a rectangle or hex range would paint
+#[derive(Component)]
+pub struct ShapePreview;
+
+/// Background of the panel with the level text and metadata
+#[derive(Component)]
+pub struct PropertiesPanel;
+
+/// Row of the properties panel, clicking it focuses the field.
+/// The row without a field switches between a locale key and inline text.
+#[derive(Clone, Copy, PartialEq, Eq, Component)]
+pub struct PropertyButton(pub Option<Field>);
+
+/// Shows the level text below the board
+#[derive(Component)]
+pub struct TextPreview;
@@ -0,0 +1,25 @@
+/// Size of a new canvas. A zoom of 1 fits this size into the window.
+pub const CANVAS_WIDTH: usize = 33;
+pub const CANVAS_HEIGHT: usize = 18;
+/// Bounds for resizing the canvas
+pub const CANVAS_MAX_WIDTH: usize = 99;
+pub const CANVAS_MAX_HEIGHT: usize = 99;
+
+pub const ZOOM_MIN: f32 = 0.5;
+pub const ZOOM_MAX: f32 = 8.;
+/// Zoom factor per scrolled line
+pub const ZOOM_STEP: f32 = 1.1;
+/// Panning with the arrow keys in pixels per second
+pub const PAN_SPEED: f32 = 800.;
+
+/// Layout of the toolbar for a 1920×1080 window
+pub const TOOLBAR_HEIGHT: f32 = 100.;
+pub const TOOLBAR_SCALE: f32 = 0.4;
+pub const TOOLBAR_SPACING: f32 = 90.;
+pub const TOOLBAR_GROUP_GAP: f32 = 60.;
+
+/// Layout of the properties panel for a 1920×1080 window
+pub const PANEL_WIDTH: f32 = 640.;
+pub const PANEL_ROW_HEIGHT: f32 = 50.;
+pub const PANEL_MARGIN: f32 = 20.;
+pub const PANEL_TEXT_SCALE: f32 = 0.45;
@@ -1,19 +1,58 @@
 use super::{
     board::Board,
-    components::{CellUpdateEvent, EditorCell, EmptyCell, NumberCell, UnsetCell},
+    components::{
+        EditorCell, EmptyCell, NumberCell, PropertiesPanel, PropertyButton, ToolAction, ToolButton,
+        Toolbar, UnsetCell,
+    },
+    constants::{
+        CANVAS_HEIGHT, CANVAS_WIDTH, PANEL_MARGIN, PANEL_ROW_HEIGHT, PANEL_TEXT_SCALE, PANEL_WIDTH,
+        TOOLBAR_GROUP_GAP, TOOLBAR_HEIGHT, TOOLBAR_SCALE, TOOLBAR_SPACING,
+    },
+    resources::{EditorTools, EditorView, Field, Paint, Symmetry, Tool},
 };
 use crate::{
     components::Cell,
-    constants::{RADIUS, Z_INDEX_CELL_BACK},
-    enums::{CellType, HintType},
-    functions::{calc_translation, make_cell_interactable, spawn_cell, spawn_cell_text},
+    constants::{RADIUS, Z_INDEX_CELL_BACK, Z_INDEX_UI},
+    enums::CellType,
+    functions::{
+        calc_dimensions, calc_translation, make_cell_interactable, rescale_board, spawn_cell,
+        spawn_cell_text, text_translation,
+    },
     resources::{CellMeshes, GameColors, TextSettings},
 };
 use bevy::{
     hierarchy::BuildChildren,
-    prelude::{Commands, Entity, EventWriter, Handle, Query, Transform, Visibility},
-    sprite::ColorMaterial,
+    math::{Vec2, Vec3},
+    prelude::{
+        default, shape::Quad, Assets, Color, Commands, Entity, Handle, Mesh, Query, Transform,
+        Visibility,
+    },
+    sprite::{ColorMaterial, ColorMesh2dBundle},
+    text::{HorizontalAlign, Text, Text2dBundle, TextAlignment, VerticalAlign},
 };
+use interactable::{components::Interactable, shapes::Shape};
+use std::collections::HashSet;
+
+/// Places the root of the board according to zoom and panning.
+/// A zoom of 1 fits a canvas of the default size into the window, so cells keep their size
+/// when the canvas grows. The initial cells stay in place when cells are added at the top or left.
+pub fn apply_view(
+    view: &EditorView,
+    board: &Board,
+    wd_width: f32,
+    wd_height: f32,
+    root: &mut Transform,
+) {
+    rescale_board(CANVAS_WIDTH, CANVAS_HEIGHT, 4, wd_width, wd_height, root);
+    let s = root.scale.x * view.zoom;
+    root.scale = Vec3::new(s, s, 1.0);
+
+    let (w, h) = calc_dimensions(board.width, board.height);
+    let (ox, oy) = calc_translation(board.origin.0, board.origin.1, w, h);
+    let (cw, ch) = calc_dimensions(CANVAS_WIDTH, CANVAS_HEIGHT);
+    root.translation.x = view.pan.x - s * (ox + cw);
+    root.translation.y = view.pan.y - s * (oy - ch);
+}
 
 pub fn row_empty(row: &Vec<(Option<CellType>, bool)>) -> bool {
     for (entry, _) in row {
@@ -81,90 +120,444 @@ pub fn spawn_cell_common(
     text_entity
 }
 
-pub fn unset_cell(
+/// Updates the components and colors of a cell to match its entry on the board
+pub fn update_cell(
     commands: &mut Commands,
     entity: Entity,
     (cell, ec): (&mut Cell, &mut EditorCell),
+    (cell_type, hidden): (Option<CellType>, bool),
     color_query: &mut Query<&mut Handle<ColorMaterial>>,
     game_colors: &GameColors,
-    board: &mut Board,
-    ev_cell_update: &mut EventWriter<CellUpdateEvent>,
 ) {
-    ec.cell_type = None;
-    ec.hidden = false;
-    commands.entity(entity).insert(UnsetCell);
-    commands
-        .entity(ec.text_entity)
-        .insert(Visibility { is_visible: false });
-    // TODO: Does it really make sense to click here?
-    cell.click(
-        commands,
-        Some(game_colors.alpha0.clone()),
-        game_colors.alpha0.clone(),
-        game_colors.alpha1.clone(),
-        color_query,
-    );
-    board.cells[cell.y as usize][cell.x as usize].0 = None;
-    board.cells[cell.y as usize][cell.x as usize].1 = false;
-    ev_cell_update.send(CellUpdateEvent);
+    ec.cell_type = cell_type;
+    ec.hidden = hidden;
+    let mut entity_commands = commands.entity(entity);
+    entity_commands
+        .remove::<UnsetCell>()
+        .remove::<EmptyCell>()
+        .remove::<NumberCell>();
+    match cell_type {
+        None => entity_commands.insert(UnsetCell),
+        Some(CellType::EmptyCell) => entity_commands.insert(EmptyCell),
+        Some(CellType::NumberCell(_)) => entity_commands.insert(NumberCell),
+    };
+    commands.entity(ec.text_entity).insert(Visibility {
+        is_visible: matches!(cell_type, Some(CellType::NumberCell(_))),
+    });
+
+    let (background, light, dark) = match (cell_type, hidden) {
+        (None, _) => (
+            game_colors.alpha0.clone(),
+            game_colors.alpha0.clone(),
+            game_colors.alpha1.clone(),
+        ),
+        (Some(_), true) => (
+            game_colors.white.clone(),
+            game_colors.yellow_light.clone(),
+            game_colors.yellow_medium.clone(),
+        ),
+        (Some(CellType::EmptyCell), false) => (
+            game_colors.white.clone(),
+            game_colors.blue_light.clone(),
+            game_colors.blue_medium.clone(),
+        ),
+        (Some(CellType::NumberCell(_)), false) => (
+            game_colors.white.clone(),
+            game_colors.gray_light.clone(),
+            game_colors.gray_medium.clone(),
+        ),
+    };
+    cell.click(commands, Some(background), light, dark, color_query);
+}
+
+/// Axial coordinates of a cell, which make distances and lines easy to compute.
+/// See <https://www.redblobgames.com/grids/hexagons/>
+fn to_axial((x, y): (usize, usize)) -> (i32, i32) {
+    let (x, y) = (x as i32, y as i32);
+    (x, y - (x + 1).div_euclid(2))
+}
+
+fn from_axial(q: i32, r: i32) -> (i32, i32) {
+    (q, r + (q + 1).div_euclid(2))
+}
+
+/// Offset from a to b in axial coordinates. Unlike the difference of the positions,
+/// it describes the same shape in columns of both parities.
+pub fn axial_offset(a: (usize, usize), b: (usize, usize)) -> (i32, i32) {
+    let (aq, ar) = to_axial(a);
+    let (bq, br) = to_axial(b);
+    (bq - aq, br - ar)
+}
+
+/// The cell at an axial offset from a, if it is on the canvas
+pub fn apply_axial_offset(
+    a: (usize, usize),
+    (dq, dr): (i32, i32),
+    width: usize,
+    height: usize,
+) -> Option<(usize, usize)> {
+    let (aq, ar) = to_axial(a);
+    let (x, y) = from_axial(aq + dq, ar + dr);
+    if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+        None
+    } else {
+        Some((x as usize, y as usize))
+    }
+}
+
+pub fn hex_distance(a: (usize, usize), b: (usize, usize)) -> i32 {
+    let (aq, ar) = to_axial(a);
+    let (bq, br) = to_axial(b);
+    let (dq, dr) = (aq - bq, ar - br);
+    (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+}
+
+/// All cells on the straight line from a to b, so that fast mouse movements leave no gaps
+pub fn hex_line(a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize)> {
+    let n = hex_distance(a, b);
+    if n == 0 {
+        return vec![a];
+    }
+    let (aq, ar) = to_axial(a);
+    let (bq, br) = to_axial(b);
+    // Nudged a bit, so that points exactly between two cells are rounded consistently
+    let (aq, ar) = (aq as f32 + 1e-4, ar as f32 + 1e-4);
+    (0..=n)
+        .map(|i| {
+            let t = i as f32 / n as f32;
+            let q = aq + (bq as f32 - aq) * t;
+            let r = ar + (br as f32 - ar) * t;
+            let s = -q - r;
+            let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+            let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+            if dq > dr && dq > ds {
+                rq = -rr - rs;
+            } else if dr > ds {
+                rr = -rq - rs;
+            }
+            let (x, y) = from_axial(rq as i32, rr as i32);
+            (x as usize, y as usize)
+        })
+        .collect()
+}
+
+/// Cells covered by a drag with the rectangle, hex range or selection tool
+pub fn shape_positions(
+    tool: Tool,
+    start: (usize, usize),
+    end: (usize, usize),
+    width: usize,
+    height: usize,
+) -> Vec<(usize, usize)> {
+    let mut positions = Vec::new();
+    match tool {
+        Tool::Rect | Tool::Select => {
+            for y in start.1.min(end.1)..=start.1.max(end.1) {
+                for x in start.0.min(end.0)..=start.0.max(end.0) {
+                    positions.push((x, y));
+                }
+            }
+        }
+        Tool::HexRange => {
+            let range = hex_distance(start, end);
+            for y in 0..height {
+                for x in 0..width {
+                    if hex_distance(start, (x, y)) <= range {
+                        positions.push((x, y));
+                    }
+                }
+            }
+        }
+        Tool::Brush | Tool::Fill | Tool::Anchor => positions.push(end),
+    }
+    positions
+}
+
+/// Adds the mirror images of the given cells.
+/// Images that don't lie on the grid are left out, e.g. mirroring at the vertical axis
+/// only works for an odd width because the odd columns are offset.
+pub fn with_symmetry(
+    positions: &[(usize, usize)],
+    width: usize,
+    height: usize,
+    symmetry: Symmetry,
+) -> Vec<(usize, usize)> {
+    let mirror_x = |x: usize| match width % 2 {
+        1 => Some(width - 1 - x),
+        _ => None,
+    };
+    // Rows of odd columns are shifted by half a cell, so their mirror image is one row lower
+    let mirror_y = |x: usize, y: usize| {
+        let y = height + x % 2 - 1 - y;
+        match y < height {
+            true => Some(y),
+            false => None,
+        }
+    };
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    for &(x, y) in positions {
+        let horizontal = mirror_x(x).map(|mx| (mx, y));
+        let vertical = mirror_y(x, y).map(|my| (x, my));
+        let rotational = mirror_x(x).and_then(|mx| mirror_y(mx, y).map(|my| (mx, my)));
+        let images = match symmetry {
+            Symmetry::None => vec![],
+            Symmetry::Horizontal => vec![horizontal],
+            Symmetry::Vertical => vec![vertical],
+            Symmetry::Both => vec![horizontal, vertical, rotational],
+            Symmetry::Rotational => vec![rotational],
+        };
+        for pos in std::iter::once((x, y)).chain(images.into_iter().flatten()) {
+            if seen.insert(pos) {
+                result.push(pos);
+            }
+        }
+    }
+    result
 }
 
-pub fn set_empty_cell(
+/// Places the toolbar at the top of the window, its layout is made for 1920×1080
+pub fn toolbar_transform(wd_width: f32, wd_height: f32) -> Transform {
+    let s = (wd_width / 1920.).min(wd_height / 1080.);
+    Transform::from_xyz(0., (wd_height - TOOLBAR_HEIGHT * s) / 2., Z_INDEX_UI)
+        .with_scale(Vec3::new(s, s, 1.0))
+}
+
+/// Spawns the toolbar with a button for each paint, tool and symmetry.
+/// The labels are the hotkeys, except for the symmetry buttons which cycle with M.
+pub fn spawn_toolbar(
     commands: &mut Commands,
-    entity: Entity,
-    (cell, ec): (&mut Cell, &mut EditorCell),
-    color_query: &mut Query<&mut Handle<ColorMaterial>>,
-    game_colors: &GameColors,
-    board: &mut Board,
-    ev_cell_update: &mut EventWriter<CellUpdateEvent>,
+    meshes: &mut Assets<Mesh>,
+    (cell_meshes, game_colors, text_settings): (&CellMeshes, &GameColors, &TextSettings),
+    tools: &EditorTools,
+    transform: Transform,
 ) {
-    ec.cell_type = Some(CellType::EmptyCell);
+    let groups: [&[(ToolAction, &str)]; 4] = [
+        &[
+            (ToolAction::Paint(Paint::Blue), "1"),
+            (ToolAction::Paint(Paint::Gray), "2"),
+            (ToolAction::Paint(Paint::Special), "3"),
+            (ToolAction::Paint(Paint::Hide), "4"),
+            (ToolAction::Paint(Paint::Reveal), "5"),
+            (ToolAction::Paint(Paint::Erase), "E"),
+        ],
+        &[
+            (ToolAction::Tool(Tool::Brush), "B"),
+            (ToolAction::Tool(Tool::Fill), "F"),
+            (ToolAction::Tool(Tool::Rect), "R"),
+            (ToolAction::Tool(Tool::HexRange), "O"),
+            (ToolAction::Tool(Tool::Select), "S"),
+            (ToolAction::Tool(Tool::Anchor), "T"),
+        ],
+        &[
+            (ToolAction::Symmetry(Symmetry::None), ""),
+            (ToolAction::Symmetry(Symmetry::Horizontal), "|"),
+            (ToolAction::Symmetry(Symmetry::Vertical), "-"),
+            (ToolAction::Symmetry(Symmetry::Both), "+"),
+            (ToolAction::Symmetry(Symmetry::Rotational), "x"),
+        ],
+        &[(ToolAction::Properties, "P")],
+    ];
+    let count: usize = groups.iter().map(|group| group.len()).sum();
+    let width = (count - 1) as f32 * TOOLBAR_SPACING
+        + (groups.len() - 1) as f32 * TOOLBAR_GROUP_GAP
+        + TOOLBAR_HEIGHT;
+
+    let mut buttons = Vec::new();
+    let mut x = (TOOLBAR_HEIGHT - width) / 2.;
+    for group in groups {
+        for (action, label) in group {
+            let mut big_transform = Transform::from_xyz(x, 0., 1.).with_scale(Vec3::new(
+                TOOLBAR_SCALE,
+                TOOLBAR_SCALE,
+                1.,
+            ));
+            big_transform.rotate_z(f32::to_radians(90.0));
+            let (light, dark) = match action {
+                ToolAction::Paint(Paint::Blue) | ToolAction::Paint(Paint::Reveal) => (
+                    game_colors.blue_light.clone(),
+                    game_colors.blue_medium.clone(),
+                ),
+                ToolAction::Paint(Paint::Hide) => (
+                    game_colors.yellow_light.clone(),
+                    game_colors.yellow_medium.clone(),
+                ),
+                ToolAction::Paint(Paint::Erase) => {
+                    (game_colors.alpha1.clone(), game_colors.alpha2.clone())
+                }
+                _ => (
+                    game_colors.gray_light.clone(),
+                    game_colors.gray_medium.clone(),
+                ),
+            };
+            let background = match tools.is_selected(*action) {
+                true => game_colors.white.clone(),
+                false => game_colors.alpha0.clone(),
+            };
+            let button = commands.spawn().id();
+            spawn_cell(
+                commands,
+                button,
+                (
+                    cell_meshes.std_hexagon_back.clone(),
+                    cell_meshes.std_hexagon_outer.clone(),
+                    cell_meshes.std_hexagon_inner.clone(),
+                ),
+                (background, dark, light),
+                big_transform,
+            );
+            make_cell_interactable(commands, button, RADIUS);
+            let text_entity = spawn_cell_text(
+                commands,
+                label,
+                text_settings.style_cell.clone(),
+                text_settings.alignment,
+            );
+            commands
+                .entity(button)
+                .insert(ToolButton(*action))
+                .add_child(text_entity);
+            buttons.push(button);
+            x += TOOLBAR_SPACING;
+        }
+        x += TOOLBAR_GROUP_GAP;
+    }
+
     commands
-        .entity(entity)
-        .remove::<UnsetCell>()
-        .insert(EmptyCell);
-    // TODO: Does it really make sense to click here?
-    cell.click(
-        commands,
-        Some(game_colors.white.clone()),
-        game_colors.blue_light.clone(),
-        game_colors.blue_medium.clone(),
-        color_query,
-    );
-    board.cells[cell.y as usize][cell.x as usize].0 = Some(CellType::EmptyCell);
-    ev_cell_update.send(CellUpdateEvent);
+        .spawn_bundle(ColorMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(Quad::new(Vec2::new(width, TOOLBAR_HEIGHT))))
+                .into(),
+            material: game_colors.alpha2.clone(),
+            transform,
+            ..default()
+        })
+        .insert(Interactable {
+            shape: Shape::Quad(interactable::shapes::Quad {
+                width,
+                height: TOOLBAR_HEIGHT,
+            }),
+            ..default()
+        })
+        .insert(Toolbar)
+        .push_children(&buttons);
 }
 
-pub fn set_number_cell(
+/// Default position of the level text in the coordinates of the root. Like in the level,
+/// it is centered below the set cells, which are all that remains of the canvas when saving.
+fn text_origin(board: &Board) -> Vec2 {
+    let (w, h) = calc_dimensions(board.width, board.height);
+    let ((x0, _), (x1, y1)) = board
+        .bounds()
+        .unwrap_or(((0, 0), (board.width - 1, board.height - 1)));
+    let (tx0, _) = calc_translation(x0 as i32, 0, w, h);
+    let (tx1, _) = calc_translation(x1 as i32, 0, w, h);
+    let (_, ty) = calc_translation(0, y1 as i32, w, h);
+    // The level places the text relative to the center of the last row
+    Vec2::new((tx0 + tx1) / 2., ty)
+}
+
+/// Position of the level text in the coordinates of the root
+pub fn text_position(board: &Board) -> Vec2 {
+    let (tx, ty) = text_translation(board.text_anchor, 0.);
+    text_origin(board) + Vec2::new(tx, ty)
+}
+
+/// The anchor that places the level text closest to a position in the coordinates of the root
+pub fn anchor_at(board: &Board, pos: Vec2) -> (i32, i32) {
+    let (_, ty) = text_translation((0, 0), 0.);
+    let d = pos - text_origin(board) - Vec2::new(0., ty);
+    (
+        (d.x / (RADIUS * 1.56)).round() as i32,
+        (-d.y / (RADIUS * 0.9)).round() as i32,
+    )
+}
+
+/// The properties panel is placed at the top right, below the toolbar
+pub fn properties_transform(wd_width: f32, wd_height: f32, rows: usize) -> Transform {
+    let s = (wd_width / 1920.).min(wd_height / 1080.);
+    let height = rows as f32 * PANEL_ROW_HEIGHT + PANEL_MARGIN;
+    Transform::from_xyz(
+        (wd_width - (PANEL_WIDTH + 2. * PANEL_MARGIN) * s) / 2.,
+        (wd_height - (2. * TOOLBAR_HEIGHT + height + 2. * PANEL_MARGIN) * s) / 2.,
+        Z_INDEX_UI,
+    )
+    .with_scale(Vec3::new(s, s, 1.0))
+}
+
+/// Spawns the properties panel with one line per row. Rows with a button can be clicked,
+/// the row of the focused field is highlighted.
+pub fn spawn_properties_panel(
     commands: &mut Commands,
-    entity: Entity,
-    (cell, ec): (&mut Cell, &mut EditorCell),
-    color_query: &mut Query<&mut Handle<ColorMaterial>>,
-    game_colors: &GameColors,
-    board: &mut Board,
-    ev_cell_update: &mut EventWriter<CellUpdateEvent>,
+    meshes: &mut Assets<Mesh>,
+    (game_colors, text_settings): (&GameColors, &TextSettings),
+    rows: &[(Option<PropertyButton>, String, Color)],
+    focus: Option<Field>,
+    transform: Transform,
 ) {
-    ec.cell_type = Some(CellType::NumberCell(HintType::None));
-    let count = 0;
-    commands
-        .entity(entity)
-        .remove::<UnsetCell>()
-        .insert(NumberCell {
-            count,
-            label: ec.text_entity,
-            special_hint: false,
-        });
+    let height = rows.len() as f32 * PANEL_ROW_HEIGHT + PANEL_MARGIN;
+    let row_mesh = meshes.add(Mesh::from(Quad::new(Vec2::new(
+        PANEL_WIDTH - PANEL_MARGIN,
+        PANEL_ROW_HEIGHT,
+    ))));
+    let mut children = Vec::new();
+    for (i, (button, text, color)) in rows.iter().enumerate() {
+        let y = height / 2. - PANEL_MARGIN / 2. - (i as f32 + 0.5) * PANEL_ROW_HEIGHT;
+        let mut style = text_settings.style_cell.clone();
+        style.color = *color;
+        let text_entity = commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::from_section(text, style).with_alignment(TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Left,
+                }),
+                transform: Transform::from_xyz(-(PANEL_WIDTH - 2. * PANEL_MARGIN) / 2., y, 2.)
+                    .with_scale(Vec3::new(PANEL_TEXT_SCALE, PANEL_TEXT_SCALE, 1.)),
+                ..default()
+            })
+            .id();
+        children.push(text_entity);
+        if let Some(button) = button {
+            let material = match focus.is_some() && button.0 == focus {
+                true => game_colors.alpha2.clone(),
+                false => game_colors.alpha1.clone(),
+            };
+            let row = commands
+                .spawn_bundle(ColorMesh2dBundle {
+                    mesh: row_mesh.clone().into(),
+                    material,
+                    transform: Transform::from_xyz(0., y, 1.).with_scale(Vec3::new(1., 0.9, 1.)),
+                    ..default()
+                })
+                .insert(Interactable {
+                    shape: Shape::Quad(interactable::shapes::Quad {
+                        width: PANEL_WIDTH - PANEL_MARGIN,
+                        height: PANEL_ROW_HEIGHT,
+                    }),
+                    ..default()
+                })
+                .insert(*button)
+                .id();
+            children.push(row);
+        }
+    }
+
     commands
-        .entity(ec.text_entity)
-        .insert(Visibility { is_visible: true });
-    // TODO: Does it really make sense to click here?
-    cell.click(
-        commands,
-        Some(game_colors.white.clone()),
-        game_colors.gray_light.clone(),
-        game_colors.gray_medium.clone(),
-        color_query,
-    );
-    board.cells[cell.y as usize][cell.x as usize].0 = Some(CellType::NumberCell(HintType::None));
-    ev_cell_update.send(CellUpdateEvent);
+        .spawn_bundle(ColorMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(Quad::new(Vec2::new(PANEL_WIDTH, height))))
+                .into(),
+            material: game_colors.alpha2.clone(),
+            transform,
+            ..default()
+        })
+        .insert(Interactable {
+            shape: Shape::Quad(interactable::shapes::Quad {
+                width: PANEL_WIDTH,
+                height,
+            }),
+            ..default()
+        })
+        .insert(PropertiesPanel)
+        .push_children(&children);
 }
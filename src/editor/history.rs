@@ -0,0 +1,92 @@
+use crate::{components::ColumnHint, enums::CellType, structs::BoardConfig};
+use std::mem;
+
+/// A single reversible change of the editor board
+#[derive(Clone)]
+pub enum Edit {
+    Cell {
+        x: usize,
+        y: usize,
+        before: (Option<CellType>, bool),
+        after: (Option<CellType>, bool),
+    },
+    AddHint(ColumnHint),
+    RemoveHint(ColumnHint),
+    /// Changes of the canvas size store the whole board and
+    /// by how much the cells moved
+    Canvas {
+        before: Box<BoardConfig>,
+        after: Box<BoardConfig>,
+        offset: (i32, i32),
+    },
+}
+
+impl Edit {
+    /// The edit that reverts this one
+    pub fn inverse(&self) -> Self {
+        match self {
+            Edit::Cell {
+                x,
+                y,
+                before,
+                after,
+            } => Edit::Cell {
+                x: *x,
+                y: *y,
+                before: *after,
+                after: *before,
+            },
+            Edit::AddHint(hint) => Edit::RemoveHint(*hint),
+            Edit::RemoveHint(hint) => Edit::AddHint(*hint),
+            Edit::Canvas {
+                before,
+                after,
+                offset,
+            } => Edit::Canvas {
+                before: after.clone(),
+                after: before.clone(),
+                offset: (-offset.0, -offset.1),
+            },
+        }
+    }
+}
+
+/// Undo and redo stacks. Edits are collected until they are committed as one batch,
+/// so that e.g. toggling all cells is undone in one step.
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Vec<Edit>>,
+    redo: Vec<Vec<Edit>>,
+    pending: Vec<Edit>,
+}
+
+impl History {
+    pub fn record(&mut self, edit: Edit) {
+        self.pending.push(edit);
+    }
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+    /// Closes the current batch
+    pub fn commit(&mut self) {
+        if self.has_pending() {
+            self.undo.push(mem::take(&mut self.pending));
+            self.redo.clear();
+        }
+    }
+    /// Returns the edits that revert the last batch, in the order they must be applied
+    pub fn undo(&mut self) -> Option<Vec<Edit>> {
+        self.commit();
+        let batch = self.undo.pop()?;
+        let inverse = batch.iter().rev().map(Edit::inverse).collect();
+        self.redo.push(batch);
+        Some(inverse)
+    }
+    /// Returns the edits of the last undone batch
+    pub fn redo(&mut self) -> Option<Vec<Edit>> {
+        self.commit();
+        let batch = self.redo.pop()?;
+        self.undo.push(batch.clone());
+        Some(batch)
+    }
+}
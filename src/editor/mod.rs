@@ -1,10 +1,18 @@
 mod board;
 mod components;
+mod constants;
 mod functions;
+mod history;
+mod resources;
 mod setup;
 mod systems;
 
-use self::{components::CellUpdateEvent, setup::setup, systems::*};
+use self::{
+    components::{BoardRebuildEvent, CellUpdateEvent, PropertiesPanel, Toolbar},
+    resources::Clipboard,
+    setup::setup,
+    systems::*,
+};
 use crate::{cleanup_system, components::RootComponent, states::AppState};
 use bevy::prelude::{App, ParallelSystemDescriptorCoercion, SystemSet};
 use interactable::InteractLabel;
@@ -21,25 +29,84 @@ pub fn prepare_editor(app: &mut App) {
                         .before(mouse_enter_cell)
                         .after(InteractLabel::Interact),
                 )
-                .with_system(mouse_click_unset_cell.after(mouse_enter_cell))
                 .with_system(
-                    mouse_click_empty_cell
-                        .before(mouse_click_unset_cell)
-                        .after(mouse_enter_cell),
+                    text_input_system
+                        .before(tool_hotkey_system)
+                        .before(clipboard_system)
+                        .before(hotkey_system)
+                        .before(canvas_resize_system)
+                        .before(view_system),
                 )
+                .with_system(view_system.after(InteractLabel::Interact))
+                .with_system(paint_system.after(InteractLabel::Interact))
+                .with_system(anchor_system.after(InteractLabel::Interact))
+                .with_system(properties_click_system.after(InteractLabel::Interact))
                 .with_system(
-                    mouse_click_number_cell
-                        .before(mouse_click_unset_cell)
-                        .after(mouse_enter_cell),
+                    properties_panel_system
+                        .after(properties_click_system)
+                        .after(text_input_system)
+                        .after(tool_hotkey_system)
+                        .after(toolbar_button_system),
                 )
                 .with_system(
-                    cell_update_system
-                        .after(mouse_click_empty_cell)
-                        .after(mouse_click_number_cell),
+                    shape_preview_system
+                        .after(paint_system)
+                        .after(clipboard_system),
                 )
+                .with_system(toolbar_button_system.after(InteractLabel::Interact))
+                .with_system(tool_hotkey_system)
+                .with_system(
+                    toolbar_update_system
+                        .after(toolbar_button_system)
+                        .after(tool_hotkey_system)
+                        .after(paint_system),
+                )
+                .with_system(toolbar_resize_system)
+                .with_system(clipboard_system.before(hotkey_system))
                 .with_system(hotkey_system)
-                .with_system(window_resize_system),
+                .with_system(reveal_task_system.before(board_sync_system))
+                .with_system(canvas_resize_system)
+                .with_system(
+                    board_sync_system
+                        .after(paint_system)
+                        .after(clipboard_system)
+                        .after(hotkey_system)
+                        .after(canvas_resize_system),
+                )
+                .with_system(cell_update_system.after(board_sync_system))
+                .with_system(
+                    text_preview_system
+                        .after(anchor_system)
+                        .after(text_input_system)
+                        .after(properties_click_system)
+                        .after(board_sync_system),
+                )
+                .with_system(
+                    commit_edits_system
+                        .after(paint_system)
+                        .after(clipboard_system)
+                        .after(hotkey_system)
+                        .after(canvas_resize_system),
+                )
+                .with_system(
+                    board_rebuild_system
+                        .after(reveal_task_system)
+                        .after(paint_system)
+                        .after(clipboard_system)
+                        .after(hotkey_system)
+                        .after(canvas_resize_system)
+                        .after(cell_update_system)
+                        .after(shape_preview_system)
+                        .after(text_preview_system),
+                ),
+        )
+        .add_system_set(
+            SystemSet::on_exit(STATE)
+                .with_system(cleanup_system::<RootComponent>)
+                .with_system(cleanup_system::<Toolbar>)
+                .with_system(cleanup_system::<PropertiesPanel>),
         )
-        .add_system_set(SystemSet::on_exit(STATE).with_system(cleanup_system::<RootComponent>))
-        .add_event::<CellUpdateEvent>();
+        .init_resource::<Clipboard>()
+        .add_event::<CellUpdateEvent>()
+        .add_event::<BoardRebuildEvent>();
 }
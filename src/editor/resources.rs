@@ -0,0 +1,189 @@
+use super::components::ToolAction;
+use crate::{
+    enums::{CellType, HintDirection, HintType},
+    structs::{BoardConfig, CellGrid, GridCell},
+};
+use bevy::{math::Vec2, tasks::Task};
+
+/// Zoom and panning of the editor canvas
+pub struct EditorView {
+    pub zoom: f32,
+    pub pan: Vec2,
+    /// Last cursor position while dragging with the middle mouse button
+    pub drag: Option<Vec2>,
+}
+
+impl Default for EditorView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.,
+            pan: Vec2::ZERO,
+            drag: None,
+        }
+    }
+}
+
+/// How the cells to paint are chosen
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// Paints every cell the cursor is dragged over
+    #[default]
+    Brush,
+    /// Paints the connected region of equal cells
+    Fill,
+    /// Paints all cells between the start and the end of a drag
+    Rect,
+    /// Paints all cells within the distance of the start to the end of a drag
+    HexRange,
+    /// Selects the cells between the start and the end of a drag for copying
+    Select,
+    /// Places the level text at the cursor
+    Anchor,
+}
+
+/// What is painted on a cell
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum Paint {
+    #[default]
+    Blue,
+    Gray,
+    /// Gray cell with a special hint, i.e. {n} or -n-
+    Special,
+    Hide,
+    Reveal,
+    Erase,
+}
+
+impl Paint {
+    /// The entry of a cell after painting it. Hiding and revealing keep the cell type,
+    /// changing the cell type keeps the hidden state.
+    pub fn apply(self, (cell_type, hidden): (Option<CellType>, bool)) -> (Option<CellType>, bool) {
+        match self {
+            Paint::Blue => (Some(CellType::EmptyCell), hidden),
+            Paint::Gray => (Some(CellType::NumberCell(HintType::None)), hidden),
+            Paint::Special => match cell_type {
+                // Keep special hints that were already resolved
+                Some(CellType::NumberCell(ht)) if ht != HintType::None => (cell_type, hidden),
+                _ => (Some(CellType::NumberCell(HintType::Some)), hidden),
+            },
+            Paint::Hide => (cell_type, cell_type.is_some()),
+            Paint::Reveal => (cell_type, false),
+            Paint::Erase => (None, false),
+        }
+    }
+}
+
+/// Painting a cell also paints its mirror images
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Mirrored at the vertical axis
+    Horizontal,
+    /// Mirrored at the horizontal axis
+    Vertical,
+    /// Mirrored at both axes
+    Both,
+    /// Rotated by 180°
+    Rotational,
+}
+
+impl Symmetry {
+    pub fn next(self) -> Self {
+        match self {
+            Symmetry::None => Symmetry::Horizontal,
+            Symmetry::Horizontal => Symmetry::Vertical,
+            Symmetry::Vertical => Symmetry::Both,
+            Symmetry::Both => Symmetry::Rotational,
+            Symmetry::Rotational => Symmetry::None,
+        }
+    }
+}
+
+/// A drag with the left (paint) or right (erase) mouse button
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Stroke {
+    pub paint: Paint,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+/// Selected tools of the toolbar
+#[derive(Default)]
+pub struct EditorTools {
+    pub tool: Tool,
+    pub paint: Paint,
+    pub symmetry: Symmetry,
+    pub stroke: Option<Stroke>,
+    /// Corners of the selected rectangle
+    pub selection: Option<((usize, usize), (usize, usize))>,
+    /// Whether the clipboard follows the cursor to be pasted
+    pub pasting: bool,
+    /// Where the clipboard would be pasted
+    pub paste_at: Option<(usize, usize)>,
+    /// Whether the panel with the level text and metadata is shown
+    pub properties: bool,
+    /// Field of the properties panel that receives the typed text
+    pub focus: Option<Field>,
+}
+
+impl EditorTools {
+    pub fn is_selected(&self, action: ToolAction) -> bool {
+        match action {
+            ToolAction::Paint(paint) => self.paint == paint,
+            ToolAction::Tool(tool) => self.tool == tool,
+            ToolAction::Symmetry(symmetry) => self.symmetry == symmetry,
+            ToolAction::Properties => self.properties,
+        }
+    }
+
+    pub fn select(&mut self, action: ToolAction) {
+        match action {
+            ToolAction::Paint(paint) => self.paint = paint,
+            ToolAction::Tool(tool) => self.tool = tool,
+            ToolAction::Symmetry(symmetry) => self.symmetry = symmetry,
+            ToolAction::Properties => {
+                self.properties = !self.properties;
+                self.focus = None;
+            }
+        }
+    }
+}
+
+/// Editable fields of the properties panel
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Text,
+    Title,
+    Author,
+    Difficulty,
+}
+
+impl Field {
+    /// The field that Tab moves the focus to
+    pub fn next(self) -> Self {
+        match self {
+            Field::Text => Field::Title,
+            Field::Title => Field::Author,
+            Field::Author => Field::Difficulty,
+            Field::Difficulty => Field::Text,
+        }
+    }
+}
+
+/// Cells copied in the editor. It is not reset on entering the editor,
+/// so it can be pasted into the next level as well.
+#[derive(Default)]
+pub struct Clipboard {
+    /// Set cells by their axial offset to the top left corner of the selection
+    pub cells: Vec<((i32, i32), GridCell)>,
+    /// Column hints by the axial offset of their cell
+    pub hints: Vec<((i32, i32), HintDirection, HintType)>,
+}
+
+/// The search for the cells to reveal. It runs in the background, on large boards it takes a while.
+#[derive(Default)]
+pub struct RevealTask {
+    /// The task and the cells of the board it started from
+    pub running: Option<(Task<BoardConfig>, CellGrid)>,
+}
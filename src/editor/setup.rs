@@ -1,12 +1,18 @@
-use super::{board::Board, components::CellUpdateEvent};
+use super::{
+    board::Board,
+    components::CellUpdateEvent,
+    constants::{CANVAS_HEIGHT, CANVAS_WIDTH},
+    functions::{spawn_toolbar, toolbar_transform},
+    resources::{EditorTools, EditorView, RevealTask},
+};
 use crate::{
     functions::rescale_board,
     parser,
     resources::{CellMeshes, GameColors, LoadState, TextSettings},
-    structs::BoardConfig,
+    structs::{BoardConfig, LevelMeta},
 };
 use bevy::{
-    prelude::{Commands, EventWriter, Res, ResMut, Transform},
+    prelude::{Assets, Commands, EventWriter, Mesh, Res, ResMut, Transform},
     window::Windows,
 };
 
@@ -19,6 +25,7 @@ pub fn setup(
         Res<TextSettings>,
     ),
     load_state: ResMut<LoadState>,
+    mut meshes: ResMut<Assets<Mesh>>,
     mut ev_cell_update: EventWriter<CellUpdateEvent>,
 ) {
     // TODO: Temporary fix
@@ -26,21 +33,22 @@ pub fn setup(
     let config = if enabled && load_state.filename.is_some() {
         parser::board_from_file(load_state.filename.as_ref().unwrap())
     } else {
-        // TODO: Think about these hardcoded values
         BoardConfig {
-            width: 33,
-            height: 18,
-            cells: vec![vec![(None, false); 33]; 18],
+            width: CANVAS_WIDTH,
+            height: CANVAS_HEIGHT,
+            cells: vec![vec![(None, false); CANVAS_WIDTH]; CANVAS_HEIGHT],
             hints: Vec::new(),
             text: None,
+            meta: LevelMeta::default(),
         }
     };
 
+    // The default view fits a canvas of the default size into the window
     let mut root_transform = Transform::identity();
     for wnd in wnds.iter() {
         rescale_board(
-            config.width,
-            config.height,
+            CANVAS_WIDTH,
+            CANVAS_HEIGHT,
             4,
             wnd.width(),
             wnd.height(),
@@ -56,16 +64,22 @@ pub fn setup(
         &text_settings,
     );
 
-    // for wnd in wnds.iter() {
-    //     rescale_board(
-    //         config.width,
-    //         config.height,
-    //         4,
-    //         wnd.width(),
-    //         wnd.height(),
-    //         &mut camera_query,
-    //     );
-    // }
+    let tools = EditorTools::default();
+    let mut toolbar_tf = Transform::identity();
+    for wnd in wnds.iter() {
+        toolbar_tf = toolbar_transform(wnd.width(), wnd.height());
+    }
+    spawn_toolbar(
+        &mut commands,
+        &mut meshes,
+        (&cell_meshes, &game_colors, &text_settings),
+        &tools,
+        toolbar_tf,
+    );
+
     commands.insert_resource(board);
+    commands.insert_resource(EditorView::default());
+    commands.insert_resource(tools);
+    commands.insert_resource(RevealTask::default());
     ev_cell_update.send(CellUpdateEvent);
 }
@@ -1,153 +1,350 @@
 use super::{
-    board::Board,
-    components::{CellUpdateEvent, EditorCell, EmptyCell, NumberCell, UnsetCell},
-    functions::{set_empty_cell, set_number_cell, unset_cell},
+    board::{Board, Edge},
+    components::{
+        BoardRebuildEvent, CellUpdateEvent, EditorCell, PropertiesPanel, PropertyButton,
+        ShapePreview, TextPreview, ToolAction, ToolButton, Toolbar,
+    },
+    constants::{PAN_SPEED, ZOOM_MAX, ZOOM_MIN, ZOOM_STEP},
+    functions::{
+        anchor_at, apply_axial_offset, apply_view, hex_line, properties_transform, shape_positions,
+        spawn_properties_panel, text_position, toolbar_transform, update_cell, with_symmetry,
+    },
+    resources::{Clipboard, EditorTools, EditorView, Field, Paint, RevealTask, Stroke, Tool},
 };
 use crate::{
+    assets::LocaleAsset,
     board_functions::{count_empty_cells, empty_connected, get_neighbours},
     components::{Cell, RootComponent},
+    constants::Z_INDEX_TEXT,
     enums::{CellType, HintType},
-    functions::{rescale_board, switch_state},
+    functions::{calc_dimensions, calc_translation, switch_state},
+    packs::save_editor_level,
     parser::board_to_string,
-    resources::{GameColors, LoadState, TextSettings},
+    resources::{Campaign, CellMeshes, GameColors, LoadState, LocaleAssets, Profile, TextSettings},
+    solver::minimal_reveal,
     states::AppState,
-    structs::BoardConfig,
+    storage::show_notice,
+    structs::{BoardConfig, LevelText},
 };
 use bevy::{
-    input::Input,
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+    input::{
+        mouse::{MouseScrollUnit, MouseWheel},
+        Input,
+    },
+    math::{Vec2, Vec3},
     prelude::{
-        Color, Commands, Entity, EventReader, EventWriter, Handle, KeyCode, Query, Res, ResMut,
-        State, Transform, With,
+        default, Assets, Color, Commands, Entity, EventReader, EventWriter, Handle, KeyCode, Local,
+        Mesh, MouseButton, Query, Res, ResMut, State, Transform, With, Without,
     },
-    sprite::ColorMaterial,
-    text::Text,
-    window::WindowResized,
-};
-use interactable::components::{
-    Entered, Exited, JustPressedLeft, JustPressedRight, PressedLeft, PressedMiddle, PressedRight,
+    sprite::{ColorMaterial, ColorMesh2dBundle},
+    tasks::AsyncComputeTaskPool,
+    text::{Text, Text2dBundle, TextStyle},
+    time::Time,
+    window::{ReceivedCharacter, WindowResized, Windows},
 };
+use futures_lite::future;
+use interactable::components::{Entered, Exited, Hovered, JustPressedLeft};
+
+/// Paints with the selected tool. The left mouse button paints, the right one erases.
+/// While pasting, the left mouse button places the clipboard and the right one stops pasting.
+pub fn paint_system(
+    cell_query: Query<&Cell, (With<EditorCell>, With<Hovered>)>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    (mut tools, mut board, clipboard): (ResMut<EditorTools>, ResMut<Board>, Res<Clipboard>),
+    mut ev_rebuild: EventWriter<BoardRebuildEvent>,
+) {
+    let hovered = cell_query
+        .iter()
+        .next()
+        .map(|cell| (cell.x as usize, cell.y as usize));
+
+    if tools.pasting {
+        if tools.paste_at != hovered {
+            tools.paste_at = hovered;
+        }
+        if let (true, Some(pos)) = (mouse_buttons.just_pressed(MouseButton::Left), hovered) {
+            board.history.commit();
+            if board.paste(&clipboard, pos) {
+                ev_rebuild.send(BoardRebuildEvent);
+            }
+        } else if mouse_buttons.just_pressed(MouseButton::Right) {
+            tools.pasting = false;
+        }
+        return;
+    }
+    if tools.tool == Tool::Anchor {
+        return;
+    }
 
-type McUnset<'a> = (
-    Entity,
-    &'a mut EditorCell,
-    &'a mut Cell,
-    Option<&'a PressedLeft>,
-    Option<&'a PressedRight>,
-);
-pub fn mouse_click_unset_cell(
+    let paint = if mouse_buttons.just_pressed(MouseButton::Left) {
+        Some(tools.paint)
+    } else if mouse_buttons.just_pressed(MouseButton::Right) {
+        Some(Paint::Erase)
+    } else {
+        None
+    };
+    // Strokes only start on the board, not on the toolbar
+    if let (Some(paint), Some(pos)) = (paint, hovered) {
+        tools.stroke = Some(Stroke {
+            paint,
+            start: pos,
+            end: pos,
+        });
+        match tools.tool {
+            Tool::Brush => board.paint(&[pos], paint, tools.symmetry),
+            Tool::Fill => {
+                let region = board.region(pos.0, pos.1);
+                board.paint(&region, paint, tools.symmetry);
+            }
+            Tool::Select => tools.selection = None,
+            Tool::Rect | Tool::HexRange | Tool::Anchor => {}
+        }
+    }
+
+    let stroke = match tools.stroke {
+        Some(stroke) => stroke,
+        None => return,
+    };
+    let end = hovered.unwrap_or(stroke.end);
+    if end != stroke.end {
+        if tools.tool == Tool::Brush {
+            board.paint(&hex_line(stroke.end, end), stroke.paint, tools.symmetry);
+        }
+        tools.stroke = Some(Stroke { end, ..stroke });
+    }
+    if !mouse_buttons.any_pressed([MouseButton::Left, MouseButton::Right]) {
+        match tools.tool {
+            Tool::Rect | Tool::HexRange => {
+                let shape =
+                    shape_positions(tools.tool, stroke.start, end, board.width, board.height);
+                board.paint(&shape, stroke.paint, tools.symmetry);
+            }
+            Tool::Select => tools.selection = Some((stroke.start, end)),
+            Tool::Brush | Tool::Fill | Tool::Anchor => {}
+        }
+        tools.stroke = None;
+    }
+}
+
+/// Position, color and opacity of a marked cell
+type PreviewMarker = ((usize, usize), Handle<ColorMaterial>, f32);
+
+/// Shows the clipboard at the cursor while pasting, and marks the selection
+/// or the cells that a rectangle or hex range would paint
+pub fn shape_preview_system(
     mut commands: Commands,
-    mut cell_query: Query<McUnset, With<UnsetCell>>,
-    mut color_query: Query<&mut Handle<ColorMaterial>>,
-    game_colors: Res<GameColors>,
-    mut board: ResMut<Board>,
-    mut ev_cell_update: EventWriter<CellUpdateEvent>,
+    preview_query: Query<Entity, With<ShapePreview>>,
+    root_query: Query<Entity, With<RootComponent>>,
+    (cell_meshes, game_colors): (Res<CellMeshes>, Res<GameColors>),
+    (tools, board, clipboard): (Res<EditorTools>, Res<Board>, Res<Clipboard>),
+    mut shown: Local<Vec<PreviewMarker>>,
 ) {
-    for (e, mut ec, mut cell, left, right) in cell_query.iter_mut() {
-        if left.is_some() {
-            set_empty_cell(
-                &mut commands,
-                e,
-                (&mut cell, &mut ec),
-                &mut color_query,
-                &game_colors,
-                &mut board,
-                &mut ev_cell_update,
-            )
-        } else if right.is_some() {
-            set_number_cell(
-                &mut commands,
-                e,
-                (&mut cell, &mut ec),
-                &mut color_query,
-                &game_colors,
-                &mut board,
-                &mut ev_cell_update,
-            );
+    let (width, height) = (board.width, board.height);
+    let mut markers = Vec::new();
+    if tools.pasting {
+        if let Some(at) = tools.paste_at {
+            for (offset, (cell_type, hidden)) in &clipboard.cells {
+                if let Some(pos) = apply_axial_offset(at, *offset, width, height) {
+                    let color = match (cell_type, hidden) {
+                        (_, true) => game_colors.yellow_light.clone(),
+                        (Some(CellType::EmptyCell), false) => game_colors.blue_light.clone(),
+                        _ => game_colors.gray_light.clone(),
+                    };
+                    markers.push((pos, color, 0.7));
+                }
+            }
+        }
+    } else {
+        let shape = match (tools.stroke, tools.tool) {
+            (Some(stroke), Tool::Rect | Tool::HexRange | Tool::Select) => {
+                let shape = shape_positions(tools.tool, stroke.start, stroke.end, width, height);
+                match tools.tool {
+                    Tool::Select => shape,
+                    _ => with_symmetry(&shape, width, height, tools.symmetry),
+                }
+            }
+            _ => match tools.selection {
+                Some((start, end)) => shape_positions(Tool::Select, start, end, width, height),
+                None => Vec::new(),
+            },
+        };
+        for pos in shape {
+            markers.push((pos, game_colors.white.clone(), 0.3));
         }
     }
+    // Positions of a shrunk canvas
+    markers.retain(|((x, y), _, _)| *x < width && *y < height);
+
+    if *shown == markers {
+        return;
+    }
+    for e in preview_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+    if let Ok(root) = root_query.get_single() {
+        let (w, h) = calc_dimensions(width, height);
+        let entities: Vec<_> = markers
+            .iter()
+            .map(|((x, y), color, scale)| {
+                let (tx, ty) = calc_translation(*x as i32, *y as i32, w, h);
+                let mut transform = Transform::from_xyz(tx, ty, Z_INDEX_TEXT + 1.)
+                    .with_scale(Vec3::new(*scale, *scale, 1.));
+                transform.rotate_z(f32::to_radians(90.0));
+                commands
+                    .spawn_bundle(ColorMesh2dBundle {
+                        mesh: cell_meshes.std_hexagon_inner.clone().into(),
+                        material: color.clone(),
+                        transform,
+                        ..default()
+                    })
+                    .insert(ShapePreview)
+                    .id()
+            })
+            .collect();
+        commands.entity(root).push_children(&entities);
+    }
+    *shown = markers;
 }
 
-type McEmpty<'a> = (
-    Entity,
-    &'a mut EditorCell,
-    &'a mut Cell,
-    Option<&'a JustPressedLeft>,
-    Option<&'a PressedMiddle>,
-);
-pub fn mouse_click_empty_cell(
+/// Copy, cut and paste of the selection with the usual shortcuts.
+/// Escape stops pasting or clears the selection before it leaves the editor.
+pub fn clipboard_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    (mut tools, mut board, mut clipboard): (ResMut<EditorTools>, ResMut<Board>, ResMut<Clipboard>),
+    mut ev_rebuild: EventWriter<BoardRebuildEvent>,
+) {
+    let ctrl = keys.pressed(KeyCode::LControl) || keys.pressed(KeyCode::RControl);
+    let copy = keys.just_pressed(KeyCode::C);
+    let cut = keys.just_pressed(KeyCode::X);
+    if let (true, true, Some((start, end))) = (ctrl, copy || cut, tools.selection) {
+        let positions = shape_positions(Tool::Select, start, end, board.width, board.height);
+        let positions: Vec<_> = positions
+            .into_iter()
+            .filter(|(x, y)| *x < board.width && *y < board.height)
+            .collect();
+        *clipboard = board.copy(&positions);
+        if cut {
+            board.history.commit();
+            if board.cut(&positions) {
+                ev_rebuild.send(BoardRebuildEvent);
+            }
+            board.history.commit();
+        }
+    }
+    if ctrl && keys.just_pressed(KeyCode::V) && !clipboard.cells.is_empty() {
+        tools.pasting = true;
+        tools.selection = None;
+    }
+    if keys.just_pressed(KeyCode::Escape) && (tools.pasting || tools.selection.is_some()) {
+        keys.clear_just_pressed(KeyCode::Escape);
+        tools.pasting = false;
+        tools.selection = None;
+    }
+}
+
+/// Updates the cells whose entry on the board changed, e.g. by painting
+pub fn board_sync_system(
     mut commands: Commands,
-    mut cell_query: Query<McEmpty, With<EmptyCell>>,
+    mut cell_query: Query<(Entity, &mut Cell, &mut EditorCell)>,
     mut color_query: Query<&mut Handle<ColorMaterial>>,
     game_colors: Res<GameColors>,
-    mut board: ResMut<Board>,
+    board: Res<Board>,
     mut ev_cell_update: EventWriter<CellUpdateEvent>,
 ) {
-    for (e, mut ec, mut cell, left, middle) in cell_query.iter_mut() {
-        if left.is_some() {
-            ec.toggle_hidden(
-                &mut cell,
-                &mut commands,
-                &mut color_query,
-                &game_colors,
-                &mut board,
-                &mut ev_cell_update,
-            );
-        } else if middle.is_some() {
-            commands.entity(e).remove::<EmptyCell>();
-            unset_cell(
+    let mut changed = false;
+    for (e, mut cell, mut ec) in cell_query.iter_mut() {
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        // Cells of a resized canvas are respawned anyway
+        if x >= board.width || y >= board.height {
+            continue;
+        }
+        let entry = board.cells[y][x];
+        if (ec.cell_type, ec.hidden) != entry {
+            update_cell(
                 &mut commands,
                 e,
                 (&mut cell, &mut ec),
+                entry,
                 &mut color_query,
                 &game_colors,
-                &mut board,
-                &mut ev_cell_update,
             );
+            changed = true;
         }
     }
+    if changed {
+        ev_cell_update.send(CellUpdateEvent);
+    }
 }
 
-type McNumber<'a> = (
-    Entity,
-    &'a mut EditorCell,
-    &'a mut Cell,
-    &'a mut NumberCell,
-    Option<&'a JustPressedLeft>,
-    Option<&'a JustPressedRight>,
-    Option<&'a PressedMiddle>,
-);
-pub fn mouse_click_number_cell(
-    mut commands: Commands,
-    mut cell_query: Query<McNumber>,
+/// Selects the tool of a clicked toolbar button
+pub fn toolbar_button_system(
+    button_query: Query<&ToolButton, With<JustPressedLeft>>,
+    mut tools: ResMut<EditorTools>,
+) {
+    for button in button_query.iter() {
+        tools.select(button.0);
+    }
+}
+
+/// Outlines the buttons of the selected tools
+pub fn toolbar_update_system(
+    button_query: Query<(Entity, &ToolButton)>,
     mut color_query: Query<&mut Handle<ColorMaterial>>,
     game_colors: Res<GameColors>,
-    mut board: ResMut<Board>,
-    mut ev_cell_update: EventWriter<CellUpdateEvent>,
+    tools: Res<EditorTools>,
 ) {
-    for (e, mut ec, mut cell, mut nc, left, right, middle) in cell_query.iter_mut() {
-        if left.is_some() {
-            ec.toggle_hidden(
-                &mut cell,
-                &mut commands,
-                &mut color_query,
-                &game_colors,
-                &mut board,
-                &mut ev_cell_update,
-            );
-        } else if right.is_some() {
-            nc.special_hint = !nc.special_hint;
-            ev_cell_update.send(CellUpdateEvent);
-        } else if middle.is_some() {
-            commands.entity(e).remove::<NumberCell>();
-            unset_cell(
-                &mut commands,
-                e,
-                (&mut cell, &mut ec),
-                &mut color_query,
-                &game_colors,
-                &mut board,
-                &mut ev_cell_update,
-            );
+    if !tools.is_changed() {
+        return;
+    }
+    for (e, button) in button_query.iter() {
+        if let Ok(mut color) = color_query.get_mut(e) {
+            *color = match tools.is_selected(button.0) {
+                true => game_colors.white.clone(),
+                false => game_colors.alpha0.clone(),
+            };
+        }
+    }
+}
+
+/// Hotkeys of the toolbar, labeled on its buttons
+pub fn tool_hotkey_system(keys: Res<Input<KeyCode>>, mut tools: ResMut<EditorTools>) {
+    let ctrl = keys.pressed(KeyCode::LControl) || keys.pressed(KeyCode::RControl);
+    if ctrl {
+        return;
+    }
+    for (key, action) in [
+        (KeyCode::Key1, ToolAction::Paint(Paint::Blue)),
+        (KeyCode::Key2, ToolAction::Paint(Paint::Gray)),
+        (KeyCode::Key3, ToolAction::Paint(Paint::Special)),
+        (KeyCode::Key4, ToolAction::Paint(Paint::Hide)),
+        (KeyCode::Key5, ToolAction::Paint(Paint::Reveal)),
+        (KeyCode::E, ToolAction::Paint(Paint::Erase)),
+        (KeyCode::B, ToolAction::Tool(Tool::Brush)),
+        (KeyCode::F, ToolAction::Tool(Tool::Fill)),
+        (KeyCode::R, ToolAction::Tool(Tool::Rect)),
+        (KeyCode::O, ToolAction::Tool(Tool::HexRange)),
+        (KeyCode::S, ToolAction::Tool(Tool::Select)),
+        (KeyCode::T, ToolAction::Tool(Tool::Anchor)),
+        (KeyCode::P, ToolAction::Properties),
+    ] {
+        if keys.just_pressed(key) {
+            tools.select(action);
+        }
+    }
+    if keys.just_pressed(KeyCode::M) {
+        tools.symmetry = tools.symmetry.next();
+    }
+}
+
+/// On resizing the window, the toolbar is resized too
+pub fn toolbar_resize_system(
+    mut ev_window_resize: EventReader<WindowResized>,
+    mut toolbar_query: Query<&mut Transform, With<Toolbar>>,
+) {
+    for ev in ev_window_resize.iter() {
+        if let Ok(mut toolbar) = toolbar_query.get_single_mut() {
+            *toolbar = toolbar_transform(ev.width, ev.height);
         }
     }
 }
@@ -177,37 +374,42 @@ pub fn mouse_exit_cell(
 }
 
 pub fn cell_update_system(
-    mut cell_query: Query<(&mut Cell, &mut NumberCell)>,
+    mut cell_query: Query<(&Cell, &mut EditorCell)>,
     mut text_query: Query<&mut Text>,
     text_settings: Res<TextSettings>,
     mut board: ResMut<Board>,
     mut ev_cell_update: EventReader<CellUpdateEvent>,
 ) {
-    for _ev in ev_cell_update.iter() {
-        for (cell, mut nc) in cell_query.iter_mut() {
-            let neighbours =
-                get_neighbours(cell.x, cell.y, &board.cells, board.width, board.height);
-            let count = count_empty_cells(&neighbours);
-            nc.count = count;
-            let mut ts = text_settings.clone();
-            if nc.special_hint {
-                if empty_connected(&neighbours, count, true) {
-                    ts.style_cell.color = Color::GREEN;
-                    board.cells[cell.y as usize][cell.x as usize].0 =
-                        Some(CellType::NumberCell(HintType::Connected));
-                } else {
-                    ts.style_cell.color = Color::rgb(1.0, 0.4, 0.3);
-                    board.cells[cell.y as usize][cell.x as usize].0 =
-                        Some(CellType::NumberCell(HintType::Seperated));
-                }
-            } else {
-                board.cells[cell.y as usize][cell.x as usize].0 =
-                    Some(CellType::NumberCell(HintType::None));
-            }
-            *text_query.get_mut(nc.label).unwrap() =
-                Text::from_section(&format!("{}", count), ts.style_cell)
-                    .with_alignment(text_settings.alignment);
+    if ev_cell_update.iter().count() == 0 {
+        return;
+    }
+    for (cell, mut ec) in cell_query.iter_mut() {
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        if x >= board.width || y >= board.height {
+            continue;
         }
+        let special = match board.cells[y][x].0 {
+            Some(CellType::NumberCell(ht)) => ht != HintType::None,
+            _ => continue,
+        };
+        let neighbours = get_neighbours(cell.x, cell.y, &board.cells, board.width, board.height);
+        let count = count_empty_cells(&neighbours);
+        let mut ts = text_settings.clone();
+        let hint_type = if !special {
+            HintType::None
+        } else if empty_connected(&neighbours, count, true) {
+            ts.style_cell.color = Color::GREEN;
+            HintType::Connected
+        } else {
+            ts.style_cell.color = Color::rgb(1.0, 0.4, 0.3);
+            HintType::Seperated
+        };
+        // Resolving the special hint is not an edit, so it is not recorded
+        board.cells[y][x].0 = Some(CellType::NumberCell(hint_type));
+        ec.cell_type = Some(CellType::NumberCell(hint_type));
+        *text_query.get_mut(ec.text_entity).unwrap() =
+            Text::from_section(&format!("{}", count), ts.style_cell)
+                .with_alignment(text_settings.alignment);
     }
 }
 
@@ -215,27 +417,51 @@ pub fn hotkey_system(
     mut commands: Commands,
     mut cell_query: Query<(&mut Cell, &mut EditorCell)>,
     mut color_query: Query<&mut Handle<ColorMaterial>>,
-    game_colors: Res<GameColors>,
-    mut keys: ResMut<Input<KeyCode>>,
+    (game_colors, mut keys): (Res<GameColors>, ResMut<Input<KeyCode>>),
     (mut board, mut app_state, mut load_state): (
         ResMut<Board>,
         ResMut<State<AppState>>,
         ResMut<LoadState>,
     ),
-    mut ev_cell_update: EventWriter<CellUpdateEvent>,
+    (mut ev_cell_update, mut ev_rebuild): (
+        EventWriter<CellUpdateEvent>,
+        EventWriter<BoardRebuildEvent>,
+    ),
+    (mut campaign, mut reveal): (ResMut<Campaign>, ResMut<RevealTask>),
 ) {
-    if keys.just_pressed(KeyCode::S) && keys.pressed(KeyCode::LControl) {
-        let c = board.trim();
-        println!(
-            "\n{}\n0",
-            board_to_string(BoardConfig {
-                width: c[0].len(),
-                height: c.len(),
-                cells: c,
-                hints: Vec::new(),
-                text: None,
-            })
-        );
+    let ctrl = keys.pressed(KeyCode::LControl) || keys.pressed(KeyCode::RControl);
+    if keys.just_pressed(KeyCode::S) && ctrl {
+        let (c, hints) = board.trim();
+        let level = board_to_string(BoardConfig {
+            width: c[0].len(),
+            height: c.len(),
+            cells: c,
+            hints,
+            text: board.level_text(),
+            meta: board.meta.clone(),
+        });
+        println!("\n{}", level);
+        // Playable from the level packs in the level selection
+        match save_editor_level(&level) {
+            Ok(path) => {
+                show_notice(&format!("Saved as {}", path.display()));
+                campaign.reload_packs();
+            }
+            Err(e) => show_notice(&format!("Error saving level: {}", e)),
+        }
+    }
+    if keys.just_pressed(KeyCode::Z) && ctrl && board.undo() {
+        ev_rebuild.send(BoardRebuildEvent);
+    }
+    if keys.just_pressed(KeyCode::Y) && ctrl && board.redo() {
+        ev_rebuild.send(BoardRebuildEvent);
+    }
+    if keys.just_pressed(KeyCode::R) && ctrl && reveal.running.is_none() {
+        // Finds the cells that must be revealed and the hints needed to solve the level
+        let config = board.to_config();
+        let cells = config.cells.clone();
+        let task = AsyncComputeTaskPool::get().spawn(async move { minimal_reveal(&config) });
+        reveal.running = Some((task, cells));
     }
     if keys.just_pressed(KeyCode::H) {
         for (mut cell, mut ec) in cell_query.iter_mut() {
@@ -255,15 +481,377 @@ pub fn hotkey_system(
     }
 }
 
-/// On resizing the window, the board is resized too
-pub fn window_resize_system(
-    mut ev_window_resize: EventReader<WindowResized>,
+/// Applies the cells to reveal once they are found. They are dropped if the board
+/// was changed in the meantime, they would undo the changes.
+pub fn reveal_task_system(
+    mut reveal: ResMut<RevealTask>,
+    mut board: ResMut<Board>,
+    mut ev_rebuild: EventWriter<BoardRebuildEvent>,
+) {
+    let design = match reveal.running.as_mut() {
+        Some((task, _)) => match future::block_on(future::poll_once(task)) {
+            Some(design) => design,
+            None => return,
+        },
+        None => return,
+    };
+    if let Some((_, cells)) = reveal.running.take() {
+        if cells == board.cells {
+            board.apply_suggestions(&design);
+            ev_rebuild.send(BoardRebuildEvent);
+        }
+    }
+}
+
+/// Closes the current batch of edits once no mouse button is held anymore,
+/// so that a whole stroke is undone at once
+pub fn commit_edits_system(mut board: ResMut<Board>, mouse_buttons: Res<Input<MouseButton>>) {
+    let pressed =
+        mouse_buttons.any_pressed([MouseButton::Left, MouseButton::Right, MouseButton::Middle]);
+    if board.history.has_pending() && !pressed {
+        board.history.commit();
+    }
+}
+
+/// Respawns the board from its data, used for changes that affect many cells
+pub fn board_rebuild_system(
+    mut commands: Commands,
+    root_query: Query<(Entity, &Transform), With<RootComponent>>,
+    (cell_meshes, game_colors, text_settings): (
+        Res<CellMeshes>,
+        Res<GameColors>,
+        Res<TextSettings>,
+    ),
+    board: Res<Board>,
+    mut ev_rebuild: EventReader<BoardRebuildEvent>,
+    mut ev_cell_update: EventWriter<CellUpdateEvent>,
+) {
+    if ev_rebuild.iter().count() == 0 {
+        return;
+    }
+    if let Ok((root, root_transform)) = root_query.get_single() {
+        commands.entity(root).despawn_recursive();
+        board.spawn(
+            &mut commands,
+            *root_transform,
+            &cell_meshes,
+            &game_colors,
+            &text_settings,
+        );
+        ev_cell_update.send(CellUpdateEvent);
+    }
+}
+
+/// Zooms with the mouse wheel and pans with the middle mouse button or the arrow keys.
+/// Home resets the view.
+pub fn view_system(
+    wnds: Res<Windows>,
     mut root_query: Query<&mut Transform, With<RootComponent>>,
+    (keys, mouse_buttons, time): (Res<Input<KeyCode>>, Res<Input<MouseButton>>, Res<Time>),
+    mut ev_scroll: EventReader<MouseWheel>,
     board: Res<Board>,
+    mut view: ResMut<EditorView>,
 ) {
-    for ev in ev_window_resize.iter() {
-        if let Ok(mut root) = root_query.get_single_mut() {
-            rescale_board(board.width, board.height, 3, ev.width, ev.height, &mut root);
+    let wnd = match wnds.get_primary() {
+        Some(wnd) => wnd,
+        None => return,
+    };
+    let cursor = wnd.cursor_position();
+    let center = Vec2::new(wnd.width(), wnd.height()) / 2.;
+
+    if keys.just_pressed(KeyCode::Home) {
+        *view = EditorView::default();
+    }
+
+    // Zoom towards the cursor
+    for ev in ev_scroll.iter() {
+        let lines = match ev.unit {
+            MouseScrollUnit::Line => ev.y,
+            MouseScrollUnit::Pixel => ev.y / 100.,
+        };
+        let zoom = (view.zoom * ZOOM_STEP.powf(lines)).clamp(ZOOM_MIN, ZOOM_MAX);
+        let factor = zoom / view.zoom;
+        let c = cursor.map_or(Vec2::ZERO, |pos| pos - center);
+        view.pan = c - (c - view.pan) * factor;
+        view.zoom = zoom;
+    }
+
+    if mouse_buttons.just_pressed(MouseButton::Middle) {
+        view.drag = cursor;
+    }
+    if mouse_buttons.pressed(MouseButton::Middle) {
+        if let (Some(last), Some(pos)) = (view.drag, cursor) {
+            view.pan += pos - last;
+            view.drag = Some(pos);
+        }
+    } else {
+        view.drag = None;
+    }
+
+    // Alt and the arrow keys resize the canvas instead
+    if !(keys.pressed(KeyCode::LAlt) || keys.pressed(KeyCode::RAlt)) {
+        let mut dir = Vec2::ZERO;
+        if keys.pressed(KeyCode::Left) {
+            dir.x -= 1.;
+        }
+        if keys.pressed(KeyCode::Right) {
+            dir.x += 1.;
+        }
+        if keys.pressed(KeyCode::Up) {
+            dir.y += 1.;
+        }
+        if keys.pressed(KeyCode::Down) {
+            dir.y -= 1.;
         }
+        view.pan -= dir * PAN_SPEED * time.delta_seconds();
+    }
+
+    if let Ok(mut root) = root_query.get_single_mut() {
+        apply_view(&view, &board, wnd.width(), wnd.height(), &mut root);
+    }
+}
+
+/// Alt and an arrow key grows the canvas at that edge, holding shift too shrinks it
+pub fn canvas_resize_system(
+    keys: Res<Input<KeyCode>>,
+    mut board: ResMut<Board>,
+    mut ev_rebuild: EventWriter<BoardRebuildEvent>,
+) {
+    if !(keys.pressed(KeyCode::LAlt) || keys.pressed(KeyCode::RAlt)) {
+        return;
+    }
+    let grow = !(keys.pressed(KeyCode::LShift) || keys.pressed(KeyCode::RShift));
+    for (key, edge) in [
+        (KeyCode::Up, Edge::Top),
+        (KeyCode::Down, Edge::Bottom),
+        (KeyCode::Left, Edge::Left),
+        (KeyCode::Right, Edge::Right),
+    ] {
+        if keys.just_pressed(key) && board.resize(edge, grow) {
+            ev_rebuild.send(BoardRebuildEvent);
+        }
+    }
+}
+
+/// Moves the level text to the clicked position while the anchor tool is selected
+pub fn anchor_system(
+    wnds: Res<Windows>,
+    root_query: Query<&Transform, With<RootComponent>>,
+    // Clicks on the toolbar or the properties panel are not on the canvas
+    ui_query: Query<(), (With<Hovered>, Without<EditorCell>)>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    (tools, mut board): (Res<EditorTools>, ResMut<Board>),
+) {
+    if tools.tool != Tool::Anchor
+        || tools.pasting
+        || !mouse_buttons.just_pressed(MouseButton::Left)
+        || !ui_query.is_empty()
+    {
+        return;
+    }
+    let (wnd, root) = match (wnds.get_primary(), root_query.get_single()) {
+        (Some(wnd), Ok(root)) => (wnd, root),
+        _ => return,
+    };
+    if let Some(cursor) = wnd.cursor_position() {
+        let center = Vec2::new(wnd.width(), wnd.height()) / 2.;
+        let pos = (cursor - center - root.translation.truncate()) / root.scale.x;
+        let anchor = anchor_at(&board, pos);
+        if board.text_anchor != anchor {
+            board.text_anchor = anchor;
+        }
+    }
+}
+
+/// Lines of the properties panel and whether they can be clicked
+type PanelRows = Vec<(Option<PropertyButton>, String, Color)>;
+
+/// Respawns the properties panel when it is opened, closed or its content changes
+pub fn properties_panel_system(
+    mut commands: Commands,
+    panel_query: Query<Entity, With<PropertiesPanel>>,
+    (wnds, mut meshes): (Res<Windows>, ResMut<Assets<Mesh>>),
+    (game_colors, text_settings): (Res<GameColors>, Res<TextSettings>),
+    (locale, locales, profile): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>, Res<Profile>),
+    (tools, board): (Res<EditorTools>, Res<Board>),
+    mut shown: Local<Option<(PanelRows, (f32, f32))>>,
+) {
+    let wnd = match wnds.get_primary() {
+        Some(wnd) => wnd,
+        None => return,
+    };
+    let get_string = |key: &str| {
+        locale
+            .get_string(key, &locales, &profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone()
+    };
+    let panel = tools.properties.then(|| {
+        let text_type = match board.text {
+            LevelText::Key(_) => get_string("text-key"),
+            LevelText::Inline(_) => get_string("inline-text"),
+        };
+        let mut rows = vec![(
+            Some(PropertyButton(None)),
+            format!("{}: {}", get_string("text-type"), text_type),
+            Color::WHITE,
+        )];
+        for (field, key) in [
+            (Field::Text, "level-text"),
+            (Field::Title, "title"),
+            (Field::Author, "author"),
+            (Field::Difficulty, "difficulty"),
+        ] {
+            let cursor = match tools.focus == Some(field) {
+                true => "_",
+                false => "",
+            };
+            rows.push((
+                Some(PropertyButton(Some(field))),
+                format!("{}: {}{}", get_string(key), board.field(field), cursor),
+                Color::WHITE,
+            ));
+        }
+        if let LevelText::Key(key) = &board.text {
+            let missing = locale.missing_text_section(key, &locales);
+            if !key.is_empty() && !missing.is_empty() {
+                let languages: Vec<_> = missing.iter().map(|lang| format!("{:?}", lang)).collect();
+                rows.push((
+                    None,
+                    format!("{}: {}", get_string("missing-key"), languages.join(", ")),
+                    Color::RED,
+                ));
+            }
+        }
+        (rows, (wnd.width(), wnd.height()))
+    });
+
+    if *shown == panel {
+        return;
+    }
+    for e in panel_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+    if let Some((rows, (width, height))) = &panel {
+        spawn_properties_panel(
+            &mut commands,
+            &mut meshes,
+            (&game_colors, &text_settings),
+            rows,
+            tools.focus,
+            properties_transform(*width, *height, rows.len()),
+        );
+    }
+    *shown = panel;
+}
+
+/// Clicking a row of the properties panel focuses its field or switches the text type,
+/// clicking anywhere else removes the focus
+pub fn properties_click_system(
+    button_query: Query<&PropertyButton, With<Hovered>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    (mut tools, mut board): (ResMut<EditorTools>, ResMut<Board>),
+) {
+    if !mouse_buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    match button_query.iter().next() {
+        Some(PropertyButton(None)) => board.toggle_text_type(),
+        Some(PropertyButton(Some(field))) => tools.focus = Some(*field),
+        None if tools.focus.is_some() => tools.focus = None,
+        None => {}
+    }
+}
+
+/// Types into the focused field. While a field is focused, the keys don't trigger hotkeys.
+/// Tab moves to the next field, Enter and Escape remove the focus.
+pub fn text_input_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut ev_chars: EventReader<ReceivedCharacter>,
+    (mut tools, mut board): (ResMut<EditorTools>, ResMut<Board>),
+) {
+    let field = match tools.focus {
+        Some(field) => field,
+        None => {
+            // Characters typed before focusing a field are dropped
+            ev_chars.iter().last();
+            return;
+        }
+    };
+    let mut value = board.field(field);
+    for ev in ev_chars.iter() {
+        if !ev.char.is_control() {
+            value.push(ev.char);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        value.pop();
+    }
+    if value != board.field(field) {
+        board.set_field(field, value);
+    }
+    if keys.just_pressed(KeyCode::Tab) {
+        tools.focus = Some(field.next());
+    } else if keys.just_pressed(KeyCode::Return) || keys.just_pressed(KeyCode::Escape) {
+        tools.focus = None;
+    }
+    keys.clear();
+}
+
+/// Shows the level text at its anchor. A locale key is shown in the current language,
+/// in red with the key itself if it is missing there.
+pub fn text_preview_system(
+    mut commands: Commands,
+    preview_query: Query<Entity, With<TextPreview>>,
+    root_query: Query<Entity, With<RootComponent>>,
+    text_settings: Res<TextSettings>,
+    (locale, locales, profile): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>, Res<Profile>),
+    board: Res<Board>,
+    mut shown: Local<Option<(Entity, Vec2, LevelText, bool)>>,
+) {
+    let root = match root_query.get_single() {
+        Ok(root) => root,
+        Err(_) => return,
+    };
+    let sections = match &board.text {
+        LevelText::Key(key) if key.is_empty() => None,
+        LevelText::Key(key) => locale.get_text_section(key, &locales, &profile),
+        LevelText::Inline(_) => None,
+    };
+    // The text is placed below the set cells, so it moves when the board is painted
+    let pos = text_position(&board);
+    let current = Some((root, pos, board.text.clone(), sections.is_some()));
+    if *shown == current {
+        return;
+    }
+    for e in preview_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+    let style = text_settings.style_cell.clone();
+    let text = match (&board.text, sections) {
+        (LevelText::Key(key), _) if key.is_empty() => None,
+        (LevelText::Key(_), Some(sections)) => Some(Text::from_sections(
+            sections.iter().map(|tsc| tsc.to_text_section(&style)),
+        )),
+        (LevelText::Key(key), None) => Some(Text::from_section(
+            key.clone(),
+            TextStyle {
+                color: Color::RED,
+                ..style
+            },
+        )),
+        (LevelText::Inline(inline), _) => Some(Text::from_section(inline.clone(), style)),
+    };
+    if let Some(text) = text {
+        let preview = commands
+            .spawn_bundle(Text2dBundle {
+                text: text.with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(pos.x, pos.y, Z_INDEX_TEXT),
+                ..default()
+            })
+            .insert(TextPreview)
+            .id();
+        commands.entity(root).add_child(preview);
     }
+    *shown = current;
 }
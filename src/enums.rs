@@ -1,3 +1,6 @@
+use crate::constants::HARDCORE_LIMITS;
+use serde::{Deserialize, Serialize};
+
 /// The type of cell.
 /// Used in cell component for uncover-handling
 #[cfg_attr(
@@ -12,7 +15,7 @@ pub enum CellType {
 
 /// Direction of the column/row hints.
 /// Straight down (TOP), down-right (RIGHT) and down-left (LEFT)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HintDirection {
     Down,
     LeftDown,
@@ -45,3 +48,77 @@ impl Default for HintType {
         Self::None
     }
 }
+
+/// Pencil mark of a hidden cell, a guess that is not committed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mark {
+    Blue,
+    Gray,
+}
+
+/// Kind of a board that is generated instead of read from a level file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Generated {
+    /// The puzzle of the day, by the days since 1970-01-01
+    Daily(u32),
+    /// A puzzle of an endless run, by its number in the run
+    Endless(u32),
+}
+
+/// How a level is played, chosen in the level selection
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlayMode {
+    #[default]
+    Normal,
+    /// Wrong clicks are explained instead of counting as mistakes and uncovers can be undone.
+    /// The score is not saved.
+    Practice,
+    /// The level fails with more mistakes than the limit
+    Hardcore(u16),
+}
+
+impl PlayMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Normal => Self::Practice,
+            Self::Practice => Self::Hardcore(HARDCORE_LIMITS[0]),
+            Self::Hardcore(limit) => HARDCORE_LIMITS
+                .iter()
+                .find(|l| **l > limit)
+                .map_or(Self::Normal, |l| Self::Hardcore(*l)),
+        }
+    }
+    /// Locale key of the name
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Practice => "practice",
+            Self::Hardcore(_) => "hardcore",
+        }
+    }
+}
+
+/// What has to be done for an achievement, as written in the achievements file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Goal {
+    /// Solve this many levels of the campaign or the level packs
+    Solve(u32),
+    /// Solve every level of the campaign, however many it has
+    SolveCampaign,
+    /// Collect this many points in the campaign
+    Points(u16),
+    /// Solve every level of a stage of the campaign without a mistake
+    FlawlessStage,
+    /// Solve a board with at least this many hidden cells
+    HiddenCells(u16),
+    /// Solve this many levels in a row without a hint
+    NoHints(u32),
+    /// Solve this many levels in a row without a mistake
+    NoMistakes(u32),
+    /// Solve this many levels in the hardcore mode
+    Hardcore(u32),
+    /// Solve the daily puzzle this many days in a row
+    DailyStreak(u32),
+    /// Reach this score in an endless run
+    EndlessScore(u32),
+}
@@ -70,6 +70,17 @@ pub fn spawn_cell(
     (child1, child2)
 }
 
+/// Formats a duration in seconds as minutes, seconds and tenths, e.g. 1:05.3
+pub fn format_time(seconds: f32) -> String {
+    let tenths = (seconds * 10.) as u32;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+/// Points for solving a level without mistakes, grows with the number of hidden cells
+pub fn max_points(hidden: u16) -> u16 {
+    ((hidden as f32).powf(0.6) as u16).max(1).min(30)
+}
+
 /// Spawns the text in a cell
 pub fn spawn_cell_text(
     commands: &mut Commands,
@@ -194,6 +205,14 @@ pub fn calc_translation(x: i32, y: i32, w: f32, h: f32) -> (f32, f32) {
     (tx, ty)
 }
 
+/// Position of the level text, the anchor is an offset in cells from the
+/// default position centered below the board
+pub fn text_translation((x, y): (i32, i32), h: f32) -> (f32, f32) {
+    let tx = x as f32 * RADIUS * 1.56;
+    let ty = -h - 3. * RADIUS - y as f32 * RADIUS * 0.9;
+    (tx, ty)
+}
+
 pub fn calc_dimensions(width: usize, height: usize) -> (f32, f32) {
     let w = ((width - 1) as f32 * RADIUS * 1.56) / 2.;
     let h = ((height - 1) as f32 * RADIUS * 1.8) / 2.;
@@ -229,3 +248,19 @@ pub fn get_base_path() -> PathBuf {
             .unwrap()
     }
 }
+
+/// Returns the directory of the player's files, like the profile and the saves.
+/// It's the platform's data directory, e.g. `~/.local/share/honeycombs` on Linux,
+/// so that read-only installs work and the game files stay untouched.
+///
+/// Falls back to the base path where no such directory exists.
+pub fn get_user_path() -> PathBuf {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(data_dir) = dirs_next::data_dir() {
+        let path = data_dir.join(crate::constants::USER_DIR);
+        if std::fs::create_dir_all(&path).is_ok() {
+            return path;
+        }
+    }
+    get_base_path()
+}
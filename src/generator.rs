@@ -0,0 +1,143 @@
+use crate::{
+    enums::{CellType, HintType},
+    solver::minimal_reveal,
+    structs::{BoardConfig, LevelMeta},
+};
+
+/// Deterministic random numbers (SplitMix64). The sequence of a seed must never change,
+/// otherwise the generated puzzles would differ between versions of the game.
+pub struct SeededRng(u64);
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// A number in 0..n
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+    /// True with the given chance in percent
+    pub fn chance(&mut self, percent: u8) -> bool {
+        self.below(100) < percent as u64
+    }
+}
+
+/// Shape and content of a generated board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorSettings {
+    pub width: usize,
+    pub height: usize,
+    /// Share of the cells that are blue, in percent
+    pub blue: u8,
+    /// Share of the positions without a cell, in percent
+    pub holes: u8,
+}
+
+/// Settings of the endless mode by difficulty, the easiest first
+pub const DIFFICULTIES: [GeneratorSettings; 6] = [
+    GeneratorSettings {
+        width: 5,
+        height: 4,
+        blue: 40,
+        holes: 10,
+    },
+    GeneratorSettings {
+        width: 6,
+        height: 5,
+        blue: 40,
+        holes: 10,
+    },
+    GeneratorSettings {
+        width: 7,
+        height: 5,
+        blue: 40,
+        holes: 15,
+    },
+    GeneratorSettings {
+        width: 8,
+        height: 6,
+        blue: 40,
+        holes: 15,
+    },
+    GeneratorSettings {
+        width: 9,
+        height: 7,
+        blue: 45,
+        holes: 15,
+    },
+    GeneratorSettings {
+        width: 11,
+        height: 8,
+        blue: 45,
+        holes: 15,
+    },
+];
+
+/// Generates a board from the seed. The cells are placed at random, then the solver
+/// reveals cells and adds hints until the board can be solved without guessing.
+pub fn generate(seed: u64, settings: &GeneratorSettings) -> BoardConfig {
+    let mut rng = SeededRng::new(seed);
+    let mut cells = vec![vec![(None, true); settings.width]; settings.height];
+    let mut blue = 0;
+    for row in cells.iter_mut() {
+        for cell in row.iter_mut() {
+            if rng.chance(settings.holes) {
+                continue;
+            }
+            cell.0 = Some(if rng.chance(settings.blue) {
+                blue += 1;
+                CellType::EmptyCell
+            } else {
+                CellType::NumberCell(HintType::None)
+            });
+        }
+    }
+    // A board without blue cells would be solved from the start
+    if blue == 0 {
+        let (x, y) = (settings.width / 2, settings.height / 2);
+        cells[y][x].0 = Some(CellType::EmptyCell);
+    }
+    minimal_reveal(&BoardConfig {
+        width: settings.width,
+        height: settings.height,
+        cells,
+        hints: Vec::new(),
+        text: None,
+        meta: LevelMeta::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: GeneratorSettings = GeneratorSettings {
+        width: 5,
+        height: 4,
+        blue: 40,
+        holes: 10,
+    };
+
+    #[test]
+    fn rng_sequence_is_fixed() {
+        // First values of SplitMix64 for the seed 0
+        let mut rng = SeededRng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+        assert_eq!(rng.next_u64(), 0x06c4_5d18_8009_454f);
+    }
+
+    #[test]
+    fn same_seed_same_board() {
+        let a = generate(42, &SETTINGS);
+        let b = generate(42, &SETTINGS);
+        assert_eq!(a.cells, b.cells);
+        assert_eq!(a.hints.len(), b.hints.len());
+    }
+}
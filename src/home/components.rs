@@ -15,6 +15,12 @@ pub struct OptionCell {
     pub app_state: AppState,
 }
 
+/// Option cells that start a generated puzzle instead of the level to continue
+#[derive(Component)]
+pub struct DailyCell;
+#[derive(Component)]
+pub struct EndlessCell;
+
 impl OptionCell {
     pub fn hover(
         &self,
@@ -38,16 +44,27 @@ impl OptionCell {
         cell.unhover(commands, None, colors.2, colors.1, color_query);
     }
 
-    pub fn click(
+    /// Only shows the click, for options that switch the state once their level is generated
+    pub fn press(
         &self,
         cell: &mut Cell,
         commands: &mut Commands,
         color_query: &mut Query<&mut Handle<ColorMaterial>>,
         game_colors: &GameColors,
-        (app_state, load_state): (&mut State<AppState>, &mut LoadState),
     ) {
         let colors = get_colors_for_app_state(game_colors, self.app_state);
         cell.click(commands, None, colors.2, colors.1, color_query);
+    }
+
+    pub fn click(
+        &self,
+        cell: &mut Cell,
+        commands: &mut Commands,
+        color_query: &mut Query<&mut Handle<ColorMaterial>>,
+        game_colors: &GameColors,
+        (app_state, load_state): (&mut State<AppState>, &mut LoadState),
+    ) {
+        self.press(cell, commands, color_query, game_colors);
         switch_state(Some(self.app_state), app_state, load_state);
     }
 }
@@ -70,12 +70,12 @@ pub fn get_colors_for_app_state(
     Handle<ColorMaterial>,
 ) {
     match app_state {
-        AppState::LevelSelection => (
+        AppState::LevelSelection | AppState::Level => (
             game_colors.blue_dark.clone(),
             game_colors.blue_medium.clone(),
             game_colors.blue_light.clone(),
         ),
-        AppState::Editor | AppState::Credits => (
+        AppState::Editor | AppState::Credits | AppState::Achievements => (
             game_colors.yellow_dark.clone(),
             game_colors.yellow_medium.clone(),
             game_colors.yellow_light.clone(),
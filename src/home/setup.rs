@@ -1,10 +1,15 @@
-use super::functions::spawn_option_cell;
+use super::{
+    components::{DailyCell, EndlessCell},
+    functions::spawn_option_cell,
+};
 use crate::{
     assets::LocaleAsset,
     components::RootComponent,
     constants::{MED_SCALE, RADIUS, Z_INDEX_CELL_BACK, Z_INDEX_TEXT},
     functions::rescale_board,
-    resources::{CellMeshes, GameColors, LocaleAssets, Profile, TextSettings},
+    resources::{
+        Campaign, CellMeshes, GameColors, LocaleAssets, Profile, Profiles, Progress, TextSettings,
+    },
     states::AppState,
 };
 use bevy::{
@@ -27,7 +32,8 @@ pub fn setup(
     wnds: Res<Windows>,
     (cell_meshes, game_colors, locale, profile, text_settings): StandardResources,
     asset_server: Res<AssetServer>,
-    locales: Res<Assets<LocaleAsset>>,
+    (locales, progress, campaign): (Res<Assets<LocaleAsset>>, Res<Progress>, Res<Campaign>),
+    profiles: Res<Profiles>,
 ) {
     let mut big_transform = Transform::from_xyz(0., 0., Z_INDEX_CELL_BACK);
     big_transform.rotate_z(f32::to_radians(90.0));
@@ -42,8 +48,13 @@ pub fn setup(
             .get_string("start", &locales, &profile)
             .unwrap_or(&"String not found".to_string()),
     );
+    // The bottom row is centered, the editor is only part of it when enabled
+    let bottom_x = match profile.show_editor {
+        true => [-2.1, -0.7, 0.7, 2.1],
+        false => [0., -1.4, 0., 1.4],
+    };
     big_transform.translation = Vec3::new(
-        -1.2 * RADIUS * MED_SCALE,
+        bottom_x[0] * RADIUS * MED_SCALE,
         -2. * RADIUS * MED_SCALE,
         Z_INDEX_CELL_BACK,
     );
@@ -95,11 +106,41 @@ pub fn setup(
             .unwrap_or(&"String not found".to_string()),
     );
     big_transform.translation = Vec3::new(
-        if profile.show_editor {
-            1.2 * RADIUS * MED_SCALE
-        } else {
-            0.
-        },
+        bottom_x[1] * RADIUS * MED_SCALE,
+        -2. * RADIUS * MED_SCALE,
+        Z_INDEX_CELL_BACK,
+    );
+    let daily_cell = spawn_option_cell(
+        &mut commands,
+        &cell_meshes,
+        &game_colors,
+        &text_settings,
+        big_transform.with_scale(Vec3::new(0.75, 0.75, 0.75)),
+        AppState::Level,
+        locale
+            .get_string("daily", &locales, &profile)
+            .unwrap_or(&"String not found".to_string()),
+    );
+    commands.entity(daily_cell).insert(DailyCell);
+    big_transform.translation = Vec3::new(
+        bottom_x[2] * RADIUS * MED_SCALE,
+        -2. * RADIUS * MED_SCALE,
+        Z_INDEX_CELL_BACK,
+    );
+    let endless_cell = spawn_option_cell(
+        &mut commands,
+        &cell_meshes,
+        &game_colors,
+        &text_settings,
+        big_transform.with_scale(Vec3::new(0.75, 0.75, 0.75)),
+        AppState::Level,
+        locale
+            .get_string("endless", &locales, &profile)
+            .unwrap_or(&"String not found".to_string()),
+    );
+    commands.entity(endless_cell).insert(EndlessCell);
+    big_transform.translation = Vec3::new(
+        bottom_x[3] * RADIUS * MED_SCALE,
         -2. * RADIUS * MED_SCALE,
         Z_INDEX_CELL_BACK,
     );
@@ -115,6 +156,68 @@ pub fn setup(
             .unwrap_or(&"String not found".to_string()),
     );
 
+    big_transform.translation = Vec3::new(
+        3. * RADIUS * MED_SCALE,
+        RADIUS * MED_SCALE,
+        Z_INDEX_CELL_BACK,
+    );
+    // Only shown if there is a level to continue that is still part of the campaign
+    let last = progress.get_last().and_then(|id| campaign.find_level(id));
+    let continue_cell = last.map(|_| {
+        spawn_option_cell(
+            &mut commands,
+            &cell_meshes,
+            &game_colors,
+            &text_settings,
+            big_transform.with_scale(Vec3::new(0.75, 0.75, 0.75)),
+            AppState::Level,
+            locale
+                .get_string("continue", &locales, &profile)
+                .unwrap_or(&"String not found".to_string()),
+        )
+    });
+
+    big_transform.translation = Vec3::new(
+        -3. * RADIUS * MED_SCALE,
+        RADIUS * MED_SCALE,
+        Z_INDEX_CELL_BACK,
+    );
+    // Shows the name of the current profile and leads to the other ones
+    let profile_cell = spawn_option_cell(
+        &mut commands,
+        &cell_meshes,
+        &game_colors,
+        &text_settings,
+        big_transform.with_scale(Vec3::new(0.75, 0.75, 0.75)),
+        AppState::ProfileSelection,
+        profiles.get_current_name(),
+    );
+
+    big_transform.translation = Vec3::new(-4.3 * RADIUS * MED_SCALE, 0., Z_INDEX_CELL_BACK);
+    let achievements_cell = spawn_option_cell(
+        &mut commands,
+        &cell_meshes,
+        &game_colors,
+        &text_settings,
+        big_transform.with_scale(Vec3::new(0.75, 0.75, 0.75)),
+        AppState::Achievements,
+        locale
+            .get_string("achievements", &locales, &profile)
+            .unwrap_or(&"String not found".to_string()),
+    );
+    big_transform.translation = Vec3::new(4.3 * RADIUS * MED_SCALE, 0., Z_INDEX_CELL_BACK);
+    let stats_cell = spawn_option_cell(
+        &mut commands,
+        &cell_meshes,
+        &game_colors,
+        &text_settings,
+        big_transform.with_scale(Vec3::new(0.75, 0.75, 0.75)),
+        AppState::Stats,
+        locale
+            .get_string("stats", &locales, &profile)
+            .unwrap_or(&"String not found".to_string()),
+    );
+
     let logo_entity = commands
         .spawn_bundle(SpriteBundle {
             texture: asset_server.load("branding/logo.png"),
@@ -126,7 +229,7 @@ pub fn setup(
     let mut root_transform = Transform::identity();
     for wnd in wnds.iter() {
         // TODO: Remove hard-coded width/height
-        rescale_board(10, 6, 1, wnd.width(), wnd.height(), &mut root_transform);
+        rescale_board(12, 6, 1, wnd.width(), wnd.height(), &mut root_transform);
     }
 
     let root = commands
@@ -136,6 +239,11 @@ pub fn setup(
             quit_cell,
             options_cell,
             credits_cell,
+            daily_cell,
+            endless_cell,
+            profile_cell,
+            achievements_cell,
+            stats_cell,
             logo_entity,
         ])
         .insert_bundle(SpatialBundle::from_transform(root_transform))
@@ -144,4 +252,7 @@ pub fn setup(
     if let Some(ec) = editor_cell {
         commands.entity(root).push_children(&[ec]);
     }
+    if let Some(cc) = continue_cell {
+        commands.entity(root).push_children(&[cc]);
+    }
 }
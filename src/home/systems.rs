@@ -1,8 +1,12 @@
-use super::components::OptionCell;
+use super::components::{DailyCell, EndlessCell, OptionCell};
 use crate::{
     components::{Cell, Language, RootComponent},
+    daily::{daily_board, today},
+    enums::{Generated, PlayMode},
     functions::{rescale_board, switch_state},
-    resources::{GameColors, LoadState, Profile, SfxAssets},
+    resources::{
+        Campaign, EndlessRun, GameColors, GenerateTask, LoadState, Profile, Progress, SfxAssets,
+    },
     states::AppState,
 };
 use bevy::{
@@ -13,14 +17,50 @@ use bevy::{
 use bevy_kira_audio::{Audio, AudioControl};
 use interactable::components::{Entered, Exited, ReleasedLeft};
 
+type GeneratedOptionCell<'a> = (
+    &'a OptionCell,
+    &'a mut Cell,
+    Option<&'a DailyCell>,
+    Option<&'a EndlessCell>,
+);
+
 pub fn mouse_click_cell(
     mut commands: Commands,
-    mut option_cell_query: Query<(&OptionCell, &mut Cell), With<ReleasedLeft>>,
+    mut option_cell_query: Query<GeneratedOptionCell, With<ReleasedLeft>>,
     mut color_query: Query<&mut Handle<ColorMaterial>>,
-    game_colors: Res<GameColors>,
+    (game_colors, progress, campaign): (Res<GameColors>, Res<Progress>, Res<Campaign>),
     (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+    (mut run, mut profile, mut generating): (
+        ResMut<EndlessRun>,
+        ResMut<Profile>,
+        ResMut<GenerateTask>,
+    ),
 ) {
-    for (oc, mut cell) in option_cell_query.iter_mut() {
+    for (oc, mut cell, daily, endless) in option_cell_query.iter_mut() {
+        // The levels reachable from the home screen are the generated ones and the one to continue
+        if daily.is_some() {
+            // These levels start once their board is generated
+            let day = today();
+            generating.start(Generated::Daily(day), AppState::Home, move || {
+                daily_board(day)
+            });
+            oc.press(&mut cell, &mut commands, &mut color_query, &game_colors);
+            continue;
+        } else if endless.is_some() {
+            run.start();
+            run.load_puzzle(&mut generating, AppState::Home);
+            profile.count_endless_run();
+            oc.press(&mut cell, &mut commands, &mut color_query, &game_colors);
+            continue;
+        } else if oc.app_state == AppState::Level {
+            let last = progress.get_last().and_then(|id| campaign.find_level(id));
+            if let Some((stage, level)) = last {
+                load_state.filename = campaign.get_path(stage, level);
+                load_state.ids = Some((stage, level));
+                load_state.generated = None;
+                load_state.mode = PlayMode::Normal;
+            }
+        }
         oc.click(
             &mut cell,
             &mut commands,
@@ -82,7 +122,7 @@ pub fn window_resize_system(
     for ev in ev_window_resize.iter() {
         if let Ok(mut root) = root_query.get_single_mut() {
             // TODO: Remove hard-coded width/height
-            rescale_board(10, 6, 1, ev.width, ev.height, &mut root);
+            rescale_board(12, 6, 1, ev.width, ev.height, &mut root);
         }
     }
 }
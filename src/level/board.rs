@@ -1,17 +1,23 @@
-use super::components::{MistakesText, RemainingText};
+use super::components::{
+    HintButton, HintText, MistakesText, PencilButton, RemainingText, TimeText, UndoButton,
+};
 use crate::{
     assets::LocaleAsset,
-    board_functions::{count_empty_cells, empty_connected, get_neighbours},
+    board_functions::{
+        count_empty_cells, empty_connected, get_column_positions, get_neighbour_positions,
+        get_neighbours,
+    },
     components::{Cell, RootComponent},
-    constants::{RADIUS, Z_INDEX_CELL_BACK, Z_INDEX_TEXT},
-    enums::{CellType, HintType},
+    constants::{GENERATED_IDS, RADIUS, Z_INDEX_CELL_BACK, Z_INDEX_TEXT},
+    enums::{CellType, HintType, Mark, PlayMode},
     functions::{
-        calc_dimensions, calc_translation, make_cell_interactable, spawn_cell, spawn_cell_text,
-        spawn_hint,
+        calc_dimensions, calc_translation, format_time, make_cell_interactable, max_points,
+        spawn_cell, spawn_cell_text, spawn_hint, text_translation,
     },
     level::components::{EmptyCell, GameCell, NumberCell},
     resources::{CellMeshes, GameColors, LocaleAssets, Profile, TextSettings},
-    structs::BoardConfig,
+    solver::ConstraintSource,
+    structs::{BoardConfig, LevelProgress, LevelText, Move},
 };
 use bevy::{
     hierarchy::BuildChildren,
@@ -21,19 +27,47 @@ use bevy::{
         Visibility,
     },
     sprite::{ColorMaterial, ColorMesh2dBundle},
-    text::{Text, Text2dBundle, TextAlignment},
+    text::{Text, Text2dBounds, Text2dBundle, TextAlignment, TextSection},
 };
 use interactable::{components::Interactable, shapes::Shape};
 
+/// Points lost for every hint
+const HINT_COST: u16 = 1;
+
 /// Board component storing common variables
 pub struct Board {
     pub cells: Vec<Entity>,
+    /// Column hints in the order of the config
     pub texts: Vec<Entity>,
     pub width: usize,
     pub height: usize,
     remaining: (u16, u16),
     hidden: u16,
     mistakes: u16,
+    hints_used: u16,
+    /// Cell of the hint that is currently shown
+    hint: Option<(usize, usize)>,
+    /// Pencil marks of hidden cells
+    marks: Vec<Vec<Option<Mark>>>,
+    /// Uncovered number cells marked as done
+    done_cells: Vec<Vec<bool>>,
+    /// Column hints marked as done, in the order of the config
+    done_hints: Vec<bool>,
+    /// Cells uncovered by the player, saved to resume the level
+    uncovered: Vec<Vec<bool>>,
+    /// Number cells whose constraint is used up, they are dimmed automatically
+    used_cells: Vec<Vec<bool>>,
+    /// Column hints whose constraint is used up, in the order of the config
+    used_hints: Vec<bool>,
+    /// Whether a constraint is used up as soon as all of its blue cells are found
+    dim_satisfied: bool,
+    /// Uncovers and mistakes in the order they were made
+    moves: Vec<Move>,
+    /// Whether clicks annotate instead of uncovering
+    pencil: bool,
+    mode: PlayMode,
+    /// The solution, used to find hints
+    config: BoardConfig,
     stage_id: u8,
     level_id: u8,
 }
@@ -58,7 +92,15 @@ impl Board {
             &mut Assets<ColorMaterial>,
             &Assets<LocaleAsset>,
         ),
+        (progress, mode): (Option<&LevelProgress>, PlayMode),
     ) -> Self {
+        let progress = progress.cloned().unwrap_or_default();
+        let mut uncovered = vec![vec![false; config.width]; config.height];
+        for (x, y) in progress.uncovered.iter() {
+            if *y < config.height && *x < config.width {
+                uncovered[*y][*x] = true;
+            }
+        }
         let cells = &config.cells;
         let hints = &config.hints;
         let width = config.width;
@@ -71,6 +113,7 @@ impl Board {
 
         let mut empty_remaining = 0;
         let mut number_remaining = 0;
+        let mut total_hidden = 0;
 
         for y in 0..height {
             for x in 0..width {
@@ -79,6 +122,11 @@ impl Board {
                 if cell_type.is_none() {
                     continue;
                 }
+                if hidden {
+                    total_hidden += 1;
+                }
+                // Cells uncovered in a previous session
+                let hidden = hidden && !uncovered[y][x];
                 let cell_type = cell_type.unwrap();
 
                 let (tx, ty) = calc_translation(x as i32, y as i32, w, h);
@@ -161,7 +209,8 @@ impl Board {
                         commands.entity(cell).insert(EmptyCell);
                     }
                 }
-                if hidden {
+                // Uncovered number cells can be marked as done
+                if hidden || matches!(cell_type, CellType::NumberCell(_)) {
                     make_cell_interactable(commands, cell, RADIUS);
                 }
 
@@ -214,29 +263,34 @@ impl Board {
             text_entities.push(hint_entity);
         }
 
-        let text1 = config.text.as_ref().map(|text| {
-            let display_text = match text.2.as_str() {
-                "intro" => {
-                    if profile.mouse_inverted {
-                        "intro-alt".to_string()
-                    } else {
-                        text.2.clone()
+        let text1 = config.text.as_ref().map(|(x, y, text)| {
+            let sections = match text {
+                LevelText::Key(key) => {
+                    let key = match key.as_str() {
+                        "intro" if profile.mouse_inverted => "intro-alt",
+                        _ => key.as_str(),
+                    };
+                    match locale.get_text_section(key, locales, profile) {
+                        Some(sections) => sections
+                            .iter()
+                            .map(|tsc| tsc.to_text_section(&text_settings.style_cell))
+                            .collect(),
+                        None => vec![TextSection::new(
+                            "String not found",
+                            text_settings.style_cell.clone(),
+                        )],
                     }
                 }
-                _ => text.2.clone(),
+                LevelText::Inline(inline) => vec![TextSection::new(
+                    inline.clone(),
+                    text_settings.style_cell.clone(),
+                )],
             };
+            let (tx, ty) = text_translation((*x, *y), h);
             commands
                 .spawn_bundle(Text2dBundle {
-                    text: Text::from_sections(
-                        locale
-                            .get_text_section(display_text.as_str(), locales, profile)
-                            .unwrap()
-                            .to_vec()
-                            .iter()
-                            .map(|tsc| tsc.to_text_section(&text_settings.style_cell)),
-                    )
-                    .with_alignment(text_settings.alignment),
-                    transform: Transform::from_xyz(0., -h - 3. * RADIUS, Z_INDEX_TEXT),
+                    text: Text::from_sections(sections).with_alignment(text_settings.alignment),
+                    transform: Transform::from_xyz(tx, ty, Z_INDEX_TEXT),
                     ..default()
                 })
                 .id()
@@ -266,30 +320,163 @@ impl Board {
             })
             .insert(MistakesText)
             .id();
+        let text4 = commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::from_section(
+                    format!("{}: {}", "Time", format_time(progress.elapsed)),
+                    text_settings.style_cell.clone(),
+                )
+                .with_alignment(TextAlignment::CENTER_LEFT),
+                transform: Transform::from_xyz(w, h + 4.5 * RADIUS, Z_INDEX_TEXT),
+                ..default()
+            })
+            .insert(TimeText)
+            .id();
+        let hint_label = locale
+            .get_string("hint", locales, profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone();
+        let hint_button = commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::from_section(
+                    format!("{} (H)", hint_label),
+                    text_settings.style_cell.clone(),
+                )
+                .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(-w + 1.5 * RADIUS, h + 2.5 * RADIUS, Z_INDEX_TEXT),
+                ..default()
+            })
+            .insert(Interactable {
+                shape: Shape::Quad(interactable::shapes::Quad {
+                    width: 3. * RADIUS,
+                    height: RADIUS,
+                }),
+                ..default()
+            })
+            .insert(HintButton)
+            .id();
+        let hint_text = commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::default(),
+                text_2d_bounds: Text2dBounds {
+                    size: Vec2::new((2. * w).max(6. * RADIUS), f32::MAX),
+                },
+                transform: Transform::from_xyz(-w, h + 4.2 * RADIUS, Z_INDEX_TEXT),
+                ..default()
+            })
+            .insert(HintText)
+            .id();
+        let pencil_label = locale
+            .get_string("pencil", locales, profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone();
+        let pencil_button = commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::from_section(
+                    format!("{} (Space)", pencil_label),
+                    text_settings.style_cell.clone(),
+                )
+                .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(-w + 1.5 * RADIUS, h + 3.5 * RADIUS, Z_INDEX_TEXT),
+                ..default()
+            })
+            .insert(Interactable {
+                shape: Shape::Quad(interactable::shapes::Quad {
+                    width: 3. * RADIUS,
+                    height: RADIUS,
+                }),
+                ..default()
+            })
+            .insert(PencilButton)
+            .id();
+
+        let mut marks = vec![vec![None; width]; height];
+        for ((x, y), mark) in progress.marks.iter() {
+            if *y < height && *x < width {
+                marks[*y][*x] = Some(*mark);
+            }
+        }
+        let mut done_cells = vec![vec![false; width]; height];
+        for (x, y) in progress.done_cells.iter() {
+            if *y < height && *x < width {
+                done_cells[*y][*x] = true;
+            }
+        }
+        let mut done_hints = vec![false; hints.len()];
+        for i in progress.done_hints.iter() {
+            if let Some(done) = done_hints.get_mut(*i) {
+                *done = true;
+            }
+        }
 
         let root = commands
             .spawn()
             .push_children(&cell_entities)
             .push_children(&text_entities)
-            .push_children(&[text2, text3])
+            .push_children(&[text2, text3, text4, hint_button, hint_text, pencil_button])
             .insert_bundle(SpatialBundle::from_transform(root_transform))
             .insert(RootComponent)
             .id();
         if let Some(t) = text1 {
             commands.entity(root).add_child(t);
         }
+        if mode == PlayMode::Practice {
+            let undo_label = locale
+                .get_string("undo", locales, profile)
+                .unwrap_or(&"String not found".to_string())
+                .clone();
+            let undo_button = commands
+                .spawn_bundle(Text2dBundle {
+                    text: Text::from_section(
+                        format!("{} (Z)", undo_label),
+                        text_settings.style_cell.clone(),
+                    )
+                    .with_alignment(text_settings.alignment),
+                    transform: Transform::from_xyz(
+                        -w + 4.5 * RADIUS,
+                        h + 2.5 * RADIUS,
+                        Z_INDEX_TEXT,
+                    ),
+                    ..default()
+                })
+                .insert(Interactable {
+                    shape: Shape::Quad(interactable::shapes::Quad {
+                        width: 3. * RADIUS,
+                        height: RADIUS,
+                    }),
+                    ..default()
+                })
+                .insert(UndoButton)
+                .id();
+            commands.entity(root).add_child(undo_button);
+        }
 
-        Self {
+        let mut board = Self {
             cells: cell_entities,
             texts: text_entities,
             width,
             height,
             remaining: (empty_remaining, number_remaining),
-            hidden: empty_remaining + number_remaining,
-            mistakes: 0,
+            hidden: total_hidden,
+            mistakes: progress.mistakes,
+            hints_used: progress.hints_used,
+            hint: None,
+            marks,
+            done_cells,
+            done_hints,
+            uncovered,
+            used_cells: vec![vec![false; width]; height],
+            used_hints: vec![false; hints.len()],
+            dim_satisfied: profile.dim_satisfied,
+            moves: progress.moves.clone(),
+            pencil: false,
+            mode,
+            config: config.clone(),
             stage_id,
             level_id,
-        }
+        };
+        board.update_used();
+        board
     }
     pub fn uncover_empty(&mut self) {
         if self.get_empty_remaining() > u16::MIN {
@@ -301,6 +488,12 @@ impl Board {
             self.remaining.1 -= 1;
         }
     }
+    pub fn cover_empty(&mut self) {
+        self.remaining.0 += 1;
+    }
+    pub fn cover_number(&mut self) {
+        self.remaining.1 += 1;
+    }
     pub fn make_mistake(&mut self) {
         if self.mistakes < u16::MAX {
             self.mistakes += 1;
@@ -309,11 +502,189 @@ impl Board {
     pub fn is_solved(&self) -> bool {
         self.get_empty_remaining() == 0 && self.get_number_remaining() == 0
     }
+    /// Whether the mistake limit of the hardcore mode is exceeded
+    pub fn is_failed(&self) -> bool {
+        matches!(self.mode, PlayMode::Hardcore(limit) if self.mistakes > limit)
+    }
     pub fn get_max_points(&self) -> u16 {
-        ((self.get_hidden() as f32).powf(0.6) as u16).max(1).min(30)
+        max_points(self.get_hidden())
     }
     pub fn get_points(&self) -> u16 {
-        self.get_max_points().saturating_sub(self.mistakes)
+        self.get_max_points()
+            .saturating_sub(self.mistakes)
+            .saturating_sub(self.hints_used.saturating_mul(HINT_COST))
+    }
+    /// Shows a hint for a cell, every new hint costs points
+    pub fn use_hint(&mut self, x: usize, y: usize) {
+        self.show_hint(x, y);
+        self.hints_used = self.hints_used.saturating_add(1);
+    }
+    /// Shows the explanation of a cell without costing points
+    pub fn show_hint(&mut self, x: usize, y: usize) {
+        self.hint = Some((x, y));
+    }
+    pub fn clear_hint(&mut self) {
+        self.hint = None;
+    }
+    pub fn get_hint(&self) -> Option<(usize, usize)> {
+        self.hint
+    }
+    /// Sets the mark of a hidden cell, setting the same mark again removes it
+    pub fn toggle_mark(&mut self, x: usize, y: usize, mark: Mark) {
+        let current = &mut self.marks[y][x];
+        *current = match *current == Some(mark) {
+            true => None,
+            false => Some(mark),
+        };
+    }
+    pub fn set_uncovered(&mut self, x: usize, y: usize) {
+        self.uncovered[y][x] = true;
+        self.update_used();
+    }
+    pub fn set_covered(&mut self, x: usize, y: usize) {
+        self.uncovered[y][x] = false;
+        self.done_cells[y][x] = false;
+        self.update_used();
+    }
+    /// Checks for every number cell and column hint whether its constraint is used up
+    fn update_used(&mut self) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.used_cells[y][x] = match self.config.cells[y][x].0 {
+                    Some(CellType::NumberCell(_)) => self.is_used(ConstraintSource::Cell(x, y)),
+                    _ => false,
+                };
+            }
+        }
+        for i in 0..self.used_hints.len() {
+            self.used_hints[i] = self.is_used(ConstraintSource::Column(i));
+        }
+    }
+    /// A constraint is used up once its whole scope is uncovered or,
+    /// if dim_satisfied is set, once all of its blue cells are uncovered
+    fn is_used(&self, source: ConstraintSource) -> bool {
+        let scope = self.get_scope(source);
+        let known =
+            |(x, y): &&(usize, usize)| !self.config.cells[*y][*x].1 || self.uncovered[*y][*x];
+        let blue =
+            |(x, y): &&(usize, usize)| self.config.cells[*y][*x].0 == Some(CellType::EmptyCell);
+        if scope.iter().all(|pos| known(&pos)) {
+            return true;
+        }
+        self.dim_satisfied
+            && scope.iter().filter(blue).count() == scope.iter().filter(blue).filter(known).count()
+    }
+    pub fn get_used_cells(&self) -> &Vec<Vec<bool>> {
+        &self.used_cells
+    }
+    pub fn get_used_hints(&self) -> &Vec<bool> {
+        &self.used_hints
+    }
+    /// The state to save, so the level can be resumed
+    pub fn get_progress(&self, elapsed: f32) -> LevelProgress {
+        let positions = |grid: &Vec<Vec<bool>>| -> Vec<(usize, usize)> {
+            grid.iter()
+                .enumerate()
+                .flat_map(|(y, row)| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|(_, set)| **set)
+                        .map(move |(x, _)| (x, y))
+                })
+                .collect()
+        };
+        LevelProgress {
+            uncovered: positions(&self.uncovered),
+            mistakes: self.mistakes,
+            hints_used: self.hints_used,
+            elapsed,
+            marks: self
+                .marks
+                .iter()
+                .enumerate()
+                .flat_map(|(y, row)| {
+                    row.iter()
+                        .enumerate()
+                        .filter_map(move |(x, mark)| mark.map(|mark| ((x, y), mark)))
+                })
+                .collect(),
+            done_cells: positions(&self.done_cells),
+            done_hints: self
+                .done_hints
+                .iter()
+                .enumerate()
+                .filter(|(_, done)| **done)
+                .map(|(i, _)| i)
+                .collect(),
+            moves: self.moves.clone(),
+        }
+    }
+    pub fn record_move(&mut self, x: usize, y: usize, mistake: bool, time: f32) {
+        self.moves.push(Move {
+            x,
+            y,
+            mistake,
+            time,
+        });
+    }
+    pub fn get_moves(&self) -> &Vec<Move> {
+        &self.moves
+    }
+    /// Removes the last move, to hide the cell again
+    pub fn undo_move(&mut self) -> Option<Move> {
+        self.moves.pop()
+    }
+    pub fn get_mode(&self) -> PlayMode {
+        self.mode
+    }
+    pub fn clear_mark(&mut self, x: usize, y: usize) {
+        if self.marks[y][x].is_some() {
+            self.marks[y][x] = None;
+        }
+    }
+    pub fn get_marks(&self) -> &Vec<Vec<Option<Mark>>> {
+        &self.marks
+    }
+    pub fn toggle_done_cell(&mut self, x: usize, y: usize) {
+        self.done_cells[y][x] = !self.done_cells[y][x];
+    }
+    pub fn get_done_cells(&self) -> &Vec<Vec<bool>> {
+        &self.done_cells
+    }
+    pub fn toggle_done_hint(&mut self, i: usize) {
+        self.done_hints[i] = !self.done_hints[i];
+    }
+    pub fn get_done_hints(&self) -> &Vec<bool> {
+        &self.done_hints
+    }
+    pub fn toggle_pencil(&mut self) {
+        self.pencil = !self.pencil;
+    }
+    pub fn is_pencil(&self) -> bool {
+        self.pencil
+    }
+    /// Cells a number cell or column hint talks about, without the gaps of the board
+    pub fn get_scope(&self, source: ConstraintSource) -> Vec<(usize, usize)> {
+        let positions = match source {
+            ConstraintSource::Cell(x, y) => {
+                get_neighbour_positions(x as i32, y as i32, self.width, self.height)
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }
+            ConstraintSource::Column(i) => {
+                let hint = &self.config.hints[i];
+                get_column_positions(hint.x, hint.y, self.width, self.height, hint.dir)
+            }
+            ConstraintSource::Remaining => Vec::new(),
+        };
+        positions
+            .into_iter()
+            .filter(|(x, y)| self.config.cells[*y][*x].0.is_some())
+            .collect()
+    }
+    pub fn get_config(&self) -> &BoardConfig {
+        &self.config
     }
     pub fn get_empty_remaining(&self) -> u16 {
         self.remaining.0
@@ -324,13 +695,26 @@ impl Board {
     pub fn get_hidden(&self) -> u16 {
         self.hidden
     }
+    /// Hidden cells that are uncovered by now
+    pub fn count_uncovered(&self) -> u16 {
+        self.hidden
+            .saturating_sub(self.get_empty_remaining())
+            .saturating_sub(self.get_number_remaining())
+    }
     pub fn get_mistakes(&self) -> u16 {
         self.mistakes
     }
+    pub fn get_hints_used(&self) -> u16 {
+        self.hints_used
+    }
     pub fn get_stage_id(&self) -> u8 {
         self.stage_id
     }
     pub fn get_level_id(&self) -> u8 {
         self.level_id
     }
+    /// The board was generated, it is no level of the campaign or a pack
+    pub fn is_generated(&self) -> bool {
+        (self.stage_id, self.level_id) == GENERATED_IDS
+    }
 }
@@ -1,5 +1,11 @@
 use super::board::Board;
-use crate::{components::Cell, constants::RADIUS, enums::CellType, resources::GameColors};
+use crate::{
+    components::Cell,
+    constants::RADIUS,
+    enums::CellType,
+    functions::make_cell_interactable,
+    resources::{GameColors, Profile},
+};
 use bevy::{
     math::Vec3,
     prelude::{ColorMaterial, Commands, Component, Entity, Handle, Query, Visibility},
@@ -63,7 +69,7 @@ impl GameCell {
         color_query: &mut Query<&mut Handle<ColorMaterial>>,
         game_colors: &GameColors,
         number_cell: Option<&NumberCell>,
-        board: &mut Board,
+        (board, time): (&mut Board, f32),
     ) {
         self.hidden = false;
         // TODO: Uncover animation/particles
@@ -87,15 +93,72 @@ impl GameCell {
                 )
             }
         };
-        // TODO: Could break
-        // commands.entity(cell.entity).remove::<Interactable>();
-        interactable::remove_interactable(commands, cell.entity);
+        board.clear_mark(cell.x as usize, cell.y as usize);
+        board.set_uncovered(cell.x as usize, cell.y as usize);
+        board.record_move(cell.x as usize, cell.y as usize, false, time);
+        // Number cells stay interactable to be marked as done
+        if self.cell_type == CellType::EmptyCell {
+            // TODO: Could break
+            // commands.entity(cell.entity).remove::<Interactable>();
+            interactable::remove_interactable(commands, cell.entity);
+        }
         // Normal scale
         cell.click(commands, None, light, dark, color_query);
     }
 
-    /// Called when cell is hidden and clicked on with the wrong mouse button
-    pub fn uncover_fail(&self, cell: &Cell, commands: &mut Commands, board: &mut Board) {
+    /// Hides the cell again, to undo an uncover in the practice mode
+    pub fn cover(
+        &mut self,
+        cell: &mut Cell,
+        commands: &mut Commands,
+        color_query: &mut Query<&mut Handle<ColorMaterial>>,
+        game_colors: &GameColors,
+        number_cell: Option<&NumberCell>,
+        board: &mut Board,
+    ) {
+        self.hidden = true;
+        match self.cell_type {
+            CellType::NumberCell(_) => {
+                board.cover_number();
+                commands
+                    .entity(number_cell.unwrap().label)
+                    .remove::<Visibility>()
+                    .insert(Visibility { is_visible: false });
+            }
+            CellType::EmptyCell => {
+                board.cover_empty();
+                make_cell_interactable(commands, cell.entity, RADIUS);
+            }
+        }
+        board.set_covered(cell.x as usize, cell.y as usize);
+        cell.click(
+            commands,
+            None,
+            game_colors.yellow_light.clone(),
+            game_colors.yellow_medium.clone(),
+            color_query,
+        );
+    }
+
+    /// Called when cell is hidden and clicked on with the wrong mouse button.
+    /// The mistake is counted in the profile too, unless it's only replayed.
+    pub fn uncover_fail(
+        &self,
+        cell: &Cell,
+        commands: &mut Commands,
+        (board, profile): (&mut Board, Option<&mut Profile>),
+        time: f32,
+    ) {
+        self.shake(cell, commands);
+        board.make_mistake();
+        board.record_move(cell.x as usize, cell.y as usize, true, time);
+        if let Some(profile) = profile {
+            profile.record_mistake();
+        }
+    }
+
+    /// Shakes the cell to show that the click was wrong
+    pub fn shake(&self, cell: &Cell, commands: &mut Commands) {
         let mut t1 = cell.orig;
         let mut t2 = cell.orig;
         t1.translation += Vec3::new(-RADIUS / 10., -RADIUS / 20., 0.0);
@@ -124,7 +187,6 @@ impl GameCell {
                     },
                 ),
         );
-        board.make_mistake();
     }
 }
 
@@ -144,3 +206,40 @@ pub struct RemainingText;
 
 #[derive(Debug, Component)]
 pub struct MistakesText;
+
+#[derive(Debug, Component)]
+pub struct TimeText;
+
+/// Clicking it shows a hint, like the H key
+#[derive(Debug, Component)]
+pub struct HintButton;
+
+/// Explanation of the current hint
+#[derive(Debug, Component)]
+pub struct HintText;
+
+/// Highlights the cell of the current hint and the clues that prove it
+#[derive(Debug, Component)]
+pub struct HintMarker;
+
+/// Highlights a cell in the scope of the hovered number cell or column hint
+#[derive(Debug, Component)]
+pub struct ScopeMarker;
+
+/// Shows the pencil mark of a cell
+#[derive(Debug, Component)]
+pub struct PencilMark;
+
+/// Clicking it switches the annotation mode, like the space key
+#[derive(Debug, Component)]
+pub struct PencilButton;
+
+/// Clicking it undoes the last uncover in the practice mode, like the Z key
+#[derive(Debug, Component)]
+pub struct UndoButton;
+
+/// Sent when a wrong click is rejected in the practice mode
+pub struct RejectedMoveEvent {
+    pub x: usize,
+    pub y: usize,
+}
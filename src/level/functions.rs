@@ -0,0 +1,89 @@
+use super::{
+    board::Board,
+    components::{GameCell, HintMarker},
+};
+use crate::{
+    components::Cell,
+    resources::{CellMeshes, GameColors},
+    solver::{ConstraintSource, Deduction},
+};
+use bevy::{
+    hierarchy::BuildChildren,
+    math::Vec3,
+    prelude::{default, Assets, Color, ColorMaterial, Commands, Entity, Transform},
+    sprite::ColorMesh2dBundle,
+};
+
+/// Which cells are still hidden, as the solver expects them
+pub fn hidden_grid<'a>(
+    board: &Board,
+    cells: impl Iterator<Item = (&'a Cell, &'a GameCell)>,
+) -> Vec<Vec<bool>> {
+    let mut hidden = vec![vec![false; board.width]; board.height];
+    for (cell, gc) in cells {
+        hidden[cell.y as usize][cell.x as usize] = gc.hidden;
+    }
+    hidden
+}
+
+/// Explains why a cell must be blue or gray
+pub fn explain_deduction(deduction: &Deduction, get_string: impl Fn(&str) -> String) -> String {
+    let color = match deduction.empty {
+        true => get_string("hint-blue"),
+        false => get_string("hint-gray"),
+    };
+    let reason = if deduction.reasons.contains(&ConstraintSource::Remaining) {
+        get_string("hint-remaining")
+    } else if deduction.reasons.len() > 1 {
+        get_string("hint-pair")
+    } else {
+        get_string("hint-single")
+    };
+    format!("{} {}", color, reason)
+}
+
+/// Marks the deduced cell with a white outline and the clues that prove it with a yellow one
+pub fn spawn_hint_markers(
+    commands: &mut Commands,
+    deduction: &Deduction,
+    cells: &[(Entity, &Cell)],
+    board: &Board,
+    (cell_meshes, game_colors, colors): (&CellMeshes, &GameColors, &mut Assets<ColorMaterial>),
+) {
+    let find = |x: usize, y: usize| {
+        cells
+            .iter()
+            .find(|(_, cell)| (cell.x as usize, cell.y as usize) == (x, y))
+            .map(|(e, _)| *e)
+    };
+    let clue_color = colors.add(ColorMaterial::from(Color::rgba(0.894, 0.627, 0.125, 0.8)));
+    let mut marked = vec![(
+        find(deduction.x, deduction.y),
+        game_colors.white.clone(),
+        1.2,
+    )];
+    for reason in deduction.reasons.iter() {
+        match *reason {
+            ConstraintSource::Cell(x, y) => marked.push((find(x, y), clue_color.clone(), 1.2)),
+            ConstraintSource::Column(i) => {
+                marked.push((board.texts.get(i).copied(), clue_color.clone(), 0.5))
+            }
+            ConstraintSource::Remaining => {}
+        }
+    }
+    for (entity, color, scale) in marked {
+        if let Some(entity) = entity {
+            let marker = commands
+                .spawn_bundle(ColorMesh2dBundle {
+                    mesh: cell_meshes.std_hexagon_back.clone().into(),
+                    material: color,
+                    transform: Transform::from_xyz(0., 0., -1.)
+                        .with_scale(Vec3::new(scale, scale, 1.)),
+                    ..default()
+                })
+                .insert(HintMarker)
+                .id();
+            commands.entity(entity).add_child(marker);
+        }
+    }
+}
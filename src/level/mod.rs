@@ -1,9 +1,11 @@
-mod board;
-mod components;
+pub mod board;
+pub mod components;
+mod functions;
+mod resources;
 mod setup;
 mod systems;
 
-use self::{setup::setup, systems::*};
+use self::{components::RejectedMoveEvent, setup::setup, systems::*};
 use crate::{cleanup_system, components::RootComponent, states::AppState};
 use bevy::{
     app::App,
@@ -25,12 +27,41 @@ pub fn prepare_level(app: &mut App) {
                 )
                 .with_system(mouse_click_cell.after(mouse_enter_cell))
                 .with_system(mouse_click_hint.after(InteractLabel::Interact))
+                .with_system(scope_highlight_system.after(InteractLabel::Interact))
+                .with_system(hint_system.after(InteractLabel::Interact))
+                .with_system(hint_clear_system.after(mouse_click_cell))
+                .with_system(practice_feedback_system.after(mouse_click_cell))
+                .with_system(undo_system.after(InteractLabel::Interact))
+                .with_system(
+                    auto_reveal_system
+                        .after(mouse_click_cell)
+                        .after(undo_system)
+                        .before(autosave_system),
+                )
+                .with_system(pencil_toggle_system.after(InteractLabel::Interact))
+                .with_system(
+                    annotation_system
+                        .after(mouse_click_cell)
+                        .after(mouse_click_hint)
+                        .after(pencil_toggle_system),
+                )
                 .with_system(check_solved)
+                .with_system(run_summary_system)
+                .with_system(timer_system.before(check_solved))
+                .with_system(
+                    autosave_system
+                        .after(mouse_click_cell)
+                        .after(mouse_click_hint)
+                        .after(hint_system)
+                        .after(undo_system)
+                        .before(check_solved),
+                )
                 .with_system(window_resize_system)
                 .with_system(hotkey_system),
         )
         // TODO: In theory, on_in_stack_update should be perfect but it doesn't seem to work
         .add_system_set(SystemSet::on_inactive_update(STATE).with_system(window_resize_system))
         .add_system_set(SystemSet::on_pause(STATE).with_system(pause))
-        .add_system_set(SystemSet::on_exit(STATE).with_system(cleanup_system::<RootComponent>));
+        .add_system_set(SystemSet::on_exit(STATE).with_system(cleanup_system::<RootComponent>))
+        .add_event::<RejectedMoveEvent>();
 }
@@ -0,0 +1,25 @@
+use std::collections::VecDeque;
+
+/// Seconds between two cells uncovered by the auto-reveal assist
+pub const AUTO_REVEAL_DELAY: f32 = 0.12;
+
+/// Time spent in the level, only counts while the level is not paused
+#[derive(Default)]
+pub struct LevelTimer {
+    pub elapsed: f32,
+}
+
+/// Play time and uncovered cells of the level that are already in the statistics of the profile
+#[derive(Default)]
+pub struct CountedStats {
+    pub time: f32,
+    pub uncovered: u16,
+}
+
+/// Cells the auto-reveal assist is about to uncover, one after another
+#[derive(Default)]
+pub struct AutoReveal {
+    pub queue: VecDeque<(usize, usize)>,
+    /// Time until the next cell is uncovered
+    pub cooldown: f32,
+}
@@ -1,9 +1,15 @@
-use super::board::Board;
+use super::{
+    board::Board,
+    resources::{AutoReveal, CountedStats, LevelTimer},
+};
 use crate::{
     assets::LocaleAsset,
+    enums::PlayMode,
     functions::rescale_board,
     parser,
-    resources::{CellMeshes, GameColors, LoadState, LocaleAssets, Profile, TextSettings},
+    resources::{
+        Campaign, CellMeshes, GameColors, LoadState, LocaleAssets, Profile, Progress, TextSettings,
+    },
 };
 use bevy::{
     prelude::{Assets, Commands, Mesh, Res, ResMut, Transform},
@@ -15,7 +21,7 @@ type StandardResources<'a> = (
     Res<'a, CellMeshes>,
     Res<'a, GameColors>,
     Res<'a, LocaleAssets>,
-    Res<'a, Profile>,
+    ResMut<'a, Profile>,
     Res<'a, TextSettings>,
 );
 type StandardAssets<'a> = (
@@ -26,14 +32,35 @@ type StandardAssets<'a> = (
 pub fn setup(
     mut commands: Commands,
     wnds: Res<Windows>,
-    (cell_meshes, game_colors, locale, profile, text_settings): StandardResources,
+    (cell_meshes, game_colors, locale, mut profile, text_settings): StandardResources,
     load_state: ResMut<LoadState>,
     (mut meshes, mut colors, locales): StandardAssets,
+    (mut progress, campaign): (ResMut<Progress>, Res<Campaign>),
 ) {
-    if load_state.filename.is_none() {
-        panic!("No level specified.");
+    let config = match (&load_state.generated, &load_state.filename) {
+        (Some((_, config)), _) => config.clone(),
+        (None, Some(filename)) => parser::board_from_file(filename),
+        (None, None) => panic!("No level specified."),
+    };
+    let ids = load_state.ids.unwrap();
+    let mode = load_state.mode;
+    // Generated boards are played in one go, they are not part of the progress
+    let saved = mode == PlayMode::Normal && load_state.generated.is_none();
+    // Resume the level where it was left, other runs always start from scratch
+    let id = campaign.get_id(ids.0, ids.1).filter(|_| saved);
+    let level_progress = id
+        .and_then(|id| progress.levels.get(id))
+        .filter(|level_progress| level_progress.fits(&config))
+        .cloned();
+    if let Some(id) = id {
+        progress.last = Some(id.to_string());
+    }
+    // Every run that is not resumed is a new attempt, practice runs aside
+    if level_progress.is_none() && mode != PlayMode::Practice {
+        if let Some(id) = campaign.get_id(ids.0, ids.1) {
+            profile.record_attempt(id);
+        }
     }
-    let config = parser::board_from_file(load_state.filename.as_ref().unwrap());
 
     let mut root_transform = Transform::identity();
     for wnd in wnds.iter() {
@@ -57,9 +84,18 @@ pub fn setup(
             &profile,
             &text_settings,
         ),
-        load_state.ids.unwrap(),
+        ids,
         (&mut meshes, &mut colors, &locales),
+        (level_progress.as_ref(), mode),
     );
 
+    let elapsed = level_progress.map_or(0., |p| p.elapsed);
+    // A resumed level was counted up to where it was left
+    commands.insert_resource(CountedStats {
+        time: elapsed,
+        uncovered: board.count_uncovered(),
+    });
     commands.insert_resource(board);
+    commands.insert_resource(LevelTimer { elapsed });
+    commands.insert_resource(AutoReveal::default());
 }
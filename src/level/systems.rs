@@ -1,26 +1,44 @@
 use super::{
     board::Board,
-    components::{EmptyCell, GameCell, MistakesText, NumberCell, RemainingText},
+    components::{
+        EmptyCell, GameCell, HintButton, HintMarker, HintText, MistakesText, NumberCell,
+        PencilButton, PencilMark, RejectedMoveEvent, RemainingText, ScopeMarker, TimeText,
+        UndoButton,
+    },
+    functions::{explain_deduction, hidden_grid, spawn_hint_markers},
+    resources::{AutoReveal, CountedStats, LevelTimer, AUTO_REVEAL_DELAY},
 };
 use crate::{
+    assets::LocaleAsset,
     components::{Cell, ColumnHint, RootComponent},
-    functions::rescale_board,
+    constants::{GameColor, Z_INDEX_CELL_INNER},
+    enums::{Generated, Mark, PlayMode},
+    functions::{format_time, rescale_board},
     overlay::resources::{OverlaySettings, OverlayType},
-    resources::{GameColors, Profile, SfxAssets, TextSettings},
+    resources::{
+        Campaign, CellMeshes, EndlessRun, GameColors, LoadState, LocaleAssets, Profile, Progress,
+        Replays, SfxAssets, TextSettings,
+    },
+    solver::{ConstraintSource, Solver},
     states::AppState,
+    structs::{DailyResult, LevelProgress},
 };
 use bevy::{
-    hierarchy::Children,
+    hierarchy::{BuildChildren, Children, DespawnRecursiveExt},
     input::Input,
+    math::Vec3,
     prelude::{
-        ColorMaterial, Commands, EventReader, Handle, KeyCode, ParamSet, Query, Res, ResMut, State,
-        Transform, Visibility, With, Without,
+        default, Assets, Color, ColorMaterial, Commands, DetectChanges, Entity, EventReader,
+        EventWriter, Handle, KeyCode, Local, ParamSet, Query, Res, ResMut, State, Transform,
+        Visibility, With, Without,
     },
-    text::Text,
+    sprite::ColorMesh2dBundle,
+    text::{Text, TextAlignment},
+    time::Time,
     window::WindowResized,
 };
 use bevy_kira_audio::{Audio, AudioControl};
-use interactable::components::{Entered, Exited, ReleasedLeft, ReleasedRight};
+use interactable::components::{Entered, Exited, Hovered, ReleasedLeft, ReleasedRight};
 
 type McNumberCell<'a> = (
     &'a mut GameCell,
@@ -35,48 +53,107 @@ type McEmptyCell<'a> = (
     Option<&'a ReleasedLeft>,
     Option<&'a ReleasedRight>,
 );
-/// Calls uncover on a cell that is clicked by the mouse
+/// Buttons and clues that were just clicked
+type Clicked<T> = (With<T>, With<ReleasedLeft>);
+/// Clues under the cursor
+type Hovering<T> = (With<T>, With<Hovered>);
+/// Cells the markers of a hint are placed on
+type HintCell<'a> = (Entity, &'a Cell, &'a GameCell);
+/// The cells, the markers of a hint and its explanation
+type HintQueries<'w, 's> = (
+    Query<'w, 's, HintCell<'static>>,
+    Query<'w, 's, Entity, With<HintMarker>>,
+    Query<'w, 's, &'static mut Text, With<HintText>>,
+);
+/// Calls uncover on a cell that is clicked by the mouse.
+/// In the annotation mode or while shift is held, clicks set pencil marks on hidden cells
+/// and mark uncovered number cells as done instead.
+/// In the practice mode, wrong clicks are rejected instead of counting as mistakes.
 pub fn mouse_click_cell(
     mut commands: Commands,
     mut number_cell_query: Query<McNumberCell, Without<EmptyCell>>,
     mut empty_cell_query: Query<McEmptyCell, With<EmptyCell>>,
     mut color_query: Query<&mut Handle<ColorMaterial>>,
-    (game_colors, profile): (Res<GameColors>, Res<Profile>),
-    mut board: ResMut<Board>,
+    (game_colors, mut profile, keys): (Res<GameColors>, ResMut<Profile>, Res<Input<KeyCode>>),
+    (mut board, timer): (ResMut<Board>, Res<LevelTimer>),
+    mut ev_rejected: EventWriter<RejectedMoveEvent>,
 ) {
+    let pencil = board.is_pencil() || keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let practice = board.get_mode() == PlayMode::Practice;
+    // The buttons that uncover blue and gray cells
+    let inverted = profile.mouse_inverted;
+    let blue = |left: bool, right: bool| left && !inverted || right && inverted;
+    let gray = |left: bool, right: bool| left && inverted || right && !inverted;
     for (mut lc, mut cell, nc, left, right) in number_cell_query.iter_mut() {
-        let fail =
-            left.is_some() && !profile.mouse_inverted || right.is_some() && profile.mouse_inverted;
-        let ok =
-            left.is_some() && profile.mouse_inverted || right.is_some() && !profile.mouse_inverted;
-        if fail {
-            lc.uncover_fail(&cell, &mut commands, &mut board);
-        } else if ok {
+        let (left, right) = (left.is_some(), right.is_some());
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        if !lc.hidden {
+            if pencil && (left || right) {
+                board.toggle_done_cell(x, y);
+            }
+            continue;
+        }
+        if pencil {
+            if blue(left, right) {
+                board.toggle_mark(x, y, Mark::Blue);
+            } else if gray(left, right) {
+                board.toggle_mark(x, y, Mark::Gray);
+            }
+        } else if blue(left, right) {
+            if practice {
+                lc.shake(&cell, &mut commands);
+                ev_rejected.send(RejectedMoveEvent { x, y });
+            } else {
+                lc.uncover_fail(
+                    &cell,
+                    &mut commands,
+                    (&mut board, Some(&mut *profile)),
+                    timer.elapsed,
+                );
+            }
+        } else if gray(left, right) {
             lc.uncover(
                 &mut cell,
                 &mut commands,
                 &mut color_query,
                 game_colors.as_ref(),
                 Some(nc),
-                &mut board,
+                (&mut board, timer.elapsed),
             );
         }
     }
     for (mut lc, mut cell, left, right) in empty_cell_query.iter_mut() {
-        let fail =
-            right.is_some() && !profile.mouse_inverted || left.is_some() && profile.mouse_inverted;
-        let ok =
-            right.is_some() && profile.mouse_inverted || left.is_some() && !profile.mouse_inverted;
-        if fail {
-            lc.uncover_fail(&cell, &mut commands, &mut board);
-        } else if ok {
+        let (left, right) = (left.is_some(), right.is_some());
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        if !lc.hidden {
+            continue;
+        }
+        if pencil {
+            if blue(left, right) {
+                board.toggle_mark(x, y, Mark::Blue);
+            } else if gray(left, right) {
+                board.toggle_mark(x, y, Mark::Gray);
+            }
+        } else if gray(left, right) {
+            if practice {
+                lc.shake(&cell, &mut commands);
+                ev_rejected.send(RejectedMoveEvent { x, y });
+            } else {
+                lc.uncover_fail(
+                    &cell,
+                    &mut commands,
+                    (&mut board, Some(&mut *profile)),
+                    timer.elapsed,
+                );
+            }
+        } else if blue(left, right) {
             lc.uncover(
                 &mut cell,
                 &mut commands,
                 &mut color_query,
                 game_colors.as_ref(),
                 None,
-                &mut board,
+                (&mut board, timer.elapsed),
             );
         }
     }
@@ -91,6 +168,10 @@ pub fn mouse_enter_cell(
     (audio, sfx_assets, profile): (Res<Audio>, Res<SfxAssets>, Res<Profile>),
 ) {
     for (lc, mut cell) in cell_query.iter_mut() {
+        // Uncovered number cells are only interactable to be marked as done
+        if !lc.hidden {
+            continue;
+        }
         audio
             .play(sfx_assets.sfx_hover.clone())
             .with_volume(profile.sfx_volume as f64);
@@ -110,11 +191,69 @@ pub fn mouse_exit_cell(
     }
 }
 
+/// Highlights the neighbours of a hovered number cell or the column of a hovered column hint
+pub fn scope_highlight_system(
+    mut commands: Commands,
+    cell_query: Query<(Entity, &Cell)>,
+    number_query: Query<(&Cell, &GameCell), Hovering<NumberCell>>,
+    hint_query: Query<Entity, Hovering<ColumnHint>>,
+    marker_query: Query<Entity, With<ScopeMarker>>,
+    (cell_meshes, game_colors, board): (Res<CellMeshes>, Res<GameColors>, Res<Board>),
+    mut shown: Local<Option<ConstraintSource>>,
+) {
+    let hovered = number_query
+        .iter()
+        .find(|(_, gc)| !gc.hidden)
+        .map(|(cell, _)| ConstraintSource::Cell(cell.x as usize, cell.y as usize))
+        .or_else(|| {
+            hint_query
+                .iter()
+                .find_map(|entity| board.texts.iter().position(|e| *e == entity))
+                .map(ConstraintSource::Column)
+        });
+    if *shown == hovered {
+        return;
+    }
+    for e in marker_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+    if let Some(source) = hovered {
+        let scope = board.get_scope(source);
+        for (entity, cell) in cell_query.iter() {
+            if !scope.contains(&(cell.x as usize, cell.y as usize)) {
+                continue;
+            }
+            let marker = commands
+                .spawn_bundle(ColorMesh2dBundle {
+                    mesh: cell_meshes.std_hexagon_back.clone().into(),
+                    material: game_colors.highlight.clone(),
+                    transform: Transform::from_xyz(0., 0., -0.5)
+                        .with_scale(Vec3::new(1.1, 1.1, 1.)),
+                    ..default()
+                })
+                .insert(ScopeMarker)
+                .id();
+            commands.entity(entity).add_child(marker);
+        }
+    }
+    *shown = hovered;
+}
+
+/// Shows the guide line of a clicked column hint, or marks it as done while annotating
 pub fn mouse_click_hint(
-    hint_query: Query<&Children, (With<ColumnHint>, With<ReleasedLeft>)>,
+    hint_query: Query<(Entity, &Children), Clicked<ColumnHint>>,
     mut hint_line_query: Query<&mut Visibility>,
+    keys: Res<Input<KeyCode>>,
+    mut board: ResMut<Board>,
 ) {
-    for hint in hint_query.iter() {
+    let pencil = board.is_pencil() || keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    for (entity, hint) in hint_query.iter() {
+        if pencil {
+            if let Some(i) = board.texts.iter().position(|e| *e == entity) {
+                board.toggle_done_hint(i);
+            }
+            continue;
+        }
         for line in hint.iter() {
             if let Ok(mut visibility) = hint_line_query.get_mut(*line) {
                 visibility.is_visible = !visibility.is_visible;
@@ -161,7 +300,13 @@ pub fn check_solved(
     text_settings: Res<TextSettings>,
     mut app_state: ResMut<State<AppState>>,
     mut overlay_settings: ResMut<OverlaySettings>,
-    mut profile: ResMut<Profile>,
+    (mut profile, mut progress, mut replays): (ResMut<Profile>, ResMut<Progress>, ResMut<Replays>),
+    (timer, campaign, load_state, mut run): (
+        Res<LevelTimer>,
+        Res<Campaign>,
+        Res<LoadState>,
+        ResMut<EndlessRun>,
+    ),
 ) {
     if board.is_changed() {
         if let Ok(mut text) = text_set.p0().get_single_mut() {
@@ -171,17 +316,66 @@ pub fn check_solved(
             );
         }
         if let Ok(mut text) = text_set.p1().get_single_mut() {
+            let mistakes = match board.get_mode() {
+                PlayMode::Hardcore(limit) => format!("{} / {}", board.get_mistakes(), limit),
+                _ => board.get_mistakes().to_string(),
+            };
             *text = Text::from_section(
-                format!("{}: {}", "Mistakes", board.get_mistakes()),
+                format!("{}: {}", "Mistakes", mistakes),
                 text_settings.style_cell.clone(),
             );
         }
-        if board.is_solved() {
-            profile.update_point(
-                board.get_points(),
-                board.get_stage_id(),
-                board.get_level_id(),
-            );
+        if board.is_failed() {
+            overlay_settings.stage_id = board.get_stage_id();
+            overlay_settings.level_id = board.get_level_id();
+            overlay_settings.max_points = board.get_max_points();
+            overlay_settings.points = 0;
+            overlay_settings.mistakes = board.get_mistakes();
+            overlay_settings.time = timer.elapsed;
+            overlay_settings.overlay_type = OverlayType::LevelFailed;
+            app_state.push(AppState::Overlay).unwrap();
+        } else if board.is_solved() {
+            let id = campaign.get_id(board.get_stage_id(), board.get_level_id());
+            // Practice runs don't count
+            if board.get_mode() != PlayMode::Practice {
+                if let Some(id) = id {
+                    profile.record_solve(
+                        id,
+                        board.get_mode(),
+                        (board.get_points(), board.get_mistakes(), timer.elapsed),
+                    );
+                }
+                profile.record_solve_details(
+                    board.get_hidden(),
+                    board.get_hints_used(),
+                    board.get_mistakes(),
+                );
+                if let Some((Generated::Daily(day), _)) = load_state.generated {
+                    profile.record_daily(
+                        day,
+                        DailyResult {
+                            points: board.get_points(),
+                            max_points: board.get_max_points(),
+                            mistakes: board.get_mistakes(),
+                            time: timer.elapsed,
+                        },
+                    );
+                }
+                if let Some((Generated::Endless(_), _)) = load_state.generated {
+                    run.record_solve((board.get_points(), board.get_mistakes(), timer.elapsed));
+                    profile.record_endless(&run);
+                }
+                // A generated board can't be loaded again to watch the replay
+                if let Some(id) = id.filter(|_| !board.is_generated()) {
+                    replays
+                        .levels
+                        .insert(id.to_string(), board.get_moves().clone());
+                }
+            }
+            // A solved level starts from scratch next time
+            if let Some(id) = id.filter(|_| board.get_mode() == PlayMode::Normal) {
+                progress.levels.remove(id);
+            }
             // switch_state(
             //     Some(AppState::LevelSelection),
             //     &mut app_state,
@@ -192,19 +386,433 @@ pub fn check_solved(
             overlay_settings.max_points = board.get_max_points();
             overlay_settings.points = board.get_points();
             overlay_settings.mistakes = board.get_mistakes();
+            overlay_settings.time = timer.elapsed;
             overlay_settings.overlay_type = OverlayType::LevelComplete;
             app_state.push(AppState::Overlay).unwrap();
         }
     }
 }
 
+/// Shows the summary of an endless run once the player ended it in the overlay
+pub fn run_summary_system(
+    (mut run, load_state): (ResMut<EndlessRun>, Res<LoadState>),
+    mut app_state: ResMut<State<AppState>>,
+    mut overlay_settings: ResMut<OverlaySettings>,
+) {
+    if !run.ended || !matches!(load_state.generated, Some((Generated::Endless(_), _))) {
+        return;
+    }
+    run.ended = false;
+    overlay_settings.max_points = 0;
+    overlay_settings.overlay_type = OverlayType::RunSummary;
+    app_state.push(AppState::Overlay).unwrap();
+}
+
+/// Shows a hint on pressing H or clicking the hint button: a cell that must be blue or gray,
+/// the clues that prove it and an explanation. Only a new hint costs points.
+pub fn hint_system(
+    mut commands: Commands,
+    (keys, button_query): (Res<Input<KeyCode>>, Query<(), Clicked<HintButton>>),
+    (cell_query, mut text_query): (Query<HintCell>, Query<&mut Text, With<HintText>>),
+    (cell_meshes, game_colors, text_settings): (
+        Res<CellMeshes>,
+        Res<GameColors>,
+        Res<TextSettings>,
+    ),
+    (locale, locales, profile): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>, Res<Profile>),
+    mut colors: ResMut<Assets<ColorMaterial>>,
+    mut board: ResMut<Board>,
+) {
+    if (!keys.just_pressed(KeyCode::H) && button_query.is_empty()) || board.get_hint().is_some() {
+        return;
+    }
+    let hidden = hidden_grid(&board, cell_query.iter().map(|(_, cell, gc)| (cell, gc)));
+    let deduction = Solver::with_hidden(board.get_config(), hidden)
+        .next_deductions()
+        .into_iter()
+        .next();
+    let get_string = |key: &str| {
+        locale
+            .get_string(key, &locales, &profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone()
+    };
+    let explanation = match &deduction {
+        Some(d) => explain_deduction(d, get_string),
+        None => get_string("hint-none"),
+    };
+    if let Ok(mut text) = text_query.get_single_mut() {
+        *text = Text::from_section(explanation, text_settings.style_cell.clone())
+            .with_alignment(TextAlignment::BOTTOM_LEFT);
+    }
+    let d = match deduction {
+        Some(d) => d,
+        None => return,
+    };
+    board.use_hint(d.x, d.y);
+    let cells: Vec<(Entity, &Cell)> = cell_query.iter().map(|(e, cell, _)| (e, cell)).collect();
+    spawn_hint_markers(
+        &mut commands,
+        &d,
+        &cells,
+        &board,
+        (&cell_meshes, &game_colors, &mut colors),
+    );
+}
+
+/// Explains why a click rejected in the practice mode was wrong.
+/// If the cell can be proven already, the proof is shown like a hint.
+pub fn practice_feedback_system(
+    mut commands: Commands,
+    mut ev_rejected: EventReader<RejectedMoveEvent>,
+    (cell_query, marker_query, mut text_query): HintQueries,
+    (cell_meshes, game_colors, text_settings): (
+        Res<CellMeshes>,
+        Res<GameColors>,
+        Res<TextSettings>,
+    ),
+    (locale, locales, profile): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>, Res<Profile>),
+    mut colors: ResMut<Assets<ColorMaterial>>,
+    mut board: ResMut<Board>,
+) {
+    let (x, y) = match ev_rejected.iter().last() {
+        Some(ev) => (ev.x, ev.y),
+        None => return,
+    };
+    // Replaces the current hint
+    board.clear_hint();
+    for e in marker_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+    let hidden = hidden_grid(&board, cell_query.iter().map(|(_, cell, gc)| (cell, gc)));
+    let deduction = Solver::with_hidden(board.get_config(), hidden)
+        .next_deductions()
+        .into_iter()
+        .find(|d| (d.x, d.y) == (x, y));
+    let get_string = |key: &str| {
+        locale
+            .get_string(key, &locales, &profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone()
+    };
+    let explanation = match &deduction {
+        Some(d) => format!(
+            "{} {}",
+            get_string("practice-wrong"),
+            explain_deduction(d, get_string)
+        ),
+        None => get_string("practice-unproven"),
+    };
+    if let Ok(mut text) = text_query.get_single_mut() {
+        *text = Text::from_section(explanation, text_settings.style_cell.clone())
+            .with_alignment(TextAlignment::BOTTOM_LEFT);
+    }
+    if let Some(d) = deduction {
+        board.show_hint(d.x, d.y);
+        let cells: Vec<(Entity, &Cell)> = cell_query.iter().map(|(e, cell, _)| (e, cell)).collect();
+        spawn_hint_markers(
+            &mut commands,
+            &d,
+            &cells,
+            &board,
+            (&cell_meshes, &game_colors, &mut colors),
+        );
+    }
+}
+
+/// Hides the last uncovered cell again on pressing Z or clicking the undo button,
+/// only in the practice mode
+pub fn undo_system(
+    mut commands: Commands,
+    (keys, button_query): (Res<Input<KeyCode>>, Query<(), Clicked<UndoButton>>),
+    mut cell_query: Query<(&mut GameCell, &mut Cell, Option<&NumberCell>)>,
+    mut color_query: Query<&mut Handle<ColorMaterial>>,
+    game_colors: Res<GameColors>,
+    mut board: ResMut<Board>,
+) {
+    if board.get_mode() != PlayMode::Practice
+        || (!keys.just_pressed(KeyCode::Z) && button_query.is_empty())
+    {
+        return;
+    }
+    let m = match board.undo_move() {
+        Some(m) => m,
+        None => return,
+    };
+    if let Some((mut gc, mut cell, nc)) = cell_query
+        .iter_mut()
+        .find(|(_, cell, _)| (cell.x as usize, cell.y as usize) == (m.x, m.y))
+    {
+        gc.cover(
+            &mut cell,
+            &mut commands,
+            &mut color_query,
+            &game_colors,
+            nc,
+            &mut board,
+        );
+    }
+}
+
+/// Uncovers trivially forced cells one after another if the assist is enabled.
+/// Practice runs are left alone, finding the deductions is the point there.
+pub fn auto_reveal_system(
+    mut commands: Commands,
+    mut cell_query: Query<(&mut GameCell, &mut Cell, Option<&NumberCell>)>,
+    mut color_query: Query<&mut Handle<ColorMaterial>>,
+    (game_colors, profile, time): (Res<GameColors>, Res<Profile>, Res<Time>),
+    (mut board, timer, mut auto_reveal): (ResMut<Board>, Res<LevelTimer>, ResMut<AutoReveal>),
+) {
+    if !profile.auto_reveal
+        || board.get_mode() == PlayMode::Practice
+        || board.is_solved()
+        || board.is_failed()
+    {
+        return;
+    }
+    // Changes made by this system are not seen by is_changed in the next frame
+    let mut check = board.is_changed();
+    if !auto_reveal.queue.is_empty() {
+        auto_reveal.cooldown -= time.delta_seconds();
+        if auto_reveal.cooldown > 0. {
+            return;
+        }
+        auto_reveal.cooldown = AUTO_REVEAL_DELAY;
+        // The player may have uncovered some of the cells in the meantime
+        while let Some((x, y)) = auto_reveal.queue.pop_front() {
+            if let Some((mut gc, mut cell, nc)) = cell_query
+                .iter_mut()
+                .find(|(gc, cell, _)| gc.hidden && (cell.x as usize, cell.y as usize) == (x, y))
+            {
+                gc.uncover(
+                    &mut cell,
+                    &mut commands,
+                    &mut color_query,
+                    &game_colors,
+                    nc,
+                    (&mut board, timer.elapsed),
+                );
+                check = true;
+                break;
+            }
+        }
+    }
+    if check && auto_reveal.queue.is_empty() && !board.is_solved() {
+        let hidden = hidden_grid(&board, cell_query.iter().map(|(gc, cell, _)| (cell, gc)));
+        auto_reveal.queue = Solver::with_hidden(board.get_config(), hidden)
+            .trivial_deductions()
+            .into_iter()
+            .map(|d| (d.x, d.y))
+            .collect();
+        auto_reveal.cooldown = AUTO_REVEAL_DELAY;
+    }
+}
+
+/// Switches the annotation mode on pressing space or clicking the pencil button
+pub fn pencil_toggle_system(
+    keys: Res<Input<KeyCode>>,
+    button_query: Query<(), (With<PencilButton>, With<ReleasedLeft>)>,
+    mut board: ResMut<Board>,
+) {
+    if keys.just_pressed(KeyCode::Space) || !button_query.is_empty() {
+        board.toggle_pencil();
+    }
+}
+
+/// Pencil marks, done or used up cells, done or used up column hints and
+/// whether the annotation mode is active
+type Annotations = (Vec<Vec<Option<Mark>>>, Vec<Vec<bool>>, Vec<bool>, bool);
+/// Cells that can carry a pencil mark or be dimmed
+type AnnotatedCell<'a> = (Entity, &'a Cell, Option<&'a NumberCell>);
+
+/// Draws the pencil marks, dims the clues marked as done or used up and
+/// highlights the pencil button while annotating
+pub fn annotation_system(
+    mut commands: Commands,
+    (cell_query, mark_query): (Query<AnnotatedCell>, Query<Entity, With<PencilMark>>),
+    (mut text_query, button_query): (Query<&mut Text>, Query<Entity, With<PencilButton>>),
+    (cell_meshes, game_colors): (Res<CellMeshes>, Res<GameColors>),
+    board: Res<Board>,
+    mut shown: Local<Option<Annotations>>,
+) {
+    // Clues are dimmed when marked as done or once they are used up
+    let dim_cells: Vec<Vec<bool>> = board
+        .get_done_cells()
+        .iter()
+        .zip(board.get_used_cells())
+        .map(|(done, used)| done.iter().zip(used).map(|(d, u)| *d || *u).collect())
+        .collect();
+    let dim_hints: Vec<bool> = board
+        .get_done_hints()
+        .iter()
+        .zip(board.get_used_hints())
+        .map(|(d, u)| *d || *u)
+        .collect();
+    let current = Some((
+        board.get_marks().clone(),
+        dim_cells.clone(),
+        dim_hints.clone(),
+        board.is_pencil(),
+    ));
+    if *shown == current {
+        return;
+    }
+    let set_alpha = |text: &mut Text, alpha: f32| {
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    };
+    let alpha = |done: bool| match done {
+        true => 0.3,
+        false => 1.,
+    };
+
+    for e in mark_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+    for (entity, cell, nc) in cell_query.iter() {
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        if let Some(mark) = board.get_marks()[y][x] {
+            let color = match mark {
+                Mark::Blue => game_colors.blue_light.clone(),
+                Mark::Gray => game_colors.gray_light.clone(),
+            };
+            let marker = commands
+                .spawn_bundle(ColorMesh2dBundle {
+                    mesh: cell_meshes.std_hexagon_inner.clone().into(),
+                    material: color,
+                    transform: Transform::from_xyz(0., 0., Z_INDEX_CELL_INNER + 1.)
+                        .with_scale(Vec3::new(0.35, 0.35, 1.)),
+                    ..default()
+                })
+                .insert(PencilMark)
+                .id();
+            commands.entity(entity).add_child(marker);
+        }
+        if let Some(nc) = nc {
+            if let Ok(mut text) = text_query.get_mut(nc.label) {
+                set_alpha(&mut text, alpha(dim_cells[y][x]));
+            }
+        }
+    }
+    for (entity, done) in board.texts.iter().zip(dim_hints.iter()) {
+        if let Ok(mut text) = text_query.get_mut(*entity) {
+            set_alpha(&mut text, alpha(*done));
+        }
+    }
+    if let Ok(button) = button_query.get_single() {
+        if let Ok(mut text) = text_query.get_mut(button) {
+            for section in text.sections.iter_mut() {
+                section.style.color = match board.is_pencil() {
+                    true => GameColor::YELLOW_LIGHT,
+                    false => Color::WHITE,
+                };
+            }
+        }
+    }
+    *shown = current;
+}
+
+/// Removes the hint once its cell is uncovered
+pub fn hint_clear_system(
+    mut commands: Commands,
+    cell_query: Query<(&Cell, &GameCell)>,
+    marker_query: Query<Entity, With<HintMarker>>,
+    mut text_query: Query<&mut Text, With<HintText>>,
+    mut board: ResMut<Board>,
+) {
+    let (x, y) = match board.get_hint() {
+        Some(pos) => pos,
+        None => return,
+    };
+    let uncovered = cell_query
+        .iter()
+        .any(|(cell, gc)| (cell.x as usize, cell.y as usize) == (x, y) && !gc.hidden);
+    if !uncovered {
+        return;
+    }
+    board.clear_hint();
+    for e in marker_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        *text = Text::default();
+    }
+}
+
 pub fn pause(
     mut commands: Commands,
     mut cell_query: Query<(&GameCell, &mut Cell)>,
     mut color_query: Query<&mut Handle<ColorMaterial>>,
     game_colors: Res<GameColors>,
+    (board, timer, mut progress): (Res<Board>, Res<LevelTimer>, ResMut<Progress>),
+    (mut counted, mut profile, campaign): (ResMut<CountedStats>, ResMut<Profile>, Res<Campaign>),
 ) {
     for (gc, mut c) in cell_query.iter_mut() {
         gc.unhover(&mut c, &mut commands, &mut color_query, &game_colors);
     }
+    // Saves the elapsed time, every way out of a level goes through the overlay
+    if !board.is_solved() {
+        save_progress(&board, &timer, (&campaign, &mut progress));
+    }
+    // Adds what was played since the last pause to the statistics
+    let uncovered = board.count_uncovered();
+    profile.record_play(
+        timer.elapsed - counted.time,
+        uncovered.saturating_sub(counted.uncovered),
+    );
+    counted.time = timer.elapsed;
+    counted.uncovered = uncovered;
+}
+
+fn save_progress(
+    board: &Board,
+    timer: &LevelTimer,
+    (campaign, progress): (&Campaign, &mut Progress),
+) {
+    // Practice runs and generated boards are not resumed
+    if board.get_mode() != PlayMode::Normal || board.is_generated() {
+        return;
+    }
+    if let Some(id) = campaign.get_id(board.get_stage_id(), board.get_level_id()) {
+        progress
+            .levels
+            .insert(id.to_string(), board.get_progress(timer.elapsed));
+    }
+}
+
+/// Counts the time spent in the level and shows it
+pub fn timer_system(
+    time: Res<Time>,
+    mut timer: ResMut<LevelTimer>,
+    board: Res<Board>,
+    mut text_query: Query<&mut Text, With<TimeText>>,
+) {
+    // The clock stops with the last uncovered cell
+    if board.is_solved() {
+        return;
+    }
+    timer.elapsed += time.delta_seconds();
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = format!("{}: {}", "Time", format_time(timer.elapsed));
+    }
+}
+
+/// Saves the progress whenever a cell is uncovered, a mistake is made or
+/// the annotations change
+pub fn autosave_system(
+    board: Res<Board>,
+    timer: Res<LevelTimer>,
+    (campaign, mut progress): (Res<Campaign>, ResMut<Progress>),
+    mut saved: Local<Option<LevelProgress>>,
+) {
+    if !board.is_changed() || board.is_solved() {
+        return;
+    }
+    // The elapsed time alone is not worth writing the file
+    let current = board.get_progress(0.);
+    if saved.as_ref() != Some(&current) {
+        save_progress(&board, &timer, (&campaign, &mut progress));
+        *saved = Some(current);
+    }
 }
@@ -1,7 +1,8 @@
 use crate::{
     components::Cell,
-    resources::{GameColors, LoadState, Profile},
+    resources::{Campaign, GameColors, LoadState, Profile, Replays},
     states::AppState,
+    structs::{LevelEntry, StageEntry},
 };
 use bevy::{
     prelude::{Commands, Component, Handle, Query, ResMut, State},
@@ -12,6 +13,8 @@ use bevy::{
 pub struct LevelSelectionCell {
     pub stage: u8,
     pub level: u8,
+    /// Id of the level in the campaign manifest
+    pub id: String,
 }
 
 impl LevelSelectionCell {
@@ -23,7 +26,7 @@ impl LevelSelectionCell {
         game_colors: &GameColors,
         profile: &Profile,
     ) {
-        let (c1, c2) = if profile.level_points[self.stage as usize][self.level as usize].is_some() {
+        let (c1, c2) = if profile.get_level_points(&self.id).is_some() {
             (
                 game_colors.blue_medium.clone(),
                 game_colors.blue_dark.clone(),
@@ -45,7 +48,7 @@ impl LevelSelectionCell {
         game_colors: &GameColors,
         profile: &Profile,
     ) {
-        let (c1, c2) = if profile.level_points[self.stage as usize][self.level as usize].is_some() {
+        let (c1, c2) = if profile.get_level_points(&self.id).is_some() {
             (
                 game_colors.blue_light.clone(),
                 game_colors.blue_medium.clone(),
@@ -64,7 +67,7 @@ impl LevelSelectionCell {
         cell: &mut Cell,
         commands: &mut Commands,
         color_query: &mut Query<&mut Handle<ColorMaterial>>,
-        game_colors: &GameColors,
+        (game_colors, campaign): (&GameColors, &Campaign),
         app_state: &mut ResMut<State<AppState>>,
         load_state: &mut ResMut<LoadState>,
     ) {
@@ -75,29 +78,50 @@ impl LevelSelectionCell {
             game_colors.blue_medium.clone(),
             color_query,
         );
-        load_state.filename = Some(format!(
-            "assets/levels/{}/{}.lvl",
-            self.stage + 1,
-            self.level + 1
-        ));
+        load_state.filename = campaign.get_path(self.stage, self.level);
         load_state.ids = Some((self.stage, self.level));
+        load_state.generated = None;
         app_state.set(AppState::Level).unwrap();
     }
+
+    /// Shows the last solve of the level, if it was solved
+    pub fn replay(
+        &self,
+        replays: &Replays,
+        campaign: &Campaign,
+        app_state: &mut ResMut<State<AppState>>,
+        load_state: &mut ResMut<LoadState>,
+    ) {
+        match campaign.get_id(self.stage, self.level) {
+            Some(id) if replays.levels.contains_key(id) => {}
+            _ => return,
+        }
+        load_state.filename = campaign.get_path(self.stage, self.level);
+        load_state.ids = Some((self.stage, self.level));
+        load_state.generated = None;
+        app_state.set(AppState::Replay).unwrap();
+    }
 }
 
 pub struct StageCluster {
     pub stage_no: u8,
+    pub name: String,
     pub unlock_required: u16,
-    pub num_levels: u8,
+    pub levels: Vec<LevelEntry>,
 }
 
 impl StageCluster {
-    pub fn new(stage_no: u8, unlock_required: u16, num_levels: u8) -> Self {
-        assert!(num_levels > 0 && num_levels <= 6);
+    pub fn new(stage_no: u8, stage: &StageEntry) -> Self {
+        assert!(!stage.levels.is_empty(), "Stages need at least one level.");
         Self {
             stage_no,
-            unlock_required,
-            num_levels,
+            // Level packs are credited to their author
+            name: match stage.author.is_empty() {
+                true => stage.name.clone(),
+                false => format!("{}\n{}", stage.name, stage.author),
+            },
+            unlock_required: stage.unlock_points,
+            levels: stage.levels.clone(),
         }
     }
 }
@@ -106,6 +130,14 @@ impl StageCluster {
 pub struct ButtonTutorial;
 #[derive(Component)]
 pub struct ButtonReturn;
+/// Switches the mode the levels are played in
+#[derive(Component)]
+pub struct ButtonMode;
+#[derive(Component)]
+pub struct ModeText;
+/// Switches between the campaign and the level packs
+#[derive(Component)]
+pub struct ButtonCustom;
 
 #[derive(Component)]
 pub struct LevelSelectionButton;
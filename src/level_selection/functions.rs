@@ -1,15 +1,83 @@
 use super::components::{LevelSelectionCell, StageCluster};
 use crate::{
+    assets::LocaleAsset,
     components::Cell,
-    constants::{RADIUS, Z_INDEX_CELL_BACK},
-    functions::{calc_translation, make_cell_interactable, spawn_cell, spawn_cell_text},
-    resources::{CellMeshes, GameColors, Profile, TextSettings},
+    constants::{RADIUS, Z_INDEX_CELL_BACK, Z_INDEX_TEXT},
+    enums::PlayMode,
+    functions::{
+        calc_translation, format_time, make_cell_interactable, spawn_cell, spawn_cell_text,
+    },
+    resources::{CellMeshes, GameColors, LocaleAssets, Profile, TextSettings},
+    structs::{LevelEntry, StageEntry},
 };
 use bevy::{
     hierarchy::BuildChildren,
-    prelude::{Commands, Entity, SpatialBundle, Transform},
+    math::Vec3,
+    prelude::{Assets, Commands, Entity, SpatialBundle, Transform},
 };
 
+/// Positions around a center in board coordinates, ring by ring and clockwise from the top
+pub fn ring_positions(n: usize) -> Vec<(i32, i32)> {
+    // Axial directions in clockwise order, starting at the top
+    const DIRECTIONS: [(i32, i32); 6] = [(0, -1), (1, -1), (1, 0), (0, 1), (-1, 1), (-1, 0)];
+    let mut positions = Vec::new();
+    let mut ring = 1;
+    while positions.len() < n {
+        let (mut q, mut r) = (0, -ring);
+        for side in 0..6 {
+            let (dq, dr) = DIRECTIONS[(side + 2) % 6];
+            for _ in 0..ring {
+                // Axial to board coordinates, odd columns are shifted up
+                positions.push((q, r + (q + (q & 1)) / 2));
+                q += dq;
+                r += dr;
+            }
+        }
+        ring += 1;
+    }
+    positions.truncate(n);
+    positions
+}
+
+/// Number of rings ring_positions needs for n positions
+fn ring_count(n: usize) -> usize {
+    let mut rings = 0;
+    while 3 * rings * (rings + 1) < n {
+        rings += 1;
+    }
+    rings
+}
+
+/// Distance between the centers of two clusters in cells, enough for the largest stage
+fn cluster_spacing(stages: &[StageEntry]) -> usize {
+    let level_rings = stages
+        .iter()
+        .map(|stage| ring_count(stage.levels.len()))
+        .max()
+        .unwrap_or(1);
+    2 * level_rings + 2
+}
+
+/// Width and height of the level selection in cells.
+/// It has room for at least one ring, so that the buttons don't cover the center.
+pub fn layout_size(stages: &[StageEntry]) -> usize {
+    let spacing = cluster_spacing(stages);
+    let stage_rings = ring_count(stages.len()).max(1);
+    2 * (stage_rings * spacing + spacing / 2 - 1) + 1
+}
+
+/// Centers of the stage clusters around the total points in the middle
+pub fn cluster_translations(stages: &[StageEntry]) -> Vec<(f32, f32)> {
+    let spacing = cluster_spacing(stages) as f32;
+    ring_positions(stages.len())
+        .into_iter()
+        .map(|(x, y)| {
+            let (tx, ty) = calc_translation(x, y, 0., 0.);
+            (tx * spacing, ty * spacing)
+        })
+        .collect()
+}
+
 pub fn spawn_cluster(
     commands: &mut Commands,
     cell_meshes: &CellMeshes,
@@ -21,13 +89,10 @@ pub fn spawn_cluster(
 ) -> Entity {
     let unlocked = profile.get_points() >= stage_cluster.unlock_required;
 
-    let mut big_transform = Transform::from_xyz(x, y, Z_INDEX_CELL_BACK);
-    big_transform.rotate_z(f32::to_radians(90.0));
-
     let mut ls_cells = Vec::new();
-    for (id, (dx, dy)) in [(0, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)]
+    for (id, ((dx, dy), level)) in ring_positions(stage_cluster.levels.len())
         .into_iter()
-        .take(stage_cluster.num_levels as usize)
+        .zip(stage_cluster.levels.iter())
         .enumerate()
     {
         let (tx, ty) = calc_translation(dx, dy, 0., 0.);
@@ -40,19 +105,24 @@ pub fn spawn_cluster(
             profile,
             text_settings,
             big_transform,
-            (id as u8, stage_cluster.stage_no, unlocked),
+            (id as u8, stage_cluster.stage_no, level, unlocked),
         ));
     }
     let (tx, ty) = calc_translation(0, 0, 0., 0.);
     let mut big_transform = Transform::from_xyz(x + tx, y + ty, Z_INDEX_CELL_BACK);
     big_transform.rotate_z(f32::to_radians(90.0));
+    // Unlocked stages show their name, locked ones the points they need
+    let label = match unlocked {
+        true => stage_cluster.name.clone(),
+        false => stage_cluster.unlock_required.to_string(),
+    };
     let cluster_cell = spawn_cluster_cell(
         commands,
         cell_meshes,
         game_colors,
         text_settings,
         big_transform,
-        (stage_cluster.unlock_required, unlocked),
+        (&label, unlocked),
     );
     commands
         .spawn()
@@ -69,13 +139,13 @@ fn spawn_level_selection_cell(
     profile: &Profile,
     text_settings: &TextSettings,
     big_transform: Transform,
-    (level_id, stage_id, unlocked): (u8, u8, bool),
+    (level_id, stage_id, level, unlocked): (u8, u8, &LevelEntry, bool),
 ) -> Entity {
     let cell = commands.spawn().id();
 
     let colors = if unlocked {
         make_cell_interactable(commands, cell, RADIUS);
-        if profile.level_points[stage_id as usize][level_id as usize].is_some() {
+        if profile.get_level_points(&level.id).is_some() {
             (
                 game_colors.white.clone(),
                 game_colors.blue_medium.clone(),
@@ -110,11 +180,25 @@ fn spawn_level_selection_cell(
 
     let text_entity = spawn_cell_text(
         commands,
-        &format!("{}–{}", stage_id + 1, level_id + 1),
+        &level.name,
         text_settings.style_cell.clone(),
         text_settings.alignment,
     );
     commands.entity(cell).add_child(text_entity);
+    if let Some(time) = profile.get_time(&level.id) {
+        let time_entity = spawn_cell_text(
+            commands,
+            &format_time(time),
+            text_settings.style_cell.clone(),
+            text_settings.alignment,
+        );
+        // Small and below the level number, the cell itself is rotated
+        let mut t = Transform::from_xyz(-0.55 * RADIUS, 0., Z_INDEX_TEXT)
+            .with_scale(Vec3::new(0.4, 0.4, 1.));
+        t.rotate_z(f32::to_radians(-90.0));
+        commands.entity(time_entity).insert(t);
+        commands.entity(cell).add_child(time_entity);
+    }
 
     let cell_component = Cell {
         x: stage_id as i32,
@@ -130,6 +214,7 @@ fn spawn_level_selection_cell(
         .insert(LevelSelectionCell {
             stage: stage_id,
             level: level_id,
+            id: level.id.clone(),
         });
     cell
 }
@@ -140,7 +225,7 @@ fn spawn_cluster_cell(
     game_colors: &GameColors,
     text_settings: &TextSettings,
     big_transform: Transform,
-    (unlock_required, unlocked): (u16, bool),
+    (label, unlocked): (&str, bool),
 ) -> Entity {
     let cell = commands.spawn().id();
     let colors = if unlocked {
@@ -170,10 +255,25 @@ fn spawn_cluster_cell(
 
     let text_entity = spawn_cell_text(
         commands,
-        &format!("{}", unlock_required),
+        label,
         text_settings.style_cell.clone(),
         text_settings.alignment,
     );
     commands.entity(cell).add_child(text_entity);
     cell
 }
+
+/// Name of the mode on the mode button, with the mistake limit of the hardcore mode
+pub fn mode_label(
+    mode: PlayMode,
+    (locale, locales, profile): (&LocaleAssets, &Assets<LocaleAsset>, &Profile),
+) -> String {
+    let name = locale
+        .get_string(mode.key(), locales, profile)
+        .unwrap_or(&"String not found".to_string())
+        .clone();
+    match mode {
+        PlayMode::Hardcore(limit) => format!("{} ({})", name, limit),
+        _ => name,
+    }
+}
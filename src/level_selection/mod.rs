@@ -23,7 +23,10 @@ pub fn prepare_level_selection(app: &mut App) {
                         .after(InteractLabel::Interact),
                 )
                 .with_system(mouse_click_cell.after(mouse_enter_cell))
+                .with_system(mouse_right_click_cell.after(mouse_enter_cell))
                 .with_system(button_click_system.after(InteractLabel::Interact))
+                .with_system(mode_button_system.after(InteractLabel::Interact))
+                .with_system(custom_button_system.after(InteractLabel::Interact))
                 .with_system(
                     menu_button_hovered::<LevelSelectionButton>.after(InteractLabel::Interact),
                 )
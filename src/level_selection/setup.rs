@@ -1,14 +1,18 @@
 use super::{
-    components::{ButtonReturn, ButtonTutorial, LevelSelectionButton, StageCluster},
-    functions::spawn_cluster,
+    components::{
+        ButtonCustom, ButtonMode, ButtonReturn, ButtonTutorial, LevelSelectionButton, ModeText,
+        StageCluster,
+    },
+    functions::{cluster_translations, layout_size, mode_label, spawn_cluster},
 };
 use crate::{
     assets::LocaleAsset,
     bundles::MenuButtonBundle,
     components::RootComponent,
-    constants::{RADIUS, UNLOCK_POINTS, Z_INDEX_TEXT},
+    constants::{RADIUS, Z_INDEX_TEXT},
     functions::{rescale_board, spawn_cell},
-    resources::{CellMeshes, GameColors, LocaleAssets, Profile, TextSettings},
+    packs::get_custom_path,
+    resources::{Campaign, CellMeshes, GameColors, LoadState, LocaleAssets, Profile, TextSettings},
 };
 use bevy::{
     hierarchy::BuildChildren,
@@ -30,13 +34,12 @@ pub fn setup(
     wnds: Res<Windows>,
     (cell_meshes, game_colors, locale, profile, text_settings): StandardResources,
     (mut meshes, locales): (ResMut<Assets<Mesh>>, Res<Assets<LocaleAsset>>),
+    (load_state, campaign): (Res<LoadState>, Res<Campaign>),
 ) {
+    let (stages, first_id) = campaign.get_section(load_state.custom);
     let mut clusters = Vec::new();
-    for (id, (dx, dy)) in [(0, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)]
-        .into_iter()
-        .enumerate()
-    {
-        let sc = StageCluster::new(id as u8, UNLOCK_POINTS[id], 6);
+    for (id, (stage, translation)) in stages.iter().zip(cluster_translations(stages)).enumerate() {
+        let sc = StageCluster::new(first_id + id as u8, stage);
         clusters.push(spawn_cluster(
             &mut commands,
             &cell_meshes,
@@ -44,16 +47,12 @@ pub fn setup(
             &profile,
             &text_settings,
             sc,
-            (
-                dx as f32 * RADIUS * 1.56 * 4.,
-                dy as f32 * RADIUS * -1.8 * 4.
-                    + match dx == 0 {
-                        true => 0.,
-                        false => RADIUS * 0.9 * 4.,
-                    },
-            ),
+            translation,
         ));
     }
+    // The buttons stay in the corners of larger layouts
+    let size = layout_size(stages);
+    let corner = size as f32 / 11.;
 
     let center_cell = commands.spawn().id();
     spawn_cell(
@@ -71,13 +70,22 @@ pub fn setup(
         ),
         Transform::from_scale(Vec3::new(2., 2., 1.)),
     );
+    // The campaign counts points, the level packs only solved levels
+    let total = match load_state.custom {
+        true => {
+            let levels = stages.iter().flat_map(|stage| stage.levels.iter());
+            let solved = levels
+                .clone()
+                .filter(|level| profile.get_level_points(&level.id).is_some())
+                .count();
+            format!("{} /\n{}", solved, levels.count())
+        }
+        false => format!("{} /\n{}", profile.get_points(), campaign.get_max_points()),
+    };
     commands.entity(center_cell).with_children(|parent| {
         parent.spawn_bundle(Text2dBundle {
-            text: Text::from_section(
-                format!("{} /\n{}", profile.get_points(), 447),
-                text_settings.style_cell.clone(),
-            )
-            .with_alignment(text_settings.alignment),
+            text: Text::from_section(total, text_settings.style_cell.clone())
+                .with_alignment(text_settings.alignment),
             transform: Transform::from_xyz(0., 0., Z_INDEX_TEXT)
                 .with_scale(Vec3::new(0.7, 0.7, 1.0)),
             ..default()
@@ -86,7 +94,7 @@ pub fn setup(
 
     let bt_tutorial = commands
         .spawn_bundle(MenuButtonBundle::new(
-            Transform::from_xyz(-6.25 * RADIUS, -8. * RADIUS, 0.9),
+            Transform::from_xyz(-6.25 * RADIUS * corner, -8. * RADIUS * corner, 0.9),
             (240., 150.),
             game_colors.menu_button.clone(),
             &mut meshes,
@@ -109,7 +117,7 @@ pub fn setup(
         .id();
     let bt_return = commands
         .spawn_bundle(MenuButtonBundle::new(
-            Transform::from_xyz(6.25 * RADIUS, -8. * RADIUS, 0.9),
+            Transform::from_xyz(6.25 * RADIUS * corner, -8. * RADIUS * corner, 0.9),
             (240., 150.),
             game_colors.menu_button.clone(),
             &mut meshes,
@@ -131,15 +139,99 @@ pub fn setup(
         .insert(LevelSelectionButton)
         .id();
 
+    let bt_mode = commands
+        .spawn_bundle(MenuButtonBundle::new(
+            Transform::from_xyz(-6.25 * RADIUS * corner, 8. * RADIUS * corner, 0.9),
+            (360., 150.),
+            game_colors.menu_button.clone(),
+            &mut meshes,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(Text2dBundle {
+                    text: Text::from_section(
+                        mode_label(load_state.mode, (&locale, &locales, &profile)),
+                        text_settings.style_menu_dark.clone(),
+                    )
+                    .with_alignment(text_settings.alignment),
+                    transform: Transform::from_xyz(0., -10., 10.)
+                        .with_scale(Vec3::new(0.5, 0.5, 1.)),
+                    ..default()
+                })
+                .insert(ModeText);
+        })
+        .insert(ButtonMode)
+        .insert(LevelSelectionButton)
+        .id();
+
+    let bt_custom = commands
+        .spawn_bundle(MenuButtonBundle::new(
+            Transform::from_xyz(6.25 * RADIUS * corner, 8. * RADIUS * corner, 0.9),
+            (360., 150.),
+            game_colors.menu_button.clone(),
+            &mut meshes,
+        ))
+        .with_children(|parent| {
+            parent.spawn_bundle(Text2dBundle {
+                text: Text::from_section(
+                    locale
+                        .get_string(
+                            if load_state.custom {
+                                "campaign"
+                            } else {
+                                "custom"
+                            },
+                            &locales,
+                            &profile,
+                        )
+                        .unwrap_or(&"String not found".to_string()),
+                    text_settings.style_menu_dark.clone(),
+                )
+                .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(0., -10., 10.).with_scale(Vec3::new(0.5, 0.5, 1.)),
+                ..default()
+            });
+        })
+        .insert(ButtonCustom)
+        .insert(LevelSelectionButton)
+        .id();
+
+    // Without packs, the players learn where to put them
+    if stages.is_empty() {
+        let text = format!(
+            "{}\n{}",
+            locale
+                .get_string("no-packs", &locales, &profile)
+                .unwrap_or(&"String not found".to_string()),
+            get_custom_path().display()
+        );
+        let hint = commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::from_section(text, text_settings.style_cell.clone())
+                    .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(0., -3. * RADIUS, Z_INDEX_TEXT)
+                    .with_scale(Vec3::new(0.5, 0.5, 1.)),
+                ..default()
+            })
+            .id();
+        clusters.push(hint);
+    }
+
     let mut root_transform = Transform::identity();
     for wnd in wnds.iter() {
-        // TODO: Remove hard-coded width/height
-        rescale_board(11, 11, 1, wnd.width(), wnd.height(), &mut root_transform);
+        rescale_board(
+            size,
+            size,
+            1,
+            wnd.width(),
+            wnd.height(),
+            &mut root_transform,
+        );
     }
     commands
         .spawn()
         .push_children(&clusters)
-        .push_children(&[center_cell, bt_tutorial, bt_return])
+        .push_children(&[center_cell, bt_tutorial, bt_return, bt_mode, bt_custom])
         .insert_bundle(SpatialBundle::from_transform(root_transform))
         .insert(RootComponent);
 }
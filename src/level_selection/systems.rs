@@ -1,24 +1,33 @@
-use super::components::{ButtonReturn, ButtonTutorial, LevelSelectionCell};
+use super::{
+    components::{
+        ButtonCustom, ButtonMode, ButtonReturn, ButtonTutorial, LevelSelectionCell, ModeText,
+    },
+    functions::{layout_size, mode_label},
+};
 use crate::{
+    assets::LocaleAsset,
     components::{Cell, RootComponent},
     functions::{rescale_board, switch_state},
-    resources::{GameColors, LoadState, Profile, SfxAssets},
+    resources::{Campaign, GameColors, LoadState, LocaleAssets, Profile, Replays, SfxAssets},
     states::AppState,
 };
 use bevy::{
     input::Input,
-    prelude::{Commands, EventReader, Handle, KeyCode, Query, Res, ResMut, State, Transform, With},
+    prelude::{
+        Assets, Commands, EventReader, Handle, KeyCode, Query, Res, ResMut, State, Transform, With,
+    },
     sprite::ColorMaterial,
+    text::Text,
     window::WindowResized,
 };
 use bevy_kira_audio::{Audio, AudioControl};
-use interactable::components::{Entered, Exited, Interactable, ReleasedLeft};
+use interactable::components::{Entered, Exited, Interactable, ReleasedLeft, ReleasedRight};
 
 pub fn mouse_click_cell(
     mut commands: Commands,
     mut level_cell_query: Query<(&LevelSelectionCell, &mut Cell), With<ReleasedLeft>>,
     mut color_query: Query<&mut Handle<ColorMaterial>>,
-    game_colors: Res<GameColors>,
+    (game_colors, campaign): (Res<GameColors>, Res<Campaign>),
     (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
 ) {
     for (lsc, mut cell) in level_cell_query.iter_mut() {
@@ -26,13 +35,24 @@ pub fn mouse_click_cell(
             &mut cell,
             &mut commands,
             &mut color_query,
-            &game_colors,
+            (&game_colors, &campaign),
             &mut app_state,
             &mut load_state,
         );
     }
 }
 
+/// Right-clicking a solved level shows the replay of the last solve
+pub fn mouse_right_click_cell(
+    level_cell_query: Query<&LevelSelectionCell, With<ReleasedRight>>,
+    (replays, campaign): (Res<Replays>, Res<Campaign>),
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    for lsc in level_cell_query.iter() {
+        lsc.replay(&replays, &campaign, &mut app_state, &mut load_state);
+    }
+}
+
 /// Calls hover on a cell that is entered by the mouse
 pub fn mouse_enter_cell(
     mut commands: Commands,
@@ -88,6 +108,37 @@ pub fn button_click_system(
     }
 }
 
+/// Switches between the campaign and the level packs
+pub fn custom_button_system(
+    button_query: Query<&ButtonCustom, With<ReleasedLeft>>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if !button_query.is_empty() {
+        load_state.custom = !load_state.custom;
+        switch_state(
+            Some(AppState::LevelSelection),
+            &mut app_state,
+            &mut load_state,
+        );
+    }
+}
+
+/// Cycles through the normal, the practice and the hardcore modes
+pub fn mode_button_system(
+    button_query: Query<&ButtonMode, With<ReleasedLeft>>,
+    mut text_query: Query<&mut Text, With<ModeText>>,
+    (locale, locales, profile): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>, Res<Profile>),
+    mut load_state: ResMut<LoadState>,
+) {
+    if button_query.is_empty() {
+        return;
+    }
+    load_state.mode = load_state.mode.next();
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = mode_label(load_state.mode, (&locale, &locales, &profile));
+    }
+}
+
 pub fn hotkey_system(
     mut keys: ResMut<Input<KeyCode>>,
     mut app_state: ResMut<State<AppState>>,
@@ -107,11 +158,12 @@ pub fn hotkey_system(
 pub fn window_resize_system(
     mut ev_window_resize: EventReader<WindowResized>,
     mut root_query: Query<&mut Transform, With<RootComponent>>,
+    (campaign, load_state): (Res<Campaign>, Res<LoadState>),
 ) {
+    let size = layout_size(campaign.get_section(load_state.custom).0);
     for ev in ev_window_resize.iter() {
         if let Ok(mut root) = root_query.get_single_mut() {
-            // TODO: Remove hard-coded width/height
-            rescale_board(11, 11, 1, ev.width, ev.height, &mut root);
+            rescale_board(size, size, 1, ev.width, ev.height, &mut root);
         }
     }
 }
@@ -1,22 +1,31 @@
 #![windows_subsystem = "windows"]
 
+mod achievements;
 mod assets;
 mod board_functions;
 mod bundles;
 mod components;
 mod constants;
+mod daily;
 mod dialog;
 mod editor;
 mod enums;
 mod functions;
+mod generator;
 mod home;
 mod level;
 mod level_selection;
 mod overlay;
+mod packs;
 mod parser;
+mod profile_selection;
+mod replay;
 mod resources;
 mod settings;
+mod solver;
 mod states;
+mod stats;
+mod storage;
 mod structs;
 mod systems;
 mod tutorial;
@@ -24,11 +33,14 @@ mod tutorial;
 use assets::{LocaleAsset, LocaleAssetLoader};
 use bevy::{
     app::{App, AppExit},
+    ecs::system::Resource,
     hierarchy::DespawnRecursiveExt,
     prelude::{
         default, AddAsset, AssetServer, Camera2dBundle, ClearColor, Color, Commands, Component,
-        Entity, EventWriter, Msaa, NonSend, Query, Res, ResMut, State, SystemSet, With,
+        Entity, EventReader, EventWriter, Local, Msaa, NonSend, Query, Res, ResMut, State,
+        SystemSet, With,
     },
+    time::Time,
     window::{WindowDescriptor, WindowId, WindowResizeConstraints, Windows},
     winit::WinitWindows,
     DefaultPlugins,
@@ -46,17 +58,33 @@ use interactable::{InteractableCamera, InteractablePlugin};
 use native_dialog::MessageDialog;
 use overlay::resources::OverlaySettings;
 use resources::{
-    CellMeshes, GameColors, LoadState, LocaleAssets, Profile, SfxAssets, TextSettings,
+    Achievements, Campaign, CellMeshes, EndlessRun, GameColors, GenerateTask, LoadState,
+    LocaleAssets, Profile, Profiles, Progress, Replays, SfxAssets, TextSettings,
 };
 use states::AppState;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::OpenOptions;
 use std::{
     io::{self, Write},
     panic,
 };
+use storage::{save_due, Saved};
+use systems::generate_task_system;
 use winit::window::Icon;
 
+/// Panics are appended to it
+#[cfg(not(target_arch = "wasm32"))]
+const CRASH_LOG: &str = "crash.log";
+
 fn main() {
     set_panic_hook();
+    storage::migrate_user_files();
+
+    // The profile needs the campaign to migrate results saved by position
+    let campaign = Campaign::new();
+    let profiles = Profiles::new();
+    let dir = profiles.get_current_dir();
+    let profile = Profile::new(&campaign, &dir);
 
     let mut app = App::new();
     app.insert_resource(Msaa { samples: 4 })
@@ -74,7 +102,14 @@ fn main() {
         .insert_resource(ClearColor(Color::rgb(0.15, 0.15, 0.15)))
         .init_resource::<LoadState>()
         .init_resource::<OverlaySettings>()
-        .insert_resource(Profile::new())
+        .init_resource::<EndlessRun>()
+        .init_resource::<GenerateTask>()
+        .insert_resource(campaign)
+        .insert_resource(Achievements::new())
+        .insert_resource(profile)
+        .insert_resource(Progress::new(&dir))
+        .insert_resource(Replays::new(&dir))
+        .insert_resource(profiles)
         .add_plugins(DefaultPlugins)
         .add_plugin(InteractablePlugin)
         .add_plugin(EasingsPlugin)
@@ -83,7 +118,11 @@ fn main() {
         // .add_plugin(FrameTimeDiagnosticsPlugin::default())
         .add_startup_system(setup)
         .add_startup_system(set_window_icon)
-        .add_system(save_profile_system)
+        .add_system(save_system::<Profile>)
+        .add_system(generate_task_system)
+        .add_system(save_system::<Progress>)
+        .add_system(save_system::<Replays>)
+        .add_system(save_system::<Profiles>)
         // .add_state(AppState::Loading)
         .add_asset::<LocaleAsset>()
         .init_asset_loader::<LocaleAssetLoader>()
@@ -101,13 +140,17 @@ fn main() {
         .add_system_set(SystemSet::on_update(AppState::StateChange).with_system(load_complete))
         .add_system_set(SystemSet::on_enter(AppState::Quit).with_system(quit_system));
 
+    achievements::prepare_achievements(&mut app);
     dialog::prepare_dialog(&mut app);
     editor::prepare_editor(&mut app);
     home::prepare_home(&mut app);
     level::prepare_level(&mut app);
     level_selection::prepare_level_selection(&mut app);
     overlay::prepare_overlay(&mut app);
+    profile_selection::prepare_profile_selection(&mut app);
+    replay::prepare_replay(&mut app);
     settings::prepare_settings(&mut app);
+    stats::prepare_stats(&mut app);
     tutorial::prepare_tutorial(&mut app);
 
     #[cfg(feature = "bevy-inspector-egui")]
@@ -136,9 +179,17 @@ fn setup(
     }
 }
 
-fn save_profile_system(profile: Res<Profile>) {
-    if profile.is_changed() {
-        profile.save();
+/// Saves a resource a moment after it changed, and right away when the app exits
+fn save_system<T: Resource + Saved>(
+    resource: Res<T>,
+    time: Res<Time>,
+    mut exit: EventReader<AppExit>,
+    mut due: Local<Option<f64>>,
+) {
+    let exiting = exit.iter().count() > 0;
+    let now = time.seconds_since_startup();
+    if save_due(resource.is_changed(), now, &mut due) || (exiting && due.take().is_some()) {
+        resource.save();
     }
 }
 
@@ -154,7 +205,16 @@ pub fn cleanup_system<T: Component>(mut commands: Commands, entities: Query<Enti
     }
 }
 
-fn quit_system(mut exit: EventWriter<AppExit>) {
+/// Writes pending changes right away, the save systems might not run again
+fn quit_system(
+    mut exit: EventWriter<AppExit>,
+    (profile, progress, replays): (Res<Profile>, Res<Progress>, Res<Replays>),
+    profiles: Res<Profiles>,
+) {
+    profiles.save();
+    profile.save();
+    progress.save();
+    replays.save();
     exit.send(AppExit);
 }
 
@@ -168,6 +228,14 @@ fn set_panic_hook() {
     panic::set_hook(Box::new(|info| {
         let mut w = Vec::new();
         let _ = writeln!(&mut w, "{}", info);
+        // Kept in the user directory, so players can attach it to a report
+        if let Ok(mut log) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(functions::get_user_path().join(CRASH_LOG))
+        {
+            let _ = writeln!(log, "{}", info);
+        }
         MessageDialog::new()
             .set_type(native_dialog::MessageType::Error)
             .set_title("Error")
@@ -15,6 +15,14 @@ pub struct ButtonMenu;
 pub struct ButtonRestart;
 #[derive(Component)]
 pub struct ButtonVariable(pub bool);
+/// Saves the result summary of a daily puzzle to share it
+#[derive(Component)]
+pub struct ButtonShare(pub String);
+/// Take the place of the restart button in endless runs
+#[derive(Component)]
+pub struct ButtonEndRun;
+#[derive(Component)]
+pub struct ButtonNewRun;
 
 #[derive(Component)]
 pub struct OverlayButton;
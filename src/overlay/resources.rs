@@ -6,6 +6,8 @@ pub struct OverlaySettings {
     pub max_points: u16,
     pub points: u16,
     pub mistakes: u16,
+    /// Solve time in seconds
+    pub time: f32,
     pub overlay_type: OverlayType,
 }
 
@@ -14,4 +16,8 @@ pub enum OverlayType {
     Pause,
     #[default]
     LevelComplete,
+    /// Too many mistakes in the hardcore mode
+    LevelFailed,
+    /// End of an endless run
+    RunSummary,
 }
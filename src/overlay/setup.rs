@@ -1,6 +1,7 @@
 use super::{
     components::{
-        ButtonMenu, ButtonRestart, ButtonVariable, OverlayButton, UiBackground, UiRootNode,
+        ButtonEndRun, ButtonMenu, ButtonNewRun, ButtonRestart, ButtonShare, ButtonVariable,
+        OverlayButton, UiBackground, UiRootNode,
     },
     resources::{OverlaySettings, OverlayType},
 };
@@ -8,8 +9,13 @@ use crate::{
     assets::LocaleAsset,
     bundles::MenuButtonBundle,
     constants::{RADIUS, Z_INDEX_CELL_BACK, Z_INDEX_UI},
-    functions::spawn_cell,
-    resources::{CellMeshes, GameColors, LocaleAssets, Profile, TextSettings},
+    daily::format_date,
+    enums::Generated,
+    functions::{format_time, spawn_cell},
+    resources::{
+        Campaign, CellMeshes, EndlessRun, GameColors, LoadState, LocaleAssets, Profile,
+        TextSettings,
+    },
 };
 use bevy::{
     hierarchy::BuildChildren,
@@ -37,8 +43,33 @@ pub fn setup(
     mut commands: Commands,
     (cell_meshes, game_colors, locale, overlay_settings, profile, text_settings): StandardResources,
     (mut meshes, mut colors, locales): StandardAssets,
-    wnds: Res<Windows>,
+    (wnds, campaign, load_state): (Res<Windows>, Res<Campaign>, Res<LoadState>),
+    run: Res<EndlessRun>,
 ) {
+    let get = |key| {
+        locale
+            .get_string(key, &locales, &profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone()
+    };
+    let (daily, endless) = match load_state.generated {
+        Some((Generated::Daily(day), _)) => (Some(day), false),
+        Some((Generated::Endless(_), _)) => (None, true),
+        None => (None, false),
+    };
+    // The daily puzzles share their best results
+    let (best_points, best_time) = match daily {
+        Some(_) => (
+            profile.get_daily().best_points,
+            profile.get_daily().best_time,
+        ),
+        None => {
+            let record = campaign
+                .get_id(overlay_settings.stage_id, overlay_settings.level_id)
+                .and_then(|id| profile.get_record(id));
+            (record.and_then(|r| r.points), record.and_then(|r| r.time))
+        }
+    };
     // Panel width and height 1920×1080p window
     let (panel_width, panel_height) = (1280., 960.);
     let (points, title_text) = match overlay_settings.overlay_type {
@@ -49,18 +80,36 @@ pub fn setup(
                 .unwrap_or(&"String not found".to_string())
                 .clone(),
         ),
+        OverlayType::LevelFailed => (
+            overlay_settings.points,
+            locale
+                .get_string("failed", &locales, &profile)
+                .unwrap_or(&"String not found".to_string())
+                .clone(),
+        ),
         OverlayType::Pause => (
-            profile.level_points[overlay_settings.stage_id as usize]
-                [overlay_settings.level_id as usize]
-                .unwrap_or_default(),
+            best_points.unwrap_or_default(),
             locale
                 .get_string("pause", &locales, &profile)
                 .unwrap_or(&"String not found".to_string())
                 .clone(),
         ),
+        OverlayType::RunSummary => (0, get("run-over")),
     };
     let point_text = match overlay_settings.overlay_type {
-        OverlayType::LevelComplete => format!(
+        OverlayType::RunSummary => format!(
+            "{}: {}\n{}: {}\n{}: {}",
+            get("puzzles"),
+            run.solved,
+            get("mistakes"),
+            run.mistakes,
+            get("best-run"),
+            profile.get_endless().best_score
+        ),
+        OverlayType::Pause if endless => {
+            format!("{}: {}", get("best-run"), profile.get_endless().best_score)
+        }
+        OverlayType::LevelComplete | OverlayType::LevelFailed => format!(
             "{}: {}",
             locale
                 .get_string("mistakes", &locales, &profile)
@@ -72,17 +121,70 @@ pub fn setup(
             locale
                 .get_string("highscore", &locales, &profile)
                 .unwrap_or(&"String not found".to_string()),
-            profile.level_points[overlay_settings.stage_id as usize]
-                [overlay_settings.level_id as usize]
-                .unwrap_or_default()
+            best_points.unwrap_or_default()
         ),
     };
-    let total_text = format!(
-        "{}:",
+    let best_time = format!(
+        "{}: {}",
         locale
-            .get_string("total", &locales, &profile)
-            .unwrap_or(&"String not found".to_string())
+            .get_string("best-time", &locales, &profile)
+            .unwrap_or(&"String not found".to_string()),
+        best_time.map_or_else(|| "–".to_string(), format_time)
     );
+    // Plain text to share the result of a daily puzzle, the first solve of the day counts
+    let summary = daily
+        .filter(|_| overlay_settings.overlay_type == OverlayType::LevelComplete)
+        .and_then(|day| {
+            let record = profile.get_daily();
+            record.results.get(&day).map(|result| {
+                format!(
+                    "Honeycombs {} {}: {}/{} – {}: {} – {} – {}: {}",
+                    get("daily-title"),
+                    format_date(day),
+                    result.points,
+                    result.max_points,
+                    get("mistakes"),
+                    result.mistakes,
+                    format_time(result.time),
+                    get("streak"),
+                    record.streak(day)
+                )
+            })
+        });
+    // Endless runs show the difficulty of the next puzzle instead of the best time
+    let difficulty = format!("{}: {}", get("difficulty"), run.difficulty + 1);
+    let time_text = match (&summary, &overlay_settings.overlay_type) {
+        (Some(summary), _) => summary.clone(),
+        (None, OverlayType::RunSummary) => {
+            format!(
+                "{}: {}   {}",
+                get("time"),
+                format_time(run.time),
+                difficulty
+            )
+        }
+        (None, OverlayType::Pause) if endless => difficulty,
+        (None, OverlayType::LevelComplete) if endless => format!(
+            "{}: {}   {}",
+            get("time"),
+            format_time(overlay_settings.time),
+            difficulty
+        ),
+        (None, OverlayType::LevelComplete | OverlayType::LevelFailed) => format!(
+            "{}: {}   {}",
+            locale
+                .get_string("time", &locales, &profile)
+                .unwrap_or(&"String not found".to_string()),
+            format_time(overlay_settings.time),
+            best_time
+        ),
+        (None, OverlayType::Pause) => best_time,
+    };
+    let total_text = format!("{}:", get(if endless { "run-score" } else { "total" }));
+    let total = match endless {
+        true => run.score,
+        false => profile.get_points() as u32,
+    };
 
     let mut point_cells = Vec::new();
     for i in 0..overlay_settings.max_points {
@@ -177,10 +279,10 @@ pub fn setup(
             parent
                 .spawn_bundle(ColorMesh2dBundle {
                     mesh: meshes
-                        .add(Mesh::from(Quad::new(Vec2::new(600., 580.))))
+                        .add(Mesh::from(Quad::new(Vec2::new(600., 520.))))
                         .into(),
                     material: colors.add(ColorMaterial::from(Color::rgba(0.7, 0.7, 0.7, 0.92))),
-                    transform: Transform::from_xyz(-310., 50., 0.9),
+                    transform: Transform::from_xyz(-310., 80., 0.9),
                     ..default()
                 })
                 .push_children(&point_cells)
@@ -195,10 +297,10 @@ pub fn setup(
             parent
                 .spawn_bundle(ColorMesh2dBundle {
                     mesh: meshes
-                        .add(Mesh::from(Quad::new(Vec2::new(600., 580.))))
+                        .add(Mesh::from(Quad::new(Vec2::new(600., 520.))))
                         .into(),
                     material: colors.add(ColorMaterial::from(Color::rgba(0.7, 0.7, 0.7, 0.92))),
-                    transform: Transform::from_xyz(310., 50., 0.9),
+                    transform: Transform::from_xyz(310., 80., 0.9),
                     ..default()
                 })
                 .add_child(total_cell)
@@ -211,7 +313,7 @@ pub fn setup(
                     });
                     parent.spawn_bundle(Text2dBundle {
                         text: Text::from_section(
-                            format!("×{}", profile.get_points()),
+                            format!("×{}", total),
                             text_settings.style_menu_dark.clone(),
                         )
                         .with_alignment(text_settings.alignment),
@@ -219,18 +321,30 @@ pub fn setup(
                         ..default()
                     });
                 });
-            parent
-                .spawn_bundle(MenuButtonBundle::new(
-                    Transform::from_xyz(-260., -355., 0.9),
-                    (240., 190.),
-                    game_colors.menu_button.clone(),
-                    &mut meshes,
-                ))
+            parent.spawn_bundle(Text2dBundle {
+                text: Text::from_section(time_text, text_settings.style_menu_dark.clone())
+                    .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(0., -220., 1.).with_scale(Vec3::new(0.5, 0.5, 1.)),
+                ..default()
+            });
+            // Restarting would repeat a puzzle of an endless run, it can only be ended
+            let left_key = match (endless, &overlay_settings.overlay_type) {
+                (true, OverlayType::RunSummary) => "new-run",
+                (true, _) => "end-run",
+                (false, _) => "restart",
+            };
+            let mut left_button = parent.spawn_bundle(MenuButtonBundle::new(
+                Transform::from_xyz(-260., -355., 0.9),
+                (240., 190.),
+                game_colors.menu_button.clone(),
+                &mut meshes,
+            ));
+            left_button
                 .with_children(|parent| {
                     parent.spawn_bundle(Text2dBundle {
                         text: Text::from_section(
                             locale
-                                .get_string("restart", &locales, &profile)
+                                .get_string(left_key, &locales, &profile)
                                 .unwrap_or(&"String not found".to_string()),
                             text_settings.style_menu_dark.clone(),
                         )
@@ -240,8 +354,12 @@ pub fn setup(
                         ..default()
                     });
                 })
-                .insert(ButtonRestart)
                 .insert(OverlayButton);
+            match (endless, &overlay_settings.overlay_type) {
+                (true, OverlayType::RunSummary) => left_button.insert(ButtonNewRun),
+                (true, _) => left_button.insert(ButtonEndRun),
+                (false, _) => left_button.insert(ButtonRestart),
+            };
             parent
                 .spawn_bundle(MenuButtonBundle::new(
                     Transform::from_xyz(0., -355., 0.9),
@@ -265,37 +383,67 @@ pub fn setup(
                 })
                 .insert(ButtonMenu)
                 .insert(OverlayButton);
-            parent
-                .spawn_bundle(MenuButtonBundle::new(
-                    Transform::from_xyz(260., -355., 0.9),
-                    (240., 190.),
-                    game_colors.menu_button.clone(),
-                    &mut meshes,
-                ))
-                .with_children(|parent| {
-                    parent.spawn_bundle(Text2dBundle {
-                        text: Text::from_section(
-                            locale
-                                .get_string(
-                                    match overlay_settings.overlay_type {
-                                        OverlayType::LevelComplete => "next",
-                                        _ => "return",
-                                    },
-                                    &locales,
-                                    &profile,
-                                )
-                                .unwrap_or(&"String not found".to_string()),
-                            text_settings.style_menu_dark.clone(),
-                        )
-                        .with_alignment(text_settings.alignment),
-                        transform: Transform::from_xyz(0., -10., 10.)
-                            .with_scale(Vec3::new(0.75, 0.75, 1.)),
-                        ..default()
-                    });
-                })
-                .insert(ButtonVariable(
-                    overlay_settings.overlay_type == OverlayType::LevelComplete,
-                ))
-                .insert(OverlayButton);
+            // A failed level can only be restarted, a daily puzzle is shared instead
+            // and there is nothing to go on with after an endless run
+            if let Some(summary) = summary {
+                parent
+                    .spawn_bundle(MenuButtonBundle::new(
+                        Transform::from_xyz(260., -355., 0.9),
+                        (240., 190.),
+                        game_colors.menu_button.clone(),
+                        &mut meshes,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn_bundle(Text2dBundle {
+                            text: Text::from_section(
+                                locale
+                                    .get_string("share", &locales, &profile)
+                                    .unwrap_or(&"String not found".to_string()),
+                                text_settings.style_menu_dark.clone(),
+                            )
+                            .with_alignment(text_settings.alignment),
+                            transform: Transform::from_xyz(0., -10., 10.)
+                                .with_scale(Vec3::new(0.75, 0.75, 1.)),
+                            ..default()
+                        });
+                    })
+                    .insert(ButtonShare(summary))
+                    .insert(OverlayButton);
+            } else if overlay_settings.overlay_type != OverlayType::LevelFailed
+                && overlay_settings.overlay_type != OverlayType::RunSummary
+            {
+                parent
+                    .spawn_bundle(MenuButtonBundle::new(
+                        Transform::from_xyz(260., -355., 0.9),
+                        (240., 190.),
+                        game_colors.menu_button.clone(),
+                        &mut meshes,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn_bundle(Text2dBundle {
+                            text: Text::from_section(
+                                locale
+                                    .get_string(
+                                        match overlay_settings.overlay_type {
+                                            OverlayType::LevelComplete => "next",
+                                            _ => "return",
+                                        },
+                                        &locales,
+                                        &profile,
+                                    )
+                                    .unwrap_or(&"String not found".to_string()),
+                                text_settings.style_menu_dark.clone(),
+                            )
+                            .with_alignment(text_settings.alignment),
+                            transform: Transform::from_xyz(0., -10., 10.)
+                                .with_scale(Vec3::new(0.75, 0.75, 1.)),
+                            ..default()
+                        });
+                    })
+                    .insert(ButtonVariable(
+                        overlay_settings.overlay_type == OverlayType::LevelComplete,
+                    ))
+                    .insert(OverlayButton);
+            }
         });
 }
@@ -1,11 +1,16 @@
 use super::{
-    components::{ButtonMenu, ButtonRestart, ButtonVariable, UiBackground, UiRootNode},
+    components::{
+        ButtonEndRun, ButtonMenu, ButtonNewRun, ButtonRestart, ButtonShare, ButtonVariable,
+        UiBackground, UiRootNode,
+    },
     resources::{OverlaySettings, OverlayType},
 };
 use crate::{
+    daily::write_summary,
     dialog::resources::DialogSettings,
+    enums::{Generated, PlayMode},
     functions::switch_state,
-    resources::{LoadState, Profile},
+    resources::{Campaign, EndlessRun, GenerateTask, LoadState, Profile, Progress},
     states::AppState,
 };
 use bevy::{
@@ -16,17 +21,32 @@ use bevy::{
 };
 use interactable::components::ReleasedLeft;
 
+type RunButtons<'w, 's> = (
+    Query<'w, 's, (), (With<ButtonEndRun>, With<ReleasedLeft>)>,
+    Query<'w, 's, (), (With<ButtonNewRun>, With<ReleasedLeft>)>,
+);
+type OverlayResources<'a> = (
+    Res<'a, OverlaySettings>,
+    ResMut<'a, Profile>,
+    Res<'a, Campaign>,
+    ResMut<'a, Progress>,
+    ResMut<'a, EndlessRun>,
+    ResMut<'a, GenerateTask>,
+);
 pub fn button_system(
     menu_button_query: Query<&ButtonMenu, With<ReleasedLeft>>,
     variable_button_query: Query<&ButtonVariable, With<ReleasedLeft>>,
     restart_button_query: Query<&ButtonRestart, With<ReleasedLeft>>,
+    share_button_query: Query<&ButtonShare, With<ReleasedLeft>>,
+    (end_run_query, new_run_query): RunButtons,
     (mut app_state, mut dialog_settings, mut load_state): (
         ResMut<State<AppState>>,
         ResMut<DialogSettings>,
         ResMut<LoadState>,
     ),
-    (overlay_settings, profile): (Res<OverlaySettings>, Res<Profile>),
+    (overlay_settings, mut profile, campaign, mut progress, mut run, mut task): OverlayResources,
 ) {
+    let endless = matches!(load_state.generated, Some((Generated::Endless(_), _)));
     if !menu_button_query.is_empty() {
         switch_state(
             Some(AppState::LevelSelection),
@@ -35,27 +55,27 @@ pub fn button_system(
         );
     }
     for bt in variable_button_query.iter() {
-        if bt.0 {
-            if overlay_settings.level_id < 5 {
-                load_state.ids = Some((overlay_settings.stage_id, overlay_settings.level_id + 1));
-                load_state.filename = Some(format!(
-                    "assets/levels/{}/{}.lvl",
-                    overlay_settings.stage_id + 1,
-                    overlay_settings.level_id + 2
-                ));
-                switch_state(Some(AppState::Level), &mut app_state, &mut load_state);
-            } else if overlay_settings.level_id == 5 && overlay_settings.stage_id < 5 {
-                if profile.is_unlocked(overlay_settings.stage_id + 1) {
-                    load_state.ids = Some((overlay_settings.stage_id + 1, 0));
-                    load_state.filename = Some(format!(
-                        "assets/levels/{}/{}.lvl",
-                        overlay_settings.stage_id + 2,
-                        1
-                    ));
+        if bt.0 && endless {
+            // The next puzzle of the run, at the difficulty adapted to the last ones
+            run.load_puzzle(&mut task, AppState::Overlay);
+        } else if bt.0 {
+            let (stage_id, level_id) = (overlay_settings.stage_id, overlay_settings.level_id);
+            match campaign.next_level(stage_id, level_id) {
+                Some((next_stage, next_level))
+                    if next_stage == stage_id || campaign.is_unlocked(next_stage, &profile) =>
+                {
+                    load_state.ids = Some((next_stage, next_level));
+                    load_state.generated = None;
+                    load_state.filename = campaign.get_path(next_stage, next_level);
                     switch_state(Some(AppState::Level), &mut app_state, &mut load_state);
-                } else {
+                }
+                next => {
                     *dialog_settings = DialogSettings {
-                        text: "need-points".to_string(),
+                        text: match next {
+                            Some(_) => "need-points",
+                            None => "last-level",
+                        }
+                        .to_string(),
                         width: 800.,
                         height: 400.,
                         x: 0.,
@@ -63,23 +83,46 @@ pub fn button_system(
                     };
                     app_state.push(AppState::Dialog).unwrap();
                 }
-            } else {
-                *dialog_settings = DialogSettings {
-                    text: "last-level".to_string(),
-                    width: 800.,
-                    height: 400.,
-                    x: 0.,
-                    y: 0.,
-                };
-                app_state.push(AppState::Dialog).unwrap();
             }
         } else {
             app_state.pop().unwrap();
         }
     }
     if !restart_button_query.is_empty() {
+        // Restarting discards the saved progress, which practice runs don't touch
+        let id = campaign.get_id(overlay_settings.stage_id, overlay_settings.level_id);
+        if let Some(id) = id.filter(|_| load_state.mode == PlayMode::Normal) {
+            progress.levels.remove(id);
+        }
         switch_state(Some(AppState::Level), &mut app_state, &mut load_state);
     }
+    // The summary is shown from the level, the overlay can't be entered again right away
+    if !end_run_query.is_empty() {
+        run.ended = true;
+        app_state.pop().unwrap();
+    }
+    if !new_run_query.is_empty() {
+        run.start();
+        run.load_puzzle(&mut task, AppState::Overlay);
+        profile.count_endless_run();
+    }
+    for bt in share_button_query.iter() {
+        let text = match write_summary(&bt.0) {
+            Ok(_) => "daily-shared",
+            Err(e) => {
+                eprintln!("Could not save the daily result: {}", e);
+                "daily-not-shared"
+            }
+        };
+        *dialog_settings = DialogSettings {
+            text: text.to_string(),
+            width: 800.,
+            height: 400.,
+            x: 0.,
+            y: 0.,
+        };
+        app_state.push(AppState::Dialog).unwrap();
+    }
 }
 
 pub fn hotkey_system(
@@ -0,0 +1,113 @@
+use crate::{
+    constants::{CUSTOM_DIR, CUSTOM_PREFIX, EDITOR_PACK, PACK_FILE},
+    functions::get_user_path,
+    parser::try_board_from_file,
+    storage::{load_ron, save_ron, write_atomic, Loaded},
+    structs::{LevelEntry, StageEntry},
+};
+use ron::{de::from_reader, ser::PrettyConfig};
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Limits, so that the ids of the levels fit into the level selection
+const MAX_PACKS: usize = 64;
+const MAX_PACK_LEVELS: usize = 64;
+
+/// Returns the directory of the players' level packs. Every pack is a directory in it
+/// with the level files and a pack.ron that lists them, like a stage of the campaign:
+/// `(name: "My pack", author: "Me", levels: [(id: "first", file: "first.lvl", name: "1")])`
+pub fn get_custom_path() -> PathBuf {
+    let path = get_user_path().join(CUSTOM_DIR);
+    let _ = fs::create_dir_all(&path);
+    path
+}
+
+/// Reads all packs, in the order of their directory names. Broken packs are skipped.
+pub fn load_packs() -> Vec<StageEntry> {
+    let mut dirs: Vec<PathBuf> = match fs::read_dir(get_custom_path()) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    dirs.sort();
+    dirs.iter()
+        .filter_map(|dir| load_pack(dir))
+        .take(MAX_PACKS)
+        .collect()
+}
+
+/// The ids of the levels get the pack as prefix, so they can't clash with the campaign
+/// or other packs, and the files are made absolute for the parser
+fn load_pack(dir: &Path) -> Option<StageEntry> {
+    let dir_name = dir.file_name()?.to_str()?;
+    let file = File::open(dir.join(PACK_FILE)).ok()?;
+    let mut pack: StageEntry = match from_reader(file) {
+        Ok(pack) => pack,
+        Err(e) => {
+            eprintln!("Error reading level pack {}: {}", dir_name, e);
+            return None;
+        }
+    };
+    pack.unlock_points = 0;
+    // Broken levels would crash the game once they are loaded
+    pack.levels
+        .retain(|level| match try_board_from_file(&dir.join(&level.file)) {
+            Ok(_) => true,
+            Err(e) => {
+                eprintln!("Skipping level {} of pack {}: {}", level.file, dir_name, e);
+                false
+            }
+        });
+    pack.levels.truncate(MAX_PACK_LEVELS);
+    for level in pack.levels.iter_mut() {
+        level.id = format!("{}{}/{}", CUSTOM_PREFIX, dir_name, level.id);
+        level.file = dir.join(&level.file).to_string_lossy().into_owned();
+    }
+    (!pack.levels.is_empty()).then_some(pack)
+}
+
+/// Adds a level made in the editor to a pack of its own and returns the path of its file
+pub fn save_editor_level(contents: &str) -> io::Result<PathBuf> {
+    let dir = get_custom_path().join(EDITOR_PACK);
+    fs::create_dir_all(&dir)?;
+    let pack_path = dir.join(PACK_FILE);
+    let mut pack: StageEntry = match load_ron(&pack_path) {
+        Loaded::Ok(pack) | Loaded::Restored(pack) => pack,
+        Loaded::Missing => StageEntry {
+            name: "Editor".to_string(),
+            author: String::new(),
+            unlock_points: 0,
+            levels: Vec::new(),
+        },
+        // Saving a new list would drop the levels of the broken one, it was moved aside instead
+        Loaded::Broken => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is broken and was moved aside", pack_path.display()),
+            ))
+        }
+    };
+    // Entries added by hand can use the numbers as ids too
+    let no = (1..)
+        .find(|no: &u32| {
+            !dir.join(format!("{}.lvl", no)).exists()
+                && !pack.levels.iter().any(|level| level.id == no.to_string())
+        })
+        .unwrap_or_default();
+    let file = format!("{}.lvl", no);
+    let path = dir.join(&file);
+    write_atomic(&path, contents)?;
+    pack.levels.push(LevelEntry {
+        id: no.to_string(),
+        file,
+        name: no.to_string(),
+    });
+    save_ron(&pack_path, &pack, PrettyConfig::new().depth_limit(2))?;
+    Ok(path)
+}
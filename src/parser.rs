@@ -2,131 +2,171 @@ use crate::{
     components::ColumnHint,
     enums::{CellType, HintDirection, HintType},
     functions::get_base_path,
-    structs::BoardConfig,
+    structs::{BoardConfig, LevelMeta, LevelText},
+};
+use std::{
+    fs,
+    path::Path,
+    str::{FromStr, Lines, Split},
 };
-use std::{fs, str::Lines};
 
 const DONT_MESS: &str = "Please don't mess with my files";
 const EXPECTED_NO: &str = "Expected a number";
 const TOO_FEW_ARGS: &str = "Expected more arguments";
+const OUTSIDE: &str = "Hint outside of the board";
+
+/// Prefixes of the optional metadata lines at the end of a file
+const TITLE: &str = "title:";
+const AUTHOR: &str = "author:";
+const DIFFICULTY: &str = "difficulty:";
 
-/// Receives a file and creates a BoardConfig from it
+/// Receives a file and creates a BoardConfig from it. Only for the files of the game,
+/// a broken one is a bug.
 pub fn board_from_file(filename: &str) -> BoardConfig {
-    let mut cells = Vec::new();
     let file = fs::read_to_string(get_base_path().join(filename))
         .unwrap_or_else(|_| panic!("File \"{}\" not found!", filename));
+    board_from_str(&file).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Reads a level file of the players, which may be broken
+pub fn try_board_from_file(path: &Path) -> Result<BoardConfig, String> {
+    let file = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    board_from_str(&file)
+}
+
+fn error(message: &str, line_no: usize) -> String {
+    format!("{} in line {}", message, line_no)
+}
+
+/// Creates a BoardConfig from the contents of a level file
+pub fn board_from_str(file: &str) -> Result<BoardConfig, String> {
+    let mut cells = Vec::new();
     let mut lines = file.lines();
     let mut line_no = 1;
     let (width, height) = parse_tuple(
-        lines
-            .next()
-            .unwrap_or_else(|| panic!("{} in line {}", DONT_MESS, line_no)),
+        lines.next().ok_or_else(|| error(DONT_MESS, line_no))?,
         line_no,
-    );
+    )?;
     line_no += 1;
-    assert!(height > 0, "Height must at least be 1.");
-    assert!(width > 0, "Width must at least be 1.");
-
-    (0..height).into_iter().for_each(|_| {
-        let l = lines
-            .next()
-            .unwrap_or_else(|| panic!("{} in line {}", DONT_MESS, line_no));
-        assert!(
-            l.len() == width,
-            "Lines must have specified width: {}",
-            width,
-        );
+    if height == 0 {
+        return Err("Height must at least be 1.".to_string());
+    }
+    if width == 0 {
+        return Err("Width must at least be 1.".to_string());
+    }
+
+    for _ in 0..height {
+        let l = lines.next().ok_or_else(|| error(DONT_MESS, line_no))?;
+        if l.chars().count() != width {
+            return Err(format!("Lines must have specified width: {}", width));
+        }
         cells.push(parse_grid_row(l));
         line_no += 1;
-    });
+    }
 
     let mut hints = Vec::new();
 
     let num_hints: usize = lines
         .next()
-        .unwrap_or_else(|| panic!("{} in line {}", DONT_MESS, line_no))
+        .ok_or_else(|| error(DONT_MESS, line_no))?
+        .trim()
         .parse()
-        .unwrap_or_else(|_| panic!("{} in line {}", EXPECTED_NO, line_no));
+        .map_err(|_| error(EXPECTED_NO, line_no))?;
     line_no += 1;
-    (0..num_hints).into_iter().for_each(|_| {
-        let l = lines
-            .next()
-            .unwrap_or_else(|| panic!("{} in line {}", DONT_MESS, line_no));
-        hints.push(parse_hint(l, line_no));
+    for _ in 0..num_hints {
+        let l = lines.next().ok_or_else(|| error(DONT_MESS, line_no))?;
+        let hint = parse_hint(l, line_no)?;
+        if hint.x >= width || hint.y >= height {
+            return Err(error(OUTSIDE, line_no));
+        }
+        hints.push(hint);
         line_no += 1;
-    });
-    BoardConfig {
+    }
+    let mut text = None;
+    let mut meta = LevelMeta::default();
+    while let Some(line) = lines.next() {
+        if let Some(title) = line.strip_prefix(TITLE) {
+            meta.title = Some(title.trim().to_string());
+        } else if let Some(author) = line.strip_prefix(AUTHOR) {
+            meta.author = Some(author.trim().to_string());
+        } else if let Some(difficulty) = line.strip_prefix(DIFFICULTY) {
+            meta.difficulty = Some(
+                difficulty
+                    .trim()
+                    .parse()
+                    .map_err(|_| error(EXPECTED_NO, line_no))?,
+            );
+        } else if !line.trim().is_empty() {
+            text = Some(parse_level_text(line, &mut lines, line_no)?);
+            line_no += 1;
+        }
+        line_no += 1;
+    }
+    Ok(BoardConfig {
         width,
         height,
         cells,
         hints,
-        text: parse_level_text(&mut lines, line_no),
-    }
+        text,
+        meta,
+    })
+}
+
+/// Parses the anchor and the following line with the text.
+/// Inline text starts with a quotation mark, otherwise the line is a locale key.
+fn parse_level_text(
+    anchor: &str,
+    lines: &mut Lines,
+    line_no: usize,
+) -> Result<(i32, i32, LevelText), String> {
+    let (x, y) = parse_signed_tuple(anchor, line_no)?;
+    let line = lines.next().ok_or_else(|| error(DONT_MESS, line_no + 1))?;
+    let text = match line.strip_prefix('"') {
+        Some(inline) => {
+            let inline = inline.strip_suffix('"').unwrap_or(inline);
+            LevelText::Inline(inline.replace("\\n", "\n"))
+        }
+        None => LevelText::Key(line.to_string()),
+    };
+    Ok((x, y, text))
 }
 
-fn parse_level_text(lines: &mut Lines, line_no: usize) -> Option<(i32, i32, String)> {
-    let line = lines.next();
-    line?;
-    let (x, y) = parse_tuple(line.unwrap(), line_no);
-    let line = lines
+/// Parses the next comma separated number of a line
+fn parse_next<T: FromStr>(split: &mut Split<char>, line_no: usize) -> Result<T, String> {
+    split
         .next()
-        .unwrap_or_else(|| panic!("{} in line {}", DONT_MESS, line_no));
+        .ok_or_else(|| error(TOO_FEW_ARGS, line_no))?
+        .trim()
+        .parse()
+        .map_err(|_| error(EXPECTED_NO, line_no))
+}
 
-    Some((x as i32, y as i32, line.to_string()))
+/// Like parse_tuple, but allows negative numbers
+fn parse_signed_tuple(line: &str, line_no: usize) -> Result<(i32, i32), String> {
+    let mut split = line.split(',');
+    Ok((
+        parse_next(&mut split, line_no)?,
+        parse_next(&mut split, line_no)?,
+    ))
 }
 
 /// Function to parse a numeric tuple in a file
-fn parse_tuple(line: &str, line_no: usize) -> (usize, usize) {
+fn parse_tuple(line: &str, line_no: usize) -> Result<(usize, usize), String> {
     let mut split = line.split(',');
-    let s = split
-        .next()
-        .unwrap_or_else(|| panic!("{} in line {}", TOO_FEW_ARGS, line_no));
-    let x = s
-        .trim()
-        .parse()
-        .unwrap_or_else(|_| panic!("{} in line {}", EXPECTED_NO, line_no));
-    let s = split
-        .next()
-        .unwrap_or_else(|| panic!("{} in line {}", TOO_FEW_ARGS, line_no));
-    let y = s
-        .trim()
-        .parse()
-        .unwrap_or_else(|_| panic!("{} in line {}", EXPECTED_NO, line_no));
-    (x, y)
+    Ok((
+        parse_next(&mut split, line_no)?,
+        parse_next(&mut split, line_no)?,
+    ))
 }
 
 /// Function to parse a column-hint in a file
-fn parse_hint(line: &str, line_no: usize) -> ColumnHint {
+fn parse_hint(line: &str, line_no: usize) -> Result<ColumnHint, String> {
     let mut split = line.split(',');
-    let s = split
-        .next()
-        .unwrap_or_else(|| panic!("{} in line {}", TOO_FEW_ARGS, line_no));
-    let x = s
-        .trim()
-        .parse()
-        .unwrap_or_else(|_| panic!("{} in line {}", EXPECTED_NO, line_no));
-    let s = split
-        .next()
-        .unwrap_or_else(|| panic!("{} in line {}", TOO_FEW_ARGS, line_no));
-    let y = s
-        .trim()
-        .parse()
-        .unwrap_or_else(|_| panic!("{} in line {}", EXPECTED_NO, line_no));
-    let s = split
-        .next()
-        .unwrap_or_else(|| panic!("{} in line {}", TOO_FEW_ARGS, line_no));
-    let hint_dir: i8 = s
-        .trim()
-        .parse()
-        .unwrap_or_else(|_| panic!("{} in line {}", EXPECTED_NO, line_no));
-    let s = split
-        .next()
-        .unwrap_or_else(|| panic!("{} in line {}", TOO_FEW_ARGS, line_no));
-    let hint_type = s
-        .trim()
-        .parse()
-        .unwrap_or_else(|_| panic!("{} in line {}", EXPECTED_NO, line_no));
-    ColumnHint {
+    let x = parse_next(&mut split, line_no)?;
+    let y = parse_next(&mut split, line_no)?;
+    let hint_dir: i8 = parse_next(&mut split, line_no)?;
+    let hint_type: i32 = parse_next(&mut split, line_no)?;
+    Ok(ColumnHint {
         x,
         y,
         dir: match hint_dir {
@@ -141,7 +181,7 @@ fn parse_hint(line: &str, line_no: usize) -> ColumnHint {
             0 => HintType::None,
             _ => HintType::Some,
         },
-    }
+    })
 }
 
 /// Function to parse a line of a file to a row in the grid
@@ -162,8 +202,8 @@ fn parse_grid_row(line: &str) -> Vec<(Option<CellType>, bool)> {
 }
 
 pub fn board_to_string(board_config: BoardConfig) -> String {
-    format!(
-        "{},{}\n{}",
+    let mut s = format!(
+        "{},{}\n{}\n{}",
         board_config.cells[0].len(),
         board_config.cells.len(),
         board_config
@@ -183,6 +223,90 @@ pub fn board_to_string(board_config: BoardConfig) -> String {
                     .collect::<String>()
             })
             .collect::<Vec<String>>()
-            .join("\n")
+            .join("\n"),
+        board_config.hints.len()
+    );
+    for hint in &board_config.hints {
+        s.push_str(&format!("\n{}", hint_to_string(hint)));
+    }
+    if let Some((x, y, text)) = &board_config.text {
+        let text = match text {
+            LevelText::Key(key) => key.clone(),
+            LevelText::Inline(inline) => format!("\"{}\"", inline.replace('\n', "\\n")),
+        };
+        s.push_str(&format!("\n{},{}\n{}", x, y, text));
+    }
+    let meta = &board_config.meta;
+    if let Some(title) = &meta.title {
+        s.push_str(&format!("\n{}{}", TITLE, title));
+    }
+    if let Some(author) = &meta.author {
+        s.push_str(&format!("\n{}{}", AUTHOR, author));
+    }
+    if let Some(difficulty) = meta.difficulty {
+        s.push_str(&format!("\n{}{}", DIFFICULTY, difficulty));
+    }
+    s
+}
+
+/// Inverse of parse_hint
+fn hint_to_string(hint: &ColumnHint) -> String {
+    format!(
+        "{},{},{},{}",
+        hint.x,
+        hint.y,
+        match hint.dir {
+            HintDirection::Down => 0,
+            HintDirection::LeftDown => -1,
+            HintDirection::RightDown => 1,
+            HintDirection::LeftUp => -2,
+            HintDirection::RightUp => 2,
+            HintDirection::Up => 3,
+        },
+        match hint.hint_type {
+            HintType::None => 0,
+            _ => 1,
+        }
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEVEL: &str = "5,3
+.0.2.
+31045
+.2.0.
+1
+1,0,0,1
+0,4
+\"Hello\\nworld\"
+title:Round trip
+author:Someone
+difficulty:3";
+
+    #[test]
+    fn board_round_trip() {
+        let config = board_from_str(LEVEL).unwrap();
+        assert_eq!(
+            config.meta,
+            LevelMeta {
+                title: Some("Round trip".to_string()),
+                author: Some("Someone".to_string()),
+                difficulty: Some(3),
+            }
+        );
+        assert_eq!(
+            config.text,
+            Some((0, 4, LevelText::Inline("Hello\nworld".to_string())))
+        );
+        assert_eq!(board_to_string(config), LEVEL);
+    }
+
+    #[test]
+    fn broken_board_is_an_error() {
+        assert!(board_from_str("5,3\n.0.2.").is_err());
+        assert!(board_from_str("3,1\n000\n1\n7,0,0,0").is_err());
+    }
+}
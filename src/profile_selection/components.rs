@@ -0,0 +1,20 @@
+use bevy::prelude::Component;
+
+/// Clicking it switches to the profile with this id
+#[derive(Component)]
+pub struct ProfileButton(pub String);
+/// Shows the name of the profile with this id
+#[derive(Component)]
+pub struct ProfileName(pub String);
+
+#[derive(Component)]
+pub struct ButtonNew;
+#[derive(Component)]
+pub struct ButtonRename;
+#[derive(Component)]
+pub struct ButtonDelete;
+#[derive(Component)]
+pub struct ButtonReturn;
+
+#[derive(Component)]
+pub struct ProfileSelectionButton;
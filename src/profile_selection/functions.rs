@@ -0,0 +1,40 @@
+use crate::{
+    resources::{Campaign, Profile, Profiles, Progress, Replays},
+    storage::Saved,
+};
+use bevy::window::{WindowMode, Windows};
+
+/// Name of a profile as shown in the list, with a cursor while it's typed
+pub fn display_name(name: &str, editing: bool) -> String {
+    if editing {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Saves the files of the current profile and loads the ones of another profile
+pub fn load_profile(
+    id: &str,
+    (profiles, campaign): (&mut Profiles, &Campaign),
+    (profile, progress, replays): (&mut Profile, &mut Progress, &mut Replays),
+    wnds: &mut Windows,
+) {
+    profile.save();
+    progress.save();
+    replays.save();
+    profiles.set_current(id);
+    let dir = profiles.get_current_dir();
+    *profile = Profile::new(campaign, &dir);
+    *progress = Progress::new(&dir);
+    *replays = Replays::new(&dir);
+    // The window mode is a setting of the profile too
+    for wnd in wnds.iter_mut() {
+        if profile.fullscreen {
+            wnd.set_mode(WindowMode::Fullscreen);
+        } else {
+            wnd.set_mode(WindowMode::Windowed);
+            wnd.set_maximized(true);
+        }
+    }
+}
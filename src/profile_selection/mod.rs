@@ -0,0 +1,35 @@
+mod components;
+mod functions;
+pub mod resources;
+mod setup;
+mod systems;
+
+use self::{components::ProfileSelectionButton, resources::NameInput, setup::setup, systems::*};
+use crate::{
+    cleanup_system, components::RootComponent, states::AppState, systems::menu_button_hovered,
+};
+use bevy::prelude::{App, ParallelSystemDescriptorCoercion, SystemSet};
+use interactable::InteractLabel;
+
+const STATE: AppState = AppState::ProfileSelection;
+
+pub fn prepare_profile_selection(app: &mut App) {
+    app.init_resource::<NameInput>()
+        .add_system_set(SystemSet::on_enter(STATE).with_system(setup))
+        .add_system_set(
+            SystemSet::on_update(STATE)
+                .with_system(profile_click_system.after(InteractLabel::Interact))
+                .with_system(new_button_click_system.after(InteractLabel::Interact))
+                .with_system(rename_button_click_system.after(InteractLabel::Interact))
+                .with_system(delete_button_click_system.after(InteractLabel::Interact))
+                .with_system(return_button_click_system.after(InteractLabel::Interact))
+                .with_system(
+                    menu_button_hovered::<ProfileSelectionButton>.after(InteractLabel::Interact),
+                )
+                .with_system(name_input_system.after(rename_button_click_system))
+                .with_system(profile_name_system.after(name_input_system))
+                .with_system(hotkey_system.after(name_input_system))
+                .with_system(window_resize_system),
+        )
+        .add_system_set(SystemSet::on_exit(STATE).with_system(cleanup_system::<RootComponent>));
+}
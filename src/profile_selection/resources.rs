@@ -0,0 +1,8 @@
+/// State of the name input on the profile screen
+#[derive(Default)]
+pub struct NameInput {
+    /// The name of the current profile is being typed
+    pub editing: bool,
+    /// The delete button was clicked once and waits for the confirmation
+    pub confirm_delete: bool,
+}
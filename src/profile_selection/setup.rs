@@ -0,0 +1,184 @@
+use super::{
+    components::{
+        ButtonDelete, ButtonNew, ButtonRename, ButtonReturn, ProfileButton, ProfileName,
+        ProfileSelectionButton,
+    },
+    functions::display_name,
+    resources::NameInput,
+};
+use crate::{
+    assets::LocaleAsset,
+    bundles::MenuButtonBundle,
+    components::RootComponent,
+    constants::{MAX_PROFILES, MED_SCALE, RADIUS, Z_INDEX_TEXT},
+    functions::rescale_board,
+    resources::{GameColors, LocaleAssets, Profile, Profiles, TextSettings},
+};
+use bevy::{
+    hierarchy::BuildChildren,
+    math::{Vec2, Vec3},
+    prelude::{
+        default, shape::Quad, AssetServer, Assets, ColorMesh2dBundle, Commands, Component, Entity,
+        Mesh, Res, ResMut, SpatialBundle, Transform,
+    },
+    sprite::SpriteBundle,
+    text::{Text, Text2dBundle},
+    window::Windows,
+};
+
+/// Size of the buttons of the profile list
+const PROFILE_BUTTON: (f32, f32) = (560., 120.);
+
+type StandardResources<'a> = (
+    Res<'a, GameColors>,
+    Res<'a, LocaleAssets>,
+    Res<'a, Profile>,
+    Res<'a, TextSettings>,
+);
+pub fn setup(
+    mut commands: Commands,
+    wnds: Res<Windows>,
+    (game_colors, locale, profile, text_settings): StandardResources,
+    (profiles, mut input): (Res<Profiles>, ResMut<NameInput>),
+    asset_server: Res<AssetServer>,
+    (mut meshes, locales): (ResMut<Assets<Mesh>>, Res<Assets<LocaleAsset>>),
+) {
+    input.confirm_delete = false;
+    let mut children = vec![commands
+        .spawn_bundle(SpriteBundle {
+            texture: asset_server.load("branding/logo.png"),
+            transform: Transform::from_xyz(0., 2. * RADIUS * MED_SCALE, Z_INDEX_TEXT),
+            ..default()
+        })
+        .id()];
+
+    // Two columns, filled row by row
+    for (i, entry) in profiles.get_entries().iter().enumerate() {
+        let x = if i % 2 == 0 { -320. } else { 320. };
+        let y = RADIUS - (i / 2) as f32 * 1.5 * RADIUS;
+        let current = entry.id == profiles.get_current();
+        let button = commands
+            .spawn_bundle(MenuButtonBundle::new(
+                Transform::from_xyz(x, y, 0.9),
+                PROFILE_BUTTON,
+                game_colors.menu_button.clone(),
+                &mut meshes,
+            ))
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(Text2dBundle {
+                        text: Text::from_section(
+                            display_name(&entry.name, current && input.editing),
+                            text_settings.style_menu_dark.clone(),
+                        )
+                        .with_alignment(text_settings.alignment),
+                        transform: Transform::from_xyz(0., -10., 10.)
+                            .with_scale(Vec3::new(0.75, 0.75, 1.)),
+                        ..default()
+                    })
+                    .insert(ProfileName(entry.id.clone()));
+                // The current profile is framed
+                if current {
+                    parent.spawn_bundle(ColorMesh2dBundle {
+                        mesh: meshes
+                            .add(Mesh::from(Quad::new(
+                                Vec2::new(PROFILE_BUTTON.0, PROFILE_BUTTON.1) + 16.,
+                            )))
+                            .into(),
+                        material: game_colors.white.clone(),
+                        transform: Transform::from_xyz(0., 0., -0.1),
+                        ..default()
+                    });
+                }
+            })
+            .insert(ProfileButton(entry.id.clone()))
+            .insert(ProfileSelectionButton)
+            .id();
+        children.push(button);
+    }
+
+    let count = profiles.get_entries().len();
+    if count < MAX_PROFILES {
+        children.push(spawn_button(
+            &mut commands,
+            (&game_colors, &locale, &profile, &text_settings),
+            (&mut meshes, &locales),
+            (-600., "new-profile"),
+            ButtonNew,
+        ));
+    }
+    children.push(spawn_button(
+        &mut commands,
+        (&game_colors, &locale, &profile, &text_settings),
+        (&mut meshes, &locales),
+        (-200., "rename-profile"),
+        ButtonRename,
+    ));
+    // The last profile can't be deleted
+    if count > 1 {
+        children.push(spawn_button(
+            &mut commands,
+            (&game_colors, &locale, &profile, &text_settings),
+            (&mut meshes, &locales),
+            (200., "delete-profile"),
+            ButtonDelete,
+        ));
+    }
+    children.push(spawn_button(
+        &mut commands,
+        (&game_colors, &locale, &profile, &text_settings),
+        (&mut meshes, &locales),
+        (600., "return"),
+        ButtonReturn,
+    ));
+
+    let mut root_transform = Transform::identity();
+    for wnd in wnds.iter() {
+        // TODO: Remove hard-coded width/height
+        rescale_board(10, 6, 1, wnd.width(), wnd.height(), &mut root_transform);
+    }
+
+    commands
+        .spawn()
+        .push_children(&children)
+        .insert_bundle(SpatialBundle::from_transform(root_transform))
+        .insert(RootComponent);
+}
+
+/// Spawns a button of the bottom row with a localized text
+fn spawn_button<T: Component>(
+    commands: &mut Commands,
+    (game_colors, locale, profile, text_settings): (
+        &GameColors,
+        &LocaleAssets,
+        &Profile,
+        &TextSettings,
+    ),
+    (meshes, locales): (&mut Assets<Mesh>, &Assets<LocaleAsset>),
+    (x, key): (f32, &str),
+    marker: T,
+) -> Entity {
+    commands
+        .spawn_bundle(MenuButtonBundle::new(
+            Transform::from_xyz(x, -4.5 * RADIUS, 0.9),
+            (270., 170.),
+            game_colors.menu_button.clone(),
+            meshes,
+        ))
+        .with_children(|parent| {
+            parent.spawn_bundle(Text2dBundle {
+                text: Text::from_section(
+                    locale
+                        .get_string(key, locales, profile)
+                        .unwrap_or(&"String not found".to_string()),
+                    text_settings.style_menu_dark.clone(),
+                )
+                .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(0., -10., 10.).with_scale(Vec3::new(0.75, 0.75, 1.)),
+                ..default()
+            });
+        })
+        .insert(marker)
+        .insert(ProfileSelectionButton)
+        .id()
+}
@@ -0,0 +1,240 @@
+use super::{
+    components::{ButtonDelete, ButtonNew, ButtonRename, ButtonReturn, ProfileButton, ProfileName},
+    functions::{display_name, load_profile},
+    resources::NameInput,
+};
+use crate::{
+    assets::LocaleAsset,
+    components::RootComponent,
+    constants::MAX_PROFILE_NAME,
+    functions::{rescale_board, switch_state},
+    resources::{Campaign, LoadState, LocaleAssets, Profile, Profiles, Progress, Replays},
+    states::AppState,
+};
+use bevy::{
+    hierarchy::Children,
+    input::Input,
+    prelude::{Assets, EventReader, KeyCode, Query, Res, ResMut, State, Transform, With},
+    text::Text,
+    window::{ReceivedCharacter, WindowResized, Windows},
+};
+use interactable::components::ReleasedLeft;
+
+type ProfileResources<'a> = (
+    ResMut<'a, Profiles>,
+    ResMut<'a, Profile>,
+    ResMut<'a, Progress>,
+    ResMut<'a, Replays>,
+);
+
+/// Clicking another profile switches to it
+pub fn profile_click_system(
+    button_query: Query<&ProfileButton, With<ReleasedLeft>>,
+    (mut profiles, mut profile, mut progress, mut replays): ProfileResources,
+    (campaign, mut input, mut wnds): (Res<Campaign>, ResMut<NameInput>, ResMut<Windows>),
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    for ProfileButton(id) in button_query.iter() {
+        if id == profiles.get_current() {
+            continue;
+        }
+        input.editing = false;
+        load_profile(
+            id,
+            (&mut profiles, &campaign),
+            (&mut profile, &mut progress, &mut replays),
+            &mut wnds,
+        );
+        // Rebuilt in the language of the profile
+        switch_state(
+            Some(AppState::ProfileSelection),
+            &mut app_state,
+            &mut load_state,
+        );
+    }
+}
+
+/// Creates a profile, switches to it and lets the player type its name
+pub fn new_button_click_system(
+    button_query: Query<&ButtonNew, With<ReleasedLeft>>,
+    (mut profiles, mut profile, mut progress, mut replays): ProfileResources,
+    (campaign, mut input, mut wnds): (Res<Campaign>, ResMut<NameInput>, ResMut<Windows>),
+    (locale, locales): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>),
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if button_query.is_empty() {
+        return;
+    }
+    let name = format!(
+        "{} {}",
+        locale
+            .get_string("player", &locales, &profile)
+            .unwrap_or(&"String not found".to_string()),
+        profiles.get_entries().len() + 1
+    );
+    let (lang, fullscreen) = (profile.lang, profile.fullscreen);
+    let id = profiles.create(name);
+    load_profile(
+        &id,
+        (&mut profiles, &campaign),
+        (&mut profile, &mut progress, &mut replays),
+        &mut wnds,
+    );
+    // A new profile keeps the language and window of the previous one
+    profile.lang = lang;
+    profile.fullscreen = fullscreen;
+    input.editing = true;
+    switch_state(
+        Some(AppState::ProfileSelection),
+        &mut app_state,
+        &mut load_state,
+    );
+}
+
+pub fn rename_button_click_system(
+    button_query: Query<&ButtonRename, With<ReleasedLeft>>,
+    mut input: ResMut<NameInput>,
+) {
+    if !button_query.is_empty() {
+        input.editing = !input.editing;
+    }
+}
+
+/// The first click asks for a confirmation, the second one deletes the current profile
+pub fn delete_button_click_system(
+    button_query: Query<&Children, (With<ButtonDelete>, With<ReleasedLeft>)>,
+    mut text_query: Query<&mut Text>,
+    (mut profiles, mut profile, mut progress, mut replays): ProfileResources,
+    (campaign, mut input, mut wnds): (Res<Campaign>, ResMut<NameInput>, ResMut<Windows>),
+    (locale, locales): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>),
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    let children = match button_query.get_single() {
+        Ok(children) => children,
+        Err(_) => return,
+    };
+    if !input.confirm_delete {
+        input.confirm_delete = true;
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = locale
+                    .get_string("delete-confirm", &locales, &profile)
+                    .unwrap_or(&"String not found".to_string())
+                    .to_string();
+            }
+        }
+        return;
+    }
+    let deleted = profiles.get_current().to_string();
+    let next = match profiles
+        .get_entries()
+        .iter()
+        .find(|entry| entry.id != deleted)
+    {
+        Some(entry) => entry.id.clone(),
+        None => return,
+    };
+    input.editing = false;
+    // Switched first, so that the files of the deleted profile are saved before they are removed
+    load_profile(
+        &next,
+        (&mut profiles, &campaign),
+        (&mut profile, &mut progress, &mut replays),
+        &mut wnds,
+    );
+    profiles.delete(&deleted);
+    switch_state(
+        Some(AppState::ProfileSelection),
+        &mut app_state,
+        &mut load_state,
+    );
+}
+
+/// Types the name of the current profile. While typing, the keys don't trigger hotkeys.
+/// Enter and Escape finish it.
+pub fn name_input_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut ev_chars: EventReader<ReceivedCharacter>,
+    (mut profiles, mut input): (ResMut<Profiles>, ResMut<NameInput>),
+    (locale, locales, profile): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>, Res<Profile>),
+) {
+    if !input.editing {
+        // Characters typed before are dropped
+        ev_chars.iter().last();
+        return;
+    }
+    let mut name = profiles.get_current_name().to_string();
+    for ev in ev_chars.iter() {
+        if !ev.char.is_control() && name.chars().count() < MAX_PROFILE_NAME {
+            name.push(ev.char);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        name.pop();
+    }
+    if keys.just_pressed(KeyCode::Return) || keys.just_pressed(KeyCode::Escape) {
+        input.editing = false;
+        // A profile always has a name
+        if name.trim().is_empty() {
+            name = locale
+                .get_string("player", &locales, &profile)
+                .unwrap_or(&"String not found".to_string())
+                .to_string();
+        }
+    }
+    if name != profiles.get_current_name() {
+        let current = profiles.get_current().to_string();
+        profiles.rename(&current, name);
+    }
+    keys.clear();
+}
+
+/// Shows the names after they are typed
+pub fn profile_name_system(
+    mut name_query: Query<(&mut Text, &ProfileName)>,
+    (profiles, input): (Res<Profiles>, Res<NameInput>),
+) {
+    if !profiles.is_changed() && !input.is_changed() {
+        return;
+    }
+    for (mut text, ProfileName(id)) in name_query.iter_mut() {
+        if let Some(entry) = profiles.get_entries().iter().find(|entry| &entry.id == id) {
+            text.sections[0].value =
+                display_name(&entry.name, input.editing && id == profiles.get_current());
+        }
+    }
+}
+
+pub fn return_button_click_system(
+    return_query: Query<&ButtonReturn, With<ReleasedLeft>>,
+    mut input: ResMut<NameInput>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if !return_query.is_empty() {
+        input.editing = false;
+        switch_state(Some(AppState::Home), &mut app_state, &mut load_state);
+    }
+}
+
+pub fn hotkey_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        keys.clear_just_pressed(KeyCode::Escape);
+        switch_state(Some(AppState::Home), &mut app_state, &mut load_state);
+    }
+}
+
+/// On resizing the window, the board is resized too
+pub fn window_resize_system(
+    mut ev_window_resize: EventReader<WindowResized>,
+    mut root_query: Query<&mut Transform, With<RootComponent>>,
+) {
+    for ev in ev_window_resize.iter() {
+        if let Ok(mut root) = root_query.get_single_mut() {
+            // TODO: Remove hard-coded width/height
+            rescale_board(10, 6, 1, ev.width, ev.height, &mut root);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use bevy::prelude::Component;
+
+/// Root of the play button and the timeline
+#[derive(Debug, Component)]
+pub struct ReplayControls;
+
+/// Clicking it starts or pauses the replay, like the space key
+#[derive(Debug, Component)]
+pub struct PlayButton;
+
+/// Clicking or dragging on it jumps to that point of the replay
+#[derive(Debug, Component)]
+pub struct Timeline;
+
+/// Part of the timeline that has been played
+#[derive(Debug, Component)]
+pub struct TimelineFill;
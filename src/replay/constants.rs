@@ -0,0 +1,5 @@
+/// Size of the timeline in a 1920×1080p window
+pub const TIMELINE_WIDTH: f32 = 1200.;
+pub const TIMELINE_HEIGHT: f32 = 16.;
+/// Distance of the controls to the bottom of the window
+pub const CONTROLS_HEIGHT: f32 = 120.;
@@ -0,0 +1,140 @@
+use super::{
+    components::{PlayButton, ReplayControls, Timeline, TimelineFill},
+    constants::{CONTROLS_HEIGHT, TIMELINE_HEIGHT, TIMELINE_WIDTH},
+};
+use crate::{
+    assets::LocaleAsset,
+    constants::Z_INDEX_UI,
+    enums::PlayMode,
+    functions::rescale_board,
+    level::board::Board,
+    resources::{CellMeshes, GameColors, LocaleAssets, Profile, TextSettings},
+    structs::BoardConfig,
+};
+use bevy::{
+    hierarchy::BuildChildren,
+    math::{Vec2, Vec3},
+    prelude::{default, shape::Quad, Assets, Commands, Mesh, SpatialBundle, Transform},
+    sprite::{ColorMaterial, ColorMesh2dBundle},
+    text::{Text, Text2dBundle},
+};
+use interactable::{components::Interactable, shapes::Shape};
+
+/// Spawns the board of the replay with every cell hidden
+pub fn spawn_board(
+    commands: &mut Commands,
+    (wd_width, wd_height): (f32, f32),
+    (config, ids): (&BoardConfig, (u8, u8)),
+    resources: (
+        &CellMeshes,
+        &GameColors,
+        &LocaleAssets,
+        &Profile,
+        &TextSettings,
+    ),
+    assets: (
+        &mut Assets<Mesh>,
+        &mut Assets<ColorMaterial>,
+        &Assets<LocaleAsset>,
+    ),
+) -> Board {
+    let mut root_transform = Transform::identity();
+    rescale_board(
+        config.width,
+        config.height,
+        4,
+        wd_width,
+        wd_height,
+        &mut root_transform,
+    );
+    Board::new(
+        commands,
+        root_transform,
+        config,
+        resources,
+        ids,
+        assets,
+        (None, PlayMode::Normal),
+    )
+}
+
+/// The controls are scaled like a 1920×1080p window and placed at its bottom
+pub fn controls_transform(wd_width: f32, wd_height: f32) -> Transform {
+    let s = (wd_width / 1920.).min(wd_height / 1080.);
+    Transform::from_xyz(0., (CONTROLS_HEIGHT * s - wd_height) / 2., Z_INDEX_UI)
+        .with_scale(Vec3::new(s, s, 1.0))
+}
+
+/// Spawns the play button and the timeline, on which the mistakes are marked
+pub fn spawn_controls(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    (game_colors, text_settings): (&GameColors, &TextSettings),
+    (label, mistakes): (&str, Vec<f32>),
+    transform: Transform,
+) {
+    let tick_mesh = meshes.add(Mesh::from(Quad::new(Vec2::new(4., 2. * TIMELINE_HEIGHT))));
+    commands
+        .spawn_bundle(SpatialBundle::from_transform(transform))
+        .insert(ReplayControls)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(Text2dBundle {
+                    text: Text::from_section(label, text_settings.style_cell.clone())
+                        .with_alignment(text_settings.alignment),
+                    transform: Transform::from_xyz(-TIMELINE_WIDTH / 2. - 180., 0., 1.)
+                        .with_scale(Vec3::new(0.6, 0.6, 1.)),
+                    ..default()
+                })
+                .insert(Interactable {
+                    shape: Shape::Quad(interactable::shapes::Quad {
+                        width: 500.,
+                        height: 100.,
+                    }),
+                    ..default()
+                })
+                .insert(PlayButton);
+            parent
+                .spawn_bundle(ColorMesh2dBundle {
+                    mesh: meshes
+                        .add(Mesh::from(Quad::new(Vec2::new(
+                            TIMELINE_WIDTH,
+                            TIMELINE_HEIGHT,
+                        ))))
+                        .into(),
+                    material: game_colors.gray_medium.clone(),
+                    transform: Transform::from_xyz(0., 0., 1.),
+                    ..default()
+                })
+                .insert(Interactable {
+                    shape: Shape::Quad(interactable::shapes::Quad {
+                        width: TIMELINE_WIDTH,
+                        height: 4. * TIMELINE_HEIGHT,
+                    }),
+                    ..default()
+                })
+                .insert(Timeline);
+            parent
+                .spawn_bundle(ColorMesh2dBundle {
+                    mesh: meshes
+                        .add(Mesh::from(Quad::new(Vec2::new(
+                            TIMELINE_WIDTH,
+                            TIMELINE_HEIGHT,
+                        ))))
+                        .into(),
+                    material: game_colors.blue_medium.clone(),
+                    transform: Transform::from_xyz(-TIMELINE_WIDTH / 2., 0., 2.)
+                        .with_scale(Vec3::new(0., 1., 1.)),
+                    ..default()
+                })
+                .insert(TimelineFill);
+            for fraction in mistakes {
+                parent.spawn_bundle(ColorMesh2dBundle {
+                    mesh: tick_mesh.clone().into(),
+                    material: game_colors.yellow_medium.clone(),
+                    transform: Transform::from_xyz((fraction - 0.5) * TIMELINE_WIDTH, 0., 3.),
+                    ..default()
+                });
+            }
+        });
+}
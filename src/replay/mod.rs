@@ -0,0 +1,36 @@
+mod components;
+mod constants;
+mod functions;
+mod resources;
+mod setup;
+mod systems;
+
+use self::{components::ReplayControls, setup::setup, systems::*};
+use crate::{cleanup_system, components::RootComponent, states::AppState};
+use bevy::{
+    app::App,
+    prelude::{ParallelSystemDescriptorCoercion, SystemSet},
+};
+use interactable::InteractLabel;
+
+const STATE: AppState = AppState::Replay;
+
+pub fn prepare_replay(app: &mut App) {
+    app.add_system_set(SystemSet::on_enter(STATE).with_system(setup))
+        .add_system_set(
+            SystemSet::on_update(STATE)
+                .with_system(controls_system.after(InteractLabel::Interact))
+                .with_system(scrub_system.after(InteractLabel::Interact))
+                .with_system(playback_system.after(controls_system).after(scrub_system))
+                .with_system(apply_system.after(playback_system))
+                .with_system(rebuild_system.after(apply_system))
+                .with_system(hud_system.after(apply_system))
+                .with_system(hide_buttons_system)
+                .with_system(window_resize_system),
+        )
+        .add_system_set(
+            SystemSet::on_exit(STATE)
+                .with_system(cleanup_system::<RootComponent>)
+                .with_system(cleanup_system::<ReplayControls>),
+        );
+}
@@ -0,0 +1,29 @@
+use crate::structs::{BoardConfig, Move};
+
+/// State of the replay, the board itself is rebuilt from the config
+pub struct ReplayState {
+    pub config: BoardConfig,
+    pub ids: (u8, u8),
+    pub moves: Vec<Move>,
+    /// Number of moves shown on the board
+    pub applied: usize,
+    /// Position in the replay in seconds
+    pub time: f32,
+    pub playing: bool,
+    /// Set to step backwards, as uncovered cells can't be hidden again
+    pub rebuild: bool,
+}
+
+impl ReplayState {
+    pub fn duration(&self) -> f32 {
+        self.moves.last().map_or(0., |m| m.time)
+    }
+    /// Jumps to a point of the replay
+    pub fn seek(&mut self, time: f32) {
+        let time = time.clamp(0., self.duration());
+        if self.applied > 0 && time < self.moves[self.applied - 1].time {
+            self.rebuild = true;
+        }
+        self.time = time;
+    }
+}
@@ -0,0 +1,105 @@
+use super::{
+    functions::{controls_transform, spawn_board, spawn_controls},
+    resources::ReplayState,
+};
+use crate::{
+    assets::LocaleAsset,
+    parser,
+    resources::{
+        Campaign, CellMeshes, GameColors, LoadState, LocaleAssets, Profile, Replays, TextSettings,
+    },
+};
+use bevy::{
+    prelude::{Assets, Commands, Mesh, Res, ResMut, Transform},
+    sprite::ColorMaterial,
+    window::Windows,
+};
+
+pub type StandardResources<'a> = (
+    Res<'a, CellMeshes>,
+    Res<'a, GameColors>,
+    Res<'a, LocaleAssets>,
+    Res<'a, Profile>,
+    Res<'a, TextSettings>,
+);
+pub type StandardAssets<'a> = (
+    ResMut<'a, Assets<Mesh>>,
+    ResMut<'a, Assets<ColorMaterial>>,
+    Res<'a, Assets<LocaleAsset>>,
+);
+pub fn setup(
+    mut commands: Commands,
+    wnds: Res<Windows>,
+    (cell_meshes, game_colors, locale, profile, text_settings): StandardResources,
+    (load_state, replays, campaign): (Res<LoadState>, Res<Replays>, Res<Campaign>),
+    (mut meshes, mut colors, locales): StandardAssets,
+) {
+    if load_state.filename.is_none() {
+        panic!("No level specified.");
+    }
+    let config = parser::board_from_file(load_state.filename.as_ref().unwrap());
+    let ids = load_state.ids.unwrap();
+    let moves = campaign
+        .get_id(ids.0, ids.1)
+        .and_then(|id| replays.levels.get(id))
+        .cloned()
+        .unwrap_or_default();
+
+    let (mut wd_width, mut wd_height) = (1920., 1080.);
+    for wnd in wnds.iter() {
+        (wd_width, wd_height) = (wnd.width(), wnd.height());
+    }
+    let board = spawn_board(
+        &mut commands,
+        (wd_width, wd_height),
+        (&config, ids),
+        (
+            &cell_meshes,
+            &game_colors,
+            &locale,
+            &profile,
+            &text_settings,
+        ),
+        (&mut meshes, &mut colors, &locales),
+    );
+
+    let replay = ReplayState {
+        config,
+        ids,
+        moves,
+        applied: 0,
+        time: 0.,
+        playing: true,
+        rebuild: false,
+    };
+    // Marks on the timeline where the mistakes were made
+    let duration = replay.duration();
+    let mistakes = replay
+        .moves
+        .iter()
+        .filter(|m| m.mistake)
+        .map(|m| if duration > 0. { m.time / duration } else { 0. })
+        .collect();
+    let mut controls_tf = Transform::identity();
+    for wnd in wnds.iter() {
+        controls_tf = controls_transform(wnd.width(), wnd.height());
+    }
+    spawn_controls(
+        &mut commands,
+        &mut meshes,
+        (&game_colors, &text_settings),
+        (
+            &format!(
+                "{} (Space)",
+                locale
+                    .get_string("pause", &locales, &profile)
+                    .unwrap_or(&"String not found".to_string())
+            ),
+            mistakes,
+        ),
+        controls_tf,
+    );
+
+    commands.insert_resource(board);
+    commands.insert_resource(replay);
+}
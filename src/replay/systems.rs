@@ -0,0 +1,255 @@
+use super::{
+    components::{PlayButton, ReplayControls, Timeline, TimelineFill},
+    constants::TIMELINE_WIDTH,
+    functions::{controls_transform, spawn_board},
+    resources::ReplayState,
+    setup::{StandardAssets, StandardResources},
+};
+use crate::{
+    assets::LocaleAsset,
+    components::{Cell, RootComponent},
+    functions::{format_time, rescale_board, switch_state},
+    level::{
+        board::Board,
+        components::{
+            GameCell, HintButton, MistakesText, NumberCell, PencilButton, RemainingText, TimeText,
+        },
+    },
+    resources::{GameColors, LoadState, LocaleAssets, Profile},
+    states::AppState,
+};
+use bevy::{
+    hierarchy::DespawnRecursiveExt,
+    input::Input,
+    prelude::{
+        Added, Assets, Commands, Entity, EventReader, Handle, KeyCode, MouseButton, Or, ParamSet,
+        Query, Res, ResMut, State, Transform, Visibility, With, Without,
+    },
+    sprite::ColorMaterial,
+    text::Text,
+    time::Time,
+    window::{WindowResized, Windows},
+};
+use interactable::components::{Hovered, ReleasedLeft};
+
+/// Starts or pauses the replay with the space key or the play button.
+/// The arrow keys step through the moves, escape returns to the level selection.
+pub fn controls_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    button_query: Query<(), (With<PlayButton>, With<ReleasedLeft>)>,
+    mut replay: ResMut<ReplayState>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        keys.clear_just_pressed(KeyCode::Escape);
+        switch_state(
+            Some(AppState::LevelSelection),
+            &mut app_state,
+            &mut load_state,
+        );
+        return;
+    }
+    if keys.just_pressed(KeyCode::Space) || !button_query.is_empty() {
+        // Plays again from the start once the end is reached
+        if !replay.playing && replay.time >= replay.duration() {
+            replay.seek(0.);
+        }
+        replay.playing = !replay.playing;
+    }
+    if keys.just_pressed(KeyCode::Left) {
+        let time = match replay.applied {
+            0 | 1 => 0.,
+            n => replay.moves[n - 2].time,
+        };
+        replay.playing = false;
+        replay.seek(time);
+    }
+    if keys.just_pressed(KeyCode::Right) {
+        if let Some(time) = replay.moves.get(replay.applied).map(|m| m.time) {
+            replay.playing = false;
+            replay.seek(time);
+        }
+    }
+}
+
+/// Jumps to the point of the timeline under the cursor while the left mouse button is held
+pub fn scrub_system(
+    wnds: Res<Windows>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    timeline_query: Query<(), (With<Timeline>, With<Hovered>)>,
+    controls_query: Query<&Transform, With<ReplayControls>>,
+    mut replay: ResMut<ReplayState>,
+) {
+    if !mouse_buttons.pressed(MouseButton::Left) || timeline_query.is_empty() {
+        return;
+    }
+    let (wnd, controls) = match (wnds.get_primary(), controls_query.get_single()) {
+        (Some(wnd), Ok(controls)) => (wnd, controls),
+        _ => return,
+    };
+    if let Some(cursor) = wnd.cursor_position() {
+        let x = (cursor.x - wnd.width() / 2. - controls.translation.x) / controls.scale.x;
+        let time = (x / TIMELINE_WIDTH + 0.5) * replay.duration();
+        replay.playing = false;
+        replay.seek(time);
+    }
+}
+
+/// Advances the replay in real time
+pub fn playback_system(time: Res<Time>, mut replay: ResMut<ReplayState>) {
+    if !replay.playing {
+        return;
+    }
+    let duration = replay.duration();
+    replay.time = (replay.time + time.delta_seconds()).min(duration);
+    if replay.time >= duration {
+        replay.playing = false;
+    }
+}
+
+/// Applies the moves up to the current point of the replay to the board
+pub fn apply_system(
+    mut commands: Commands,
+    mut cell_query: Query<(&mut GameCell, &mut Cell, Option<&NumberCell>)>,
+    mut color_query: Query<&mut Handle<ColorMaterial>>,
+    game_colors: Res<GameColors>,
+    (mut board, mut replay): (ResMut<Board>, ResMut<ReplayState>),
+) {
+    // The cells are about to be replaced
+    if replay.rebuild {
+        return;
+    }
+    while let Some(m) = replay.moves.get(replay.applied).copied() {
+        if m.time > replay.time {
+            break;
+        }
+        if let Some((mut gc, mut cell, nc)) = cell_query
+            .iter_mut()
+            .find(|(_, cell, _)| cell.x == m.x as i32 && cell.y == m.y as i32)
+        {
+            if m.mistake {
+                gc.uncover_fail(&cell, &mut commands, (&mut board, None), m.time);
+            } else if gc.hidden {
+                gc.uncover(
+                    &mut cell,
+                    &mut commands,
+                    &mut color_query,
+                    &game_colors,
+                    nc,
+                    (&mut board, m.time),
+                );
+            }
+        }
+        replay.applied += 1;
+    }
+}
+
+/// Rebuilds the board with every cell hidden to step backwards
+pub fn rebuild_system(
+    mut commands: Commands,
+    (wnds, root_query): (Res<Windows>, Query<Entity, With<RootComponent>>),
+    (cell_meshes, game_colors, locale, profile, text_settings): StandardResources,
+    (mut meshes, mut colors, locales): StandardAssets,
+    mut replay: ResMut<ReplayState>,
+) {
+    if !replay.rebuild {
+        return;
+    }
+    for entity in root_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let (mut wd_width, mut wd_height) = (1920., 1080.);
+    for wnd in wnds.iter() {
+        (wd_width, wd_height) = (wnd.width(), wnd.height());
+    }
+    let board = spawn_board(
+        &mut commands,
+        (wd_width, wd_height),
+        (&replay.config, replay.ids),
+        (
+            &cell_meshes,
+            &game_colors,
+            &locale,
+            &profile,
+            &text_settings,
+        ),
+        (&mut meshes, &mut colors, &locales),
+    );
+    commands.insert_resource(board);
+    replay.applied = 0;
+    replay.rebuild = false;
+}
+
+/// Buttons of the board that were just spawned
+type NewBoardButtons = Or<(Added<HintButton>, Added<PencilButton>)>;
+/// The buttons of the board can't be used in a replay
+pub fn hide_buttons_system(mut button_query: Query<&mut Visibility, NewBoardButtons>) {
+    for mut visibility in button_query.iter_mut() {
+        visibility.is_visible = false;
+    }
+}
+
+type HudTexts<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        Query<'w, 's, &'static mut Text, With<RemainingText>>,
+        Query<'w, 's, &'static mut Text, With<MistakesText>>,
+        Query<'w, 's, &'static mut Text, With<TimeText>>,
+        Query<'w, 's, &'static mut Text, With<PlayButton>>,
+    ),
+>;
+/// Shows the state of the board and the position in the replay
+pub fn hud_system(
+    mut text_set: HudTexts,
+    mut fill_query: Query<&mut Transform, With<TimelineFill>>,
+    (board, replay): (Res<Board>, Res<ReplayState>),
+    (locale, locales, profile): (Res<LocaleAssets>, Res<Assets<LocaleAsset>>, Res<Profile>),
+) {
+    if let Ok(mut text) = text_set.p0().get_single_mut() {
+        text.sections[0].value = format!("{}: {}", "Remaining", board.get_empty_remaining());
+    }
+    if let Ok(mut text) = text_set.p1().get_single_mut() {
+        text.sections[0].value = format!("{}: {}", "Mistakes", board.get_mistakes());
+    }
+    if let Ok(mut text) = text_set.p2().get_single_mut() {
+        text.sections[0].value = format!("{}: {}", "Time", format_time(replay.time));
+    }
+    if let Ok(mut text) = text_set.p3().get_single_mut() {
+        text.sections[0].value = format!(
+            "{} (Space)",
+            locale
+                .get_string(
+                    if replay.playing { "pause" } else { "play" },
+                    &locales,
+                    &profile
+                )
+                .unwrap_or(&"String not found".to_string())
+        );
+    }
+    if let Ok(mut fill) = fill_query.get_single_mut() {
+        let fraction = match replay.duration() > 0. {
+            true => replay.time / replay.duration(),
+            false => 1.,
+        };
+        fill.scale.x = fraction;
+        fill.translation.x = (fraction - 1.) * TIMELINE_WIDTH / 2.;
+    }
+}
+
+/// On resizing the window, the board and the controls are resized too
+pub fn window_resize_system(
+    mut ev_window_resize: EventReader<WindowResized>,
+    mut root_query: Query<&mut Transform, (With<RootComponent>, Without<ReplayControls>)>,
+    mut controls_query: Query<&mut Transform, (With<ReplayControls>, Without<RootComponent>)>,
+    board: Res<Board>,
+) {
+    for ev in ev_window_resize.iter() {
+        if let Ok(mut root) = root_query.get_single_mut() {
+            rescale_board(board.width, board.height, 4, ev.width, ev.height, &mut root);
+        }
+        if let Ok(mut controls) = controls_query.get_single_mut() {
+            *controls = controls_transform(ev.width, ev.height);
+        }
+    }
+}
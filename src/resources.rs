@@ -1,32 +1,73 @@
 use crate::{
     assets::LocaleAsset,
     components::Language,
-    constants::{GameColor, MED_SCALE, RADIUS, UNLOCK_POINTS},
-    functions::get_base_path,
+    constants::{
+        GameColor, ACHIEVEMENTS_FILE, CAMPAIGN_FILE, CUSTOM_PREFIX, LEVEL_DIR, MAX_ACHIEVEMENTS,
+        MED_SCALE, PROFILES_DIR, PROFILES_FILE, RADIUS,
+    },
+    enums::{Generated, PlayMode},
+    functions::{get_base_path, get_user_path, max_points},
+    generator::{generate, DIFFICULTIES},
+    packs, parser,
     states::AppState,
-    structs::TextSectionConfig,
+    storage::{load_ron, move_user_files, save_ron, show_notice, Loaded, Saved},
+    structs::{
+        AchievementEntry, AchievementRecord, BoardConfig, DailyRecord, DailyResult, EndlessRecord,
+        LevelEntry, LevelProgress, LevelRecord, Move, ProfileEntry, StageEntry, StatsRecord,
+        TextSectionConfig,
+    },
 };
 use bevy::{
     asset::HandleUntyped,
     ecs::world::{Mut, World},
-    prelude::{shape::RegularPolygon, AssetServer, Assets, Color, FromWorld, Handle, Mesh},
+    prelude::{
+        default, shape::RegularPolygon, AssetServer, Assets, Color, FromWorld, Handle, Mesh,
+    },
     sprite::ColorMaterial,
+    tasks::{AsyncComputeTaskPool, Task},
     text::{TextAlignment, TextStyle},
 };
 use bevy_asset_loader::prelude::AssetCollection;
 use bevy_kira_audio::AudioSource;
-use ron::{
-    de::from_reader,
-    ser::{to_writer_pretty, PrettyConfig},
-};
+use ron::{de::from_reader, ser::PrettyConfig};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Debug, Default)]
 pub struct LoadState {
     pub next_state: Option<AppState>,
     pub filename: Option<String>,
     pub ids: Option<(u8, u8)>,
+    pub mode: PlayMode,
+    /// The level selection shows the level packs instead of the campaign
+    pub custom: bool,
+    /// A generated board, played instead of the level file
+    pub generated: Option<(Generated, BoardConfig)>,
+}
+
+/// A board that is generated in the background, the level starts once it is done
+#[derive(Default)]
+pub struct GenerateTask {
+    /// What the board is for and the state it was asked for in.
+    /// The result is dropped if that state was left meanwhile.
+    pub running: Option<(Generated, AppState, Task<BoardConfig>)>,
+}
+impl GenerateTask {
+    pub fn start(
+        &mut self,
+        generated: Generated,
+        from: AppState,
+        generate: impl FnOnce() -> BoardConfig + Send + 'static,
+    ) {
+        let task = AsyncComputeTaskPool::get().spawn(async move { generate() });
+        self.running = Some((generated, from, task));
+    }
 }
 
 pub struct CellMeshes {
@@ -68,6 +109,7 @@ pub struct GameColors {
     pub blue_medium: Handle<ColorMaterial>,
     pub blue_light: Handle<ColorMaterial>,
     pub white: Handle<ColorMaterial>,
+    pub highlight: Handle<ColorMaterial>,
     pub alpha0: Handle<ColorMaterial>,
     pub alpha1: Handle<ColorMaterial>,
     pub alpha2: Handle<ColorMaterial>,
@@ -89,6 +131,7 @@ impl FromWorld for GameColors {
             blue_dark: materials.add(ColorMaterial::from(GameColor::BLUE_DARK)),
             blue_medium: materials.add(ColorMaterial::from(GameColor::BLUE_MEDIUM)),
             blue_light: materials.add(ColorMaterial::from(GameColor::BLUE_LIGHT)),
+            highlight: materials.add(ColorMaterial::from(GameColor::HIGHLIGHT)),
             alpha0: materials.add(ColorMaterial::from(GameColor::ALPHA_0)),
             alpha1: materials.add(ColorMaterial::from(GameColor::ALPHA_1)),
             alpha2: materials.add(ColorMaterial::from(GameColor::ALPHA_2)),
@@ -180,6 +223,27 @@ impl LocaleAssets {
             None
         }
     }
+    /// Languages whose locale file has no text section with the given key
+    pub fn missing_text_section(
+        &self,
+        key: &str,
+        locale_assets: &Assets<LocaleAsset>,
+    ) -> Vec<Language> {
+        [
+            (Language::EN, &self.en),
+            (Language::DE, &self.de),
+            (Language::FR, &self.fr),
+            (Language::ES, &self.es),
+        ]
+        .into_iter()
+        .filter(|(_, handle)| {
+            locale_assets
+                .get(handle)
+                .is_none_or(|la| !la.text_sections.contains_key(key))
+        })
+        .map(|(lang, _)| lang)
+        .collect()
+    }
     pub fn get_locale_handle(&self, profile: &Profile) -> Handle<LocaleAsset> {
         match profile.lang {
             Language::DE => self.de.clone_weak(),
@@ -190,8 +254,26 @@ impl LocaleAssets {
     }
 }
 
+/// Version of the profile format, older profiles are migrated on loading
+const PROFILE_VERSION: u32 = 1;
+
+/// Shown before the menus are loaded, so they can't be localized
+const PROFILE_RESTORED: &str =
+    "Your profile was damaged and has been restored from the last backup.";
+const PROFILE_LOST: &str =
+    "Your profile was damaged and no backup could be read. A new profile has been started.";
+const CAMPAIGN_LOST: &str =
+    "The levels of the campaign could not be read. Reinstalling the game should fix this.";
+const PROFILES_LOST: &str =
+    "The list of profiles was damaged and no backup could be read. It has been started anew.";
+/// Name of the first profile, before the locale is loaded
+const DEFAULT_PROFILE_NAME: &str = "Player";
+
 #[derive(Serialize, Deserialize)]
 pub struct Profile {
+    /// Missing in profiles from before versioning, they count as version 0
+    #[serde(default)]
+    pub version: u32,
     #[serde(default)]
     pub lang: Language,
     #[serde(default)]
@@ -202,59 +284,706 @@ pub struct Profile {
     pub show_editor: bool,
     #[serde(default)]
     pub sfx_volume: f32,
+    /// Results of the levels by the id in the campaign manifest
+    #[serde(default)]
+    levels: HashMap<String, LevelRecord>,
+    /// Assist that uncovers cells which are trivially forced
+    #[serde(default)]
+    pub auto_reveal: bool,
+    /// Dims clues as soon as all of their blue cells are found, not only once all cells are
     #[serde(default)]
-    pub level_points: [[Option<u16>; 6]; 6],
+    pub dim_satisfied: bool,
+    #[serde(default)]
+    daily: DailyRecord,
+    #[serde(default)]
+    endless: EndlessRecord,
+    #[serde(default)]
+    achievements: AchievementRecord,
+    #[serde(default)]
+    stats: StatsRecord,
+    /// Version 0 stored the results by position, only read to migrate them
+    #[serde(default, skip_serializing)]
+    level_points: [[Option<u16>; 6]; 6],
+    #[serde(default, skip_serializing)]
+    level_times: [[Option<f32>; 6]; 6],
+    #[serde(default, skip_serializing)]
+    hardcore_points: [[Option<u16>; 6]; 6],
+    /// Directory of the profile's files
+    #[serde(skip)]
+    dir: PathBuf,
 }
 impl Profile {
-    pub fn new() -> Self {
-        if let Ok(file) = File::open(get_base_path().join("settings.ron")) {
-            from_reader(file).unwrap()
-        } else {
-            Self::default()
+    /// Loads the profile in the given directory
+    pub fn new(campaign: &Campaign, dir: &Path) -> Self {
+        let new = Self {
+            dir: dir.to_path_buf(),
+            ..default()
+        };
+        let (mut profile, restored): (Self, bool) = match load_ron(&dir.join("settings.ron")) {
+            Loaded::Ok(profile) => (profile, false),
+            Loaded::Missing => return new,
+            Loaded::Restored(profile) => {
+                show_notice(PROFILE_RESTORED);
+                (profile, true)
+            }
+            Loaded::Broken => {
+                show_notice(PROFILE_LOST);
+                return new;
+            }
+        };
+        profile.dir = new.dir;
+        if restored {
+            profile.save();
+        }
+        if profile.version < PROFILE_VERSION {
+            profile.migrate(campaign);
+            profile.save();
+        }
+        profile
+    }
+    /// Brings an older profile up to the current version, step by step
+    fn migrate(&mut self, campaign: &Campaign) {
+        if self.version < 1 {
+            for stage in campaign.stages.iter() {
+                for level in stage.levels.iter() {
+                    let (s, l) = match legacy_position(&level.file) {
+                        Some(pos) => pos,
+                        None => continue,
+                    };
+                    let points = self.level_points[s][l];
+                    let time = self.level_times[s][l];
+                    let hardcore_points = self.hardcore_points[s][l];
+                    if points.is_none() && time.is_none() && hardcore_points.is_none() {
+                        continue;
+                    }
+                    let record = self.levels.entry(level.id.clone()).or_default();
+                    record.points = points;
+                    record.time = time;
+                    record.hardcore_points = hardcore_points;
+                }
+            }
+            self.level_points = Default::default();
+            self.level_times = Default::default();
+            self.hardcore_points = Default::default();
         }
+        self.version = PROFILE_VERSION;
     }
+    /// Points of all solved levels of the campaign, they unlock the stages
     pub fn get_points(&self) -> u16 {
-        self.level_points
+        self.levels
             .iter()
-            .map(|stage| stage.iter().map(|pts| pts.unwrap_or(0)).sum::<u16>())
+            .filter(|(id, _)| !id.starts_with(CUSTOM_PREFIX))
+            .map(|(_, record)| record.points.unwrap_or(0))
             .sum()
     }
-    pub fn is_unlocked(&self, stage_id: u8) -> bool {
-        return self.get_points() >= UNLOCK_POINTS[stage_id as usize];
+    pub fn get_record(&self, id: &str) -> Option<&LevelRecord> {
+        self.levels.get(id)
+    }
+    /// Counts a level started from scratch
+    pub fn record_attempt(&mut self, id: &str) {
+        self.levels.entry(id.to_string()).or_default().attempts += 1;
+    }
+    /// Keeps the best results of a solve and the date of the first one.
+    /// The hardcore mode only keeps its points, apart from the normal results.
+    pub fn record_solve(
+        &mut self,
+        id: &str,
+        mode: PlayMode,
+        (points, mistakes, time): (u16, u16, f32),
+    ) {
+        let record = self.levels.entry(id.to_string()).or_default();
+        if let PlayMode::Hardcore(_) = mode {
+            if record.hardcore_points.is_none_or(|best| points > best) {
+                record.hardcore_points = Some(points);
+            }
+        } else {
+            if record.points.is_none_or(|best| points > best) {
+                record.points = Some(points);
+            }
+            if record.time.is_none_or(|best| time < best) {
+                record.time = Some(time);
+            }
+            if record.mistakes.is_none_or(|fewest| mistakes < fewest) {
+                record.mistakes = Some(mistakes);
+            }
+        }
+        record.solves += 1;
+        record.total_mistakes = record.total_mistakes.saturating_add(mistakes as u32);
+        if record.completed.is_none() {
+            record.completed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_secs());
+        }
+    }
+    pub fn get_level_points(&self, id: &str) -> Option<u16> {
+        self.get_record(id).and_then(|record| record.points)
+    }
+    pub fn get_time(&self, id: &str) -> Option<f32> {
+        self.get_record(id).and_then(|record| record.time)
+    }
+    pub fn get_daily(&self) -> &DailyRecord {
+        &self.daily
+    }
+    pub fn get_endless(&self) -> &EndlessRecord {
+        &self.endless
+    }
+    pub fn count_endless_run(&mut self) {
+        self.endless.runs += 1;
+    }
+    pub fn get_achievements(&self) -> &AchievementRecord {
+        &self.achievements
     }
-    pub fn save(&self) {
-        to_writer_pretty(
-            File::create(get_base_path().join("settings.ron")).expect("Failed opening file"),
+    pub fn has_achievement(&self, id: &str) -> bool {
+        self.achievements.unlocked.contains_key(id)
+    }
+    pub fn unlock_achievement(&mut self, id: &str) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.achievements
+            .unlocked
+            .entry(id.to_string())
+            .or_insert(now);
+    }
+    /// Counts what achievements look at in a solve besides the results
+    pub fn record_solve_details(&mut self, hidden: u16, hints: u16, mistakes: u16) {
+        let record = &mut self.achievements;
+        record.no_hint_streak = if hints == 0 {
+            record.no_hint_streak + 1
+        } else {
+            0
+        };
+        record.no_mistake_streak = if mistakes == 0 {
+            record.no_mistake_streak + 1
+        } else {
+            0
+        };
+        record.largest_board = record.largest_board.max(hidden);
+    }
+    /// A mistake ends the streak of levels without one right away
+    pub fn record_mistake(&mut self) {
+        self.achievements.no_mistake_streak = 0;
+        self.stats.mistakes = self.stats.mistakes.saturating_add(1);
+    }
+    pub fn get_stats(&self) -> &StatsRecord {
+        &self.stats
+    }
+    /// Adds play time and uncovered cells to the totals
+    pub fn record_play(&mut self, time: f32, uncovered: u16) {
+        self.stats.play_time += time;
+        self.stats.cells_uncovered = self.stats.cells_uncovered.saturating_add(uncovered as u32);
+    }
+    /// Levels of the campaign and the packs that were solved
+    pub fn count_solved(&self) -> u32 {
+        self.levels.values().filter(|r| r.points.is_some()).count() as u32
+    }
+    pub fn count_hardcore(&self) -> u32 {
+        self.levels
+            .values()
+            .filter(|r| r.hardcore_points.is_some())
+            .count() as u32
+    }
+    /// Keeps the best results of endless runs, updated after every puzzle of a run
+    pub fn record_endless(&mut self, run: &EndlessRun) {
+        let endless = &mut self.endless;
+        endless.best_score = endless.best_score.max(run.score);
+        endless.best_solved = endless.best_solved.max(run.solved);
+        endless.best_difficulty = endless.best_difficulty.max(run.difficulty as u8);
+    }
+    /// Keeps the first result of a daily puzzle, later solves only count for the best ones
+    pub fn record_daily(&mut self, day: u32, result: DailyResult) {
+        let daily = &mut self.daily;
+        daily.results.entry(day).or_insert(result);
+        if daily.best_points.is_none_or(|best| result.points > best) {
+            daily.best_points = Some(result.points);
+        }
+        if daily.best_time.is_none_or(|best| result.time < best) {
+            daily.best_time = Some(result.time);
+        }
+        daily.best_streak = daily.best_streak.max(daily.streak(day));
+    }
+}
+impl Saved for Profile {
+    /// A failed save is not fatal, the next change tries again
+    fn save(&self) {
+        if let Err(e) = save_ron(
+            &self.dir.join("settings.ron"),
             self,
             PrettyConfig::new()
                 .depth_limit(2)
                 .separate_tuple_members(true)
                 .enumerate_arrays(true),
-        )
-        .expect("Error saving profile");
-    }
-    pub fn update_point(
-        &mut self,
-        points: u16,
-        stage_id: impl Into<usize> + std::marker::Copy,
-        level_id: impl Into<usize> + std::marker::Copy,
-    ) {
-        let current = self.level_points[stage_id.into()][level_id.into()].unwrap_or_default();
-        if points > current {
-            self.level_points[stage_id.into()][level_id.into()] = Some(points);
-            self.save();
+        ) {
+            eprintln!("Error saving profile: {}", e);
         }
     }
 }
+/// Position of a level in the results of version 0 profiles,
+/// they belonged to the level file stage+1/level+1.lvl
+fn legacy_position(file: &str) -> Option<(usize, usize)> {
+    let (stage, level) = file.strip_suffix(".lvl")?.split_once('/')?;
+    let (stage, level): (usize, usize) = (stage.parse().ok()?, level.parse().ok()?);
+    match (1..=6).contains(&stage) && (1..=6).contains(&level) {
+        true => Some((stage - 1, level - 1)),
+        false => None,
+    }
+}
+
 impl Default for Profile {
     fn default() -> Self {
         Self {
+            version: PROFILE_VERSION,
             lang: Language::EN,
             mouse_inverted: false,
             fullscreen: false,
             show_editor: false,
             sfx_volume: 0.5,
+            levels: HashMap::new(),
+            auto_reveal: false,
+            dim_satisfied: false,
+            daily: DailyRecord::default(),
+            endless: EndlessRecord::default(),
+            achievements: AchievementRecord::default(),
+            stats: StatsRecord::default(),
             level_points: Default::default(),
+            level_times: Default::default(),
+            hardcore_points: Default::default(),
+            dir: PathBuf::new(),
+        }
+    }
+}
+
+/// Stages and levels of the campaign, read from the manifest in the levels directory.
+/// The level packs of the players follow the stages, they use the stage ids after them.
+#[derive(Default, Deserialize)]
+pub struct Campaign {
+    pub stages: Vec<StageEntry>,
+    /// Points of all levels together, counted once the level selection asks for them
+    #[serde(skip)]
+    max_points: OnceLock<u16>,
+    #[serde(skip)]
+    pub packs: Vec<StageEntry>,
+}
+impl Campaign {
+    /// Reads the manifest. Without it only the level packs are left.
+    pub fn new() -> Self {
+        let path = get_base_path().join(LEVEL_DIR).join(CAMPAIGN_FILE);
+        let read = File::open(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| from_reader(file).map_err(|e| e.to_string()));
+        let mut campaign: Self = match read {
+            Ok(campaign) => campaign,
+            Err(e) => {
+                show_notice(&format!("{}\n\n{}: {}", CAMPAIGN_LOST, path.display(), e));
+                Self::default()
+            }
+        };
+        campaign.packs = packs::load_packs();
+        campaign
+    }
+    /// Scans the level packs again, e.g. after the editor saved a level
+    pub fn reload_packs(&mut self) {
+        self.packs = packs::load_packs();
+    }
+    /// Whether the stage id belongs to a level pack
+    pub fn is_custom(&self, stage_id: u8) -> bool {
+        stage_id as usize >= self.stages.len()
+    }
+    /// The stages of the campaign or the level packs, with the id of the first one
+    pub fn get_section(&self, custom: bool) -> (&[StageEntry], u8) {
+        match custom {
+            true => (&self.packs, self.stages.len() as u8),
+            false => (&self.stages, 0),
+        }
+    }
+    fn get_stage(&self, stage_id: u8) -> Option<&StageEntry> {
+        let stage_id = stage_id as usize;
+        self.stages.get(stage_id).or_else(|| {
+            stage_id
+                .checked_sub(self.stages.len())
+                .and_then(|pack_id| self.packs.get(pack_id))
+        })
+    }
+    pub fn get_level(&self, stage_id: u8, level_id: u8) -> Option<&LevelEntry> {
+        self.get_stage(stage_id)
+            .and_then(|stage| stage.levels.get(level_id as usize))
+    }
+    pub fn get_id(&self, stage_id: u8, level_id: u8) -> Option<&str> {
+        self.get_level(stage_id, level_id)
+            .map(|level| level.id.as_str())
+    }
+    /// Stage and level id of the level with the given id in the manifest
+    pub fn find_level(&self, id: &str) -> Option<(u8, u8)> {
+        self.stages
+            .iter()
+            .chain(self.packs.iter())
+            .enumerate()
+            .find_map(|(stage_id, stage)| {
+                let level_id = stage.levels.iter().position(|level| level.id == id)?;
+                Some((stage_id as u8, level_id as u8))
+            })
+    }
+    /// Path of the level file as the parser expects it, the ones of packs are absolute
+    pub fn get_path(&self, stage_id: u8, level_id: u8) -> Option<String> {
+        let custom = self.is_custom(stage_id);
+        self.get_level(stage_id, level_id)
+            .map(|level| match custom {
+                true => level.file.clone(),
+                false => format!("{}/{}", LEVEL_DIR, level.file),
+            })
+    }
+    /// The level after the given one, the first level of the next stage after the last one.
+    /// Level packs end with their last level.
+    pub fn next_level(&self, stage_id: u8, level_id: u8) -> Option<(u8, u8)> {
+        if self.get_level(stage_id, level_id + 1).is_some() {
+            Some((stage_id, level_id + 1))
+        } else if self.is_custom(stage_id) {
+            None
+        } else {
+            (stage_id as usize + 1..self.stages.len())
+                .find(|s| !self.stages[*s].levels.is_empty())
+                .map(|s| (s as u8, 0))
+        }
+    }
+    pub fn is_unlocked(&self, stage_id: u8, profile: &Profile) -> bool {
+        self.get_stage(stage_id)
+            .is_some_and(|stage| profile.get_points() >= stage.unlock_points)
+    }
+    pub fn get_max_points(&self) -> u16 {
+        *self.max_points.get_or_init(|| {
+            self.stages
+                .iter()
+                .flat_map(|stage| stage.levels.iter())
+                .map(|level| {
+                    let path = get_base_path().join(LEVEL_DIR).join(&level.file);
+                    match parser::try_board_from_file(&path) {
+                        Ok(config) => {
+                            let hidden = config
+                                .cells
+                                .iter()
+                                .flatten()
+                                .filter(|(ct, hidden)| ct.is_some() && *hidden)
+                                .count();
+                            max_points(hidden as u16)
+                        }
+                        Err(e) => {
+                            eprintln!("Error reading level {}: {}", level.file, e);
+                            0
+                        }
+                    }
+                })
+                .sum()
+        })
+    }
+}
+
+/// Progress of the levels that were started but not solved
+#[derive(Default, Serialize, Deserialize)]
+pub struct Progress {
+    /// Progress by the id in the campaign manifest
+    #[serde(default)]
+    pub levels: HashMap<String, LevelProgress>,
+    /// Id of the level played last, it can be continued from the home screen
+    #[serde(default)]
+    pub last: Option<String>,
+    /// Directory of the profile it belongs to
+    #[serde(skip)]
+    dir: PathBuf,
+}
+impl Progress {
+    pub fn new(dir: &Path) -> Self {
+        let mut progress = match load_ron(&dir.join("progress.ron")) {
+            Loaded::Ok(value) | Loaded::Restored(value) => value,
+            Loaded::Missing | Loaded::Broken => Self::default(),
+        };
+        progress.dir = dir.to_path_buf();
+        progress
+    }
+    /// Id of the level that can be continued, if any
+    pub fn get_last(&self) -> Option<&str> {
+        self.last
+            .as_deref()
+            .filter(|id| self.levels.contains_key(*id))
+    }
+}
+impl Saved for Progress {
+    fn save(&self) {
+        if let Err(e) = save_ron(
+            &self.dir.join("progress.ron"),
+            self,
+            PrettyConfig::new().depth_limit(3),
+        ) {
+            eprintln!("Error saving progress: {}", e);
+        }
+    }
+}
+
+/// Moves of the last solve of every level
+#[derive(Default, Serialize, Deserialize)]
+pub struct Replays {
+    /// Moves by the id in the campaign manifest
+    #[serde(default)]
+    pub levels: HashMap<String, Vec<Move>>,
+    /// Directory of the profile it belongs to
+    #[serde(skip)]
+    dir: PathBuf,
+}
+impl Replays {
+    pub fn new(dir: &Path) -> Self {
+        let mut replays = match load_ron(&dir.join("replays.ron")) {
+            Loaded::Ok(value) | Loaded::Restored(value) => value,
+            Loaded::Missing | Loaded::Broken => Self::default(),
+        };
+        replays.dir = dir.to_path_buf();
+        replays
+    }
+}
+impl Saved for Replays {
+    fn save(&self) {
+        if let Err(e) = save_ron(
+            &self.dir.join("replays.ron"),
+            self,
+            PrettyConfig::new().depth_limit(2),
+        ) {
+            eprintln!("Error saving replays: {}", e);
+        }
+    }
+}
+
+/// The achievements, read from the achievements file
+#[derive(Deserialize)]
+pub struct Achievements {
+    pub entries: Vec<AchievementEntry>,
+}
+impl Achievements {
+    pub fn new() -> Self {
+        let file = File::open(get_base_path().join(ACHIEVEMENTS_FILE))
+            .unwrap_or_else(|_| panic!("File \"{}\" not found!", ACHIEVEMENTS_FILE));
+        let mut achievements: Self = from_reader(file).expect("Error reading achievements");
+        // Only as many as the gallery can show
+        achievements.entries.truncate(MAX_ACHIEVEMENTS);
+        achievements
+    }
+}
+
+/// Solves that are looked at to adapt the difficulty of an endless run
+const RECENT_SOLVES: usize = 3;
+/// Seconds per position of a generated board that still count as a quick solve
+const PAR_SECONDS: f32 = 1.5;
+
+/// An endless run, a series of generated puzzles that get harder or easier
+/// with the player's mistakes and times. It lasts until the player ends it.
+#[derive(Default)]
+pub struct EndlessRun {
+    /// Every puzzle of the run is derived from it
+    seed: u64,
+    /// Index into the difficulties of the generator
+    pub difficulty: usize,
+    pub solved: u32,
+    /// Points of the puzzles, the harder ones count more
+    pub score: u32,
+    pub mistakes: u32,
+    /// Time of all solves in seconds
+    pub time: f32,
+    /// Mistakes and the time relative to the par time of the last solves
+    recent: VecDeque<(u16, f32)>,
+    /// The player ended the run, the summary is due
+    pub ended: bool,
+}
+impl EndlessRun {
+    /// Starts a new run from the easiest difficulty
+    pub fn start(&mut self) {
+        *self = Self {
+            seed: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64),
+            ..default()
+        };
+    }
+    /// Generates the current puzzle of the run in the background, it's played once done
+    pub fn load_puzzle(&self, generating: &mut GenerateTask, from: AppState) {
+        let seed = self.seed.wrapping_add(self.solved as u64);
+        let settings = DIFFICULTIES[self.difficulty];
+        generating.start(Generated::Endless(self.solved), from, move || {
+            generate(seed, &settings)
+        });
+    }
+    /// Counts a solved puzzle and adapts the difficulty of the next one to the last solves
+    pub fn record_solve(&mut self, (points, mistakes, time): (u16, u16, f32)) {
+        let settings = DIFFICULTIES[self.difficulty];
+        let par = (settings.width * settings.height) as f32 * PAR_SECONDS;
+        self.score += points as u32 * (self.difficulty as u32 + 1);
+        self.solved += 1;
+        self.mistakes += mistakes as u32;
+        self.time += time;
+        self.recent.push_back((mistakes, time / par));
+        if self.recent.len() > RECENT_SOLVES {
+            self.recent.pop_front();
+        }
+        let count = self.recent.len() as f32;
+        let mistakes = self.recent.iter().map(|(m, _)| *m as f32).sum::<f32>() / count;
+        let pace = self.recent.iter().map(|(_, p)| *p).sum::<f32>() / count;
+        // Only the solves at the new difficulty count for the next change
+        if mistakes < 0.5 && pace < 1. && self.difficulty + 1 < DIFFICULTIES.len() {
+            self.difficulty += 1;
+            self.recent.clear();
+        } else if (mistakes >= 2. || pace > 2.) && self.difficulty > 0 {
+            self.difficulty -= 1;
+            self.recent.clear();
+        }
+    }
+}
+
+/// The player profiles. Each one has a directory in the user directory
+/// with its settings, results and saves.
+#[derive(Serialize, Deserialize)]
+pub struct Profiles {
+    /// Id of the profile in use
+    current: String,
+    entries: Vec<ProfileEntry>,
+    /// Number of the next new profile, ids are never reused
+    next_id: u32,
+}
+impl Profiles {
+    pub fn new() -> Self {
+        let path = get_user_path().join(PROFILES_FILE);
+        match load_ron(&path) {
+            Loaded::Ok(profiles) => return profiles,
+            Loaded::Restored(profiles) => {
+                profiles.save();
+                return profiles;
+            }
+            Loaded::Broken => show_notice(PROFILES_LOST),
+            Loaded::Missing => {}
+        }
+        // The first profile takes over the files from before there were several
+        let mut profiles = Self {
+            current: String::new(),
+            entries: Vec::new(),
+            next_id: 1,
+        };
+        profiles.current = profiles.create(DEFAULT_PROFILE_NAME.to_string());
+        move_user_files(&profiles.get_current_dir());
+        profiles.save();
+        profiles
+    }
+    pub fn get_entries(&self) -> &[ProfileEntry] {
+        &self.entries
+    }
+    pub fn get_current(&self) -> &str {
+        &self.current
+    }
+    pub fn get_current_name(&self) -> &str {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == self.current)
+            .map_or("", |entry| &entry.name)
+    }
+    pub fn get_current_dir(&self) -> PathBuf {
+        let dir = get_user_path().join(PROFILES_DIR).join(&self.current);
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("Error creating profile directory: {}", e);
+        }
+        dir
+    }
+    pub fn set_current(&mut self, id: &str) {
+        if self.entries.iter().any(|entry| entry.id == id) {
+            self.current = id.to_string();
         }
     }
+    /// Adds a profile and returns its id, it doesn't switch to it
+    pub fn create(&mut self, name: String) -> String {
+        let id = format!("profile-{}", self.next_id);
+        self.next_id += 1;
+        self.entries.push(ProfileEntry {
+            id: id.clone(),
+            name,
+        });
+        id
+    }
+    pub fn rename(&mut self, id: &str, name: String) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.name = name;
+        }
+    }
+    /// Removes a profile with all of its files. The last profile can't be deleted,
+    /// if the current one is deleted, the first one becomes current.
+    pub fn delete(&mut self, id: &str) {
+        if self.entries.len() <= 1 {
+            return;
+        }
+        self.entries.retain(|entry| entry.id != id);
+        let _ = fs::remove_dir_all(get_user_path().join(PROFILES_DIR).join(id));
+        if self.current == id {
+            self.current = self.entries[0].id.clone();
+        }
+    }
+}
+impl Saved for Profiles {
+    fn save(&self) {
+        if let Err(e) = save_ron(
+            &get_user_path().join(PROFILES_FILE),
+            self,
+            PrettyConfig::new(),
+        ) {
+            eprintln!("Error saving profiles: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A settings file as version 0 of the game wrote it
+    const LEGACY_SETTINGS: &str = "(
+    lang: DE,
+    mouse_inverted: false,
+    fullscreen: false,
+    show_editor: true,
+    sfx_volume: 0.5,
+    level_points: (
+        (Some(5), None, None, None, None, Some(15)),
+        (None, None, None, None, None, None),
+        (None, None, None, None, None, None),
+        (None, None, None, None, None, None),
+        (None, None, None, None, None, None),
+        (None, None, None, None, None, None),
+    ),
+)";
+
+    fn level(id: &str, file: &str) -> LevelEntry {
+        LevelEntry {
+            id: id.to_string(),
+            file: file.to_string(),
+            name: String::new(),
+        }
+    }
+
+    #[test]
+    fn migrate_moves_legacy_points_to_ids() {
+        let campaign = Campaign {
+            stages: vec![StageEntry {
+                name: String::new(),
+                author: String::new(),
+                unlock_points: 0,
+                levels: vec![
+                    level("classic-1-1", "1/1.lvl"),
+                    level("classic-1-2", "1/2.lvl"),
+                    level("classic-1-6", "1/6.lvl"),
+                ],
+            }],
+            ..Default::default()
+        };
+        let mut profile: Profile = ron::de::from_str(LEGACY_SETTINGS).unwrap();
+        assert_eq!(profile.version, 0);
+        profile.migrate(&campaign);
+        assert_eq!(profile.version, PROFILE_VERSION);
+        assert_eq!(profile.get_level_points("classic-1-1"), Some(5));
+        assert_eq!(profile.get_level_points("classic-1-6"), Some(15));
+        assert!(profile.get_record("classic-1-2").is_none());
+        assert_eq!(profile.get_points(), 20);
+        assert!(profile.show_editor);
+    }
 }
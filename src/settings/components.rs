@@ -7,9 +7,17 @@ pub struct MouseInverted(pub bool);
 pub struct ButtonWindowMode;
 #[derive(Component)]
 pub struct ButtonReturn;
+#[derive(Component)]
+pub struct ButtonAutoReveal;
+#[derive(Component)]
+pub struct ButtonDimSatisfied;
 
 #[derive(Component)]
 pub struct TextWindowMode;
+#[derive(Component)]
+pub struct TextAutoReveal;
+#[derive(Component)]
+pub struct TextDimSatisfied;
 
 #[derive(Component)]
 pub struct SettingsButton;
@@ -3,3 +3,7 @@ use bevy::prelude::Color;
 pub const COLOR_SELECTED: Color = Color::WHITE;
 pub const COLOR_UNSELECTED: Color = Color::rgb(0.5, 0.5, 0.5);
 pub const COLOR_HOVERED: Color = Color::rgb(0.75, 0.75, 0.75);
+
+/// Locale keys of the assist settings, for the states on and off
+pub const AUTO_REVEAL_KEYS: (&str, &str) = ("auto-reveal-on", "auto-reveal-off");
+pub const DIM_SATISFIED_KEYS: (&str, &str) = ("dim-satisfied-on", "dim-satisfied-off");
@@ -27,3 +27,21 @@ pub fn window_mode_text(
     )
     .with_alignment(text_settings.alignment)
 }
+
+/// Text of a button that switches a setting on and off
+pub fn toggle_text(
+    (key_on, key_off): (&str, &str),
+    on: bool,
+    locale: &LocaleAssets,
+    locales: &Assets<LocaleAsset>,
+    profile: &Profile,
+    text_settings: &TextSettings,
+) -> Text {
+    Text::from_section(
+        locale
+            .get_string(if on { key_on } else { key_off }, locales, profile)
+            .unwrap_or(&"String not found".to_string()),
+        text_settings.style_menu_dark.clone(),
+    )
+    .with_alignment(text_settings.alignment)
+}
@@ -22,6 +22,8 @@ pub fn prepare_settings(app: &mut App) {
                 .with_system(lang_hover_system.after(InteractLabel::Interact))
                 .with_system(mouse_setting_hover_system.after(InteractLabel::Interact))
                 .with_system(window_mode_button_click_system.after(InteractLabel::Interact))
+                .with_system(auto_reveal_button_click_system.after(InteractLabel::Interact))
+                .with_system(dim_satisfied_button_click_system.after(InteractLabel::Interact))
                 .with_system(return_button_click_system.after(InteractLabel::Interact))
                 .with_system(menu_button_hovered::<SettingsButton>.after(InteractLabel::Interact))
                 .with_system(hotkey_system)
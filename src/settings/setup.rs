@@ -1,7 +1,10 @@
 use super::{
-    components::{ButtonReturn, ButtonWindowMode, MouseInverted, SettingsButton, TextWindowMode},
-    constants::{COLOR_SELECTED, COLOR_UNSELECTED},
-    functions::window_mode_text,
+    components::{
+        ButtonAutoReveal, ButtonDimSatisfied, ButtonReturn, ButtonWindowMode, MouseInverted,
+        SettingsButton, TextAutoReveal, TextDimSatisfied, TextWindowMode,
+    },
+    constants::{AUTO_REVEAL_KEYS, COLOR_SELECTED, COLOR_UNSELECTED, DIM_SATISFIED_KEYS},
+    functions::{toggle_text, window_mode_text},
 };
 use crate::{
     assets::LocaleAsset,
@@ -227,7 +230,7 @@ pub fn setup(
 
     let bt_window_mode = commands
         .spawn_bundle(MenuButtonBundle::new(
-            Transform::from_xyz(-400., -4.5 * RADIUS, 0.9),
+            Transform::from_xyz(-600., -4.5 * RADIUS, 0.9),
             (270., 170.),
             game_colors.menu_button.clone(),
             &mut meshes,
@@ -246,9 +249,65 @@ pub fn setup(
         .insert(SettingsButton)
         .id();
 
+    let bt_auto_reveal = commands
+        .spawn_bundle(MenuButtonBundle::new(
+            Transform::from_xyz(-200., -4.5 * RADIUS, 0.9),
+            (270., 170.),
+            game_colors.menu_button.clone(),
+            &mut meshes,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(Text2dBundle {
+                    text: toggle_text(
+                        AUTO_REVEAL_KEYS,
+                        profile.auto_reveal,
+                        &locale,
+                        &locales,
+                        &profile,
+                        &text_settings,
+                    ),
+                    transform: Transform::from_xyz(0., -10., 10.)
+                        .with_scale(Vec3::new(0.6, 0.6, 1.)),
+                    ..default()
+                })
+                .insert(TextAutoReveal);
+        })
+        .insert(ButtonAutoReveal)
+        .insert(SettingsButton)
+        .id();
+
+    let bt_dim_satisfied = commands
+        .spawn_bundle(MenuButtonBundle::new(
+            Transform::from_xyz(200., -4.5 * RADIUS, 0.9),
+            (270., 170.),
+            game_colors.menu_button.clone(),
+            &mut meshes,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(Text2dBundle {
+                    text: toggle_text(
+                        DIM_SATISFIED_KEYS,
+                        profile.dim_satisfied,
+                        &locale,
+                        &locales,
+                        &profile,
+                        &text_settings,
+                    ),
+                    transform: Transform::from_xyz(0., -10., 10.)
+                        .with_scale(Vec3::new(0.6, 0.6, 1.)),
+                    ..default()
+                })
+                .insert(TextDimSatisfied);
+        })
+        .insert(ButtonDimSatisfied)
+        .insert(SettingsButton)
+        .id();
+
     let bt_return = commands
         .spawn_bundle(MenuButtonBundle::new(
-            Transform::from_xyz(400., -4.5 * RADIUS, 0.9),
+            Transform::from_xyz(600., -4.5 * RADIUS, 0.9),
             (270., 170.),
             game_colors.menu_button.clone(),
             &mut meshes,
@@ -283,6 +342,8 @@ pub fn setup(
             language_panel,
             mouse_panel,
             bt_window_mode,
+            bt_auto_reveal,
+            bt_dim_satisfied,
             bt_return,
         ])
         .insert_bundle(SpatialBundle::from_transform(root_transform))
@@ -1,7 +1,12 @@
 use super::{
-    components::{ButtonReturn, ButtonWindowMode, MouseInverted, TextWindowMode},
-    constants::{COLOR_HOVERED, COLOR_SELECTED, COLOR_UNSELECTED},
-    functions::window_mode_text,
+    components::{
+        ButtonAutoReveal, ButtonDimSatisfied, ButtonReturn, ButtonWindowMode, MouseInverted,
+        TextAutoReveal, TextDimSatisfied, TextWindowMode,
+    },
+    constants::{
+        AUTO_REVEAL_KEYS, COLOR_HOVERED, COLOR_SELECTED, COLOR_UNSELECTED, DIM_SATISFIED_KEYS,
+    },
+    functions::{toggle_text, window_mode_text},
 };
 use crate::{
     assets::LocaleAsset,
@@ -112,6 +117,48 @@ pub fn window_mode_button_click_system(
     }
 }
 
+pub fn auto_reveal_button_click_system(
+    button_query: Query<&ButtonAutoReveal, With<ReleasedLeft>>,
+    mut text_query: Query<&mut Text, With<TextAutoReveal>>,
+    (locale, mut profile, text_settings): (Res<LocaleAssets>, ResMut<Profile>, Res<TextSettings>),
+    locales: Res<Assets<LocaleAsset>>,
+) {
+    if !button_query.is_empty() {
+        profile.auto_reveal = !profile.auto_reveal;
+        if let Ok(mut text) = text_query.get_single_mut() {
+            *text = toggle_text(
+                AUTO_REVEAL_KEYS,
+                profile.auto_reveal,
+                &locale,
+                &locales,
+                &profile,
+                &text_settings,
+            );
+        }
+    }
+}
+
+pub fn dim_satisfied_button_click_system(
+    button_query: Query<&ButtonDimSatisfied, With<ReleasedLeft>>,
+    mut text_query: Query<&mut Text, With<TextDimSatisfied>>,
+    (locale, mut profile, text_settings): (Res<LocaleAssets>, ResMut<Profile>, Res<TextSettings>),
+    locales: Res<Assets<LocaleAsset>>,
+) {
+    if !button_query.is_empty() {
+        profile.dim_satisfied = !profile.dim_satisfied;
+        if let Ok(mut text) = text_query.get_single_mut() {
+            *text = toggle_text(
+                DIM_SATISFIED_KEYS,
+                profile.dim_satisfied,
+                &locale,
+                &locales,
+                &profile,
+                &text_settings,
+            );
+        }
+    }
+}
+
 pub fn return_button_click_system(
     return_query: Query<&ButtonReturn, With<ReleasedLeft>>,
     mut app_state: ResMut<State<AppState>>,
@@ -0,0 +1,712 @@
+use crate::{
+    board_functions::{empty_connected, get_column_positions, get_neighbour_positions},
+    components::ColumnHint,
+    enums::{CellType, HintDirection, HintType},
+    structs::BoardConfig,
+};
+use std::collections::HashMap;
+
+/// Largest number of hidden cells of a single constraint that is still enumerated
+const MAX_SINGLE_UNKNOWN: usize = 16;
+/// Largest number of hidden cells two overlapping constraints may have combined
+const MAX_PAIR_UNKNOWN: usize = 12;
+
+/// Where a constraint comes from, used to explain a deduction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintSource {
+    /// An uncovered number cell at (x, y)
+    Cell(usize, usize),
+    /// The column hint with the given index in the board config
+    Column(usize),
+    /// The remaining counter
+    Remaining,
+}
+
+/// A single clue: exactly `count` cells of `scope` are blue.
+/// Positions in scope are None if they lie outside of the board.
+pub struct Constraint {
+    pub source: ConstraintSource,
+    pub scope: Vec<Option<(usize, usize)>>,
+    pub count: u8,
+    pub hint_type: HintType,
+    pub circular: bool,
+}
+
+/// A hidden cell whose type follows from the constraints in `reasons`
+#[derive(Debug, Clone)]
+pub struct Deduction {
+    pub x: usize,
+    pub y: usize,
+    /// True if the cell must be blue, false if it must be gray
+    pub empty: bool,
+    pub reasons: Vec<ConstraintSource>,
+}
+
+/// Step-by-step logical solver.
+/// The config holds the solution, `hidden` the cells that are not yet known.
+pub struct Solver<'a> {
+    config: &'a BoardConfig,
+    hidden: Vec<Vec<bool>>,
+}
+
+impl<'a> Solver<'a> {
+    /// Starts with the hidden cells of the config
+    pub fn new(config: &'a BoardConfig) -> Self {
+        let hidden = config
+            .cells
+            .iter()
+            .map(|row| row.iter().map(|(ct, h)| ct.is_some() && *h).collect())
+            .collect();
+        Self { config, hidden }
+    }
+
+    /// Starts with an arbitrary state, e.g. a partially solved board
+    pub fn with_hidden(config: &'a BoardConfig, hidden: Vec<Vec<bool>>) -> Self {
+        Self { config, hidden }
+    }
+
+    pub fn get_hidden(&self) -> &Vec<Vec<bool>> {
+        &self.hidden
+    }
+
+    pub fn count_hidden(&self) -> usize {
+        self.hidden
+            .iter()
+            .map(|row| row.iter().filter(|h| **h).count())
+            .sum()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.count_hidden() == 0
+    }
+
+    /// Uncovers a cell. The solver does not care whether it was deduced or guessed.
+    pub fn uncover(&mut self, x: usize, y: usize) {
+        self.hidden[y][x] = false;
+    }
+
+    /// Applies deductions until no more can be found. Returns whether the board was solved.
+    pub fn solve(&mut self) -> bool {
+        loop {
+            let deductions = self.next_deductions();
+            if deductions.is_empty() {
+                break;
+            }
+            for d in deductions {
+                debug_assert_eq!(d.empty, self.is_empty(d.x, d.y));
+                self.uncover(d.x, d.y);
+            }
+        }
+        self.is_solved()
+    }
+
+    /// All constraints visible to a player in the current state
+    pub fn constraints(&self) -> Vec<Constraint> {
+        let (w, h) = (self.config.width, self.config.height);
+        let mut constraints = Vec::new();
+        for (y, row) in self.config.cells.iter().enumerate() {
+            for (x, (ct, _)) in row.iter().enumerate() {
+                if let Some(CellType::NumberCell(ht)) = ct {
+                    if self.hidden[y][x] {
+                        continue;
+                    }
+                    constraints.push(self.make_constraint(
+                        ConstraintSource::Cell(x, y),
+                        get_neighbour_positions(x as i32, y as i32, w, h),
+                        *ht,
+                        true,
+                    ));
+                }
+            }
+        }
+        for (i, hint) in self.config.hints.iter().enumerate() {
+            constraints.push(self.make_constraint(
+                ConstraintSource::Column(i),
+                get_column_positions(hint.x, hint.y, w, h, hint.dir)
+                    .into_iter()
+                    .map(Some)
+                    .collect(),
+                hint.hint_type,
+                false,
+            ));
+        }
+        constraints
+    }
+
+    fn make_constraint(
+        &self,
+        source: ConstraintSource,
+        scope: Vec<Option<(usize, usize)>>,
+        hint_type: HintType,
+        circular: bool,
+    ) -> Constraint {
+        let truth = self.solution_list(&scope);
+        let count = truth
+            .iter()
+            .filter(|(ct, _)| *ct == Some(CellType::EmptyCell))
+            .count() as u8;
+        let hint_type = match hint_type {
+            HintType::None => HintType::None,
+            _ => match empty_connected(&truth, count, circular) {
+                true => HintType::Connected,
+                false => HintType::Seperated,
+            },
+        };
+        Constraint {
+            source,
+            scope,
+            count,
+            hint_type,
+            circular,
+        }
+    }
+
+    /// The solution for the given positions in the format of the board functions
+    fn solution_list(&self, scope: &[Option<(usize, usize)>]) -> Vec<(Option<CellType>, bool)> {
+        scope
+            .iter()
+            .map(|pos| match pos {
+                Some((x, y)) => match self.config.cells[*y][*x].0 {
+                    Some(CellType::EmptyCell) => (Some(CellType::EmptyCell), false),
+                    Some(CellType::NumberCell(_)) => {
+                        (Some(CellType::NumberCell(HintType::None)), false)
+                    }
+                    None => (None, false),
+                },
+                None => (None, false),
+            })
+            .collect()
+    }
+
+    fn is_empty(&self, x: usize, y: usize) -> bool {
+        self.config.cells[y][x].0 == Some(CellType::EmptyCell)
+    }
+
+    /// Hidden cells within the scope of a constraint
+    fn unknowns(&self, constraint: &Constraint) -> Vec<(usize, usize)> {
+        constraint
+            .scope
+            .iter()
+            .flatten()
+            .filter(|(x, y)| self.hidden[*y][*x])
+            .copied()
+            .collect()
+    }
+
+    /// Translates the scope of a constraint for the enumeration of the given hidden cells
+    fn slots(&self, constraint: &Constraint, cells: &[(usize, usize)]) -> Vec<Slot> {
+        constraint
+            .scope
+            .iter()
+            .map(|pos| match pos {
+                Some((x, y)) if self.config.cells[*y][*x].0.is_some() => {
+                    match cells.iter().position(|p| p == &(*x, *y)) {
+                        Some(i) => Slot::Unknown(i),
+                        None => Slot::Known(self.is_empty(*x, *y)),
+                    }
+                }
+                _ => Slot::Gap,
+            })
+            .collect()
+    }
+
+    /// Checks a constraint against an assignment of hidden cells, given as bit mask
+    fn satisfies(constraint: &Constraint, slots: &[Slot], mask: u32) -> bool {
+        let list: Vec<(Option<CellType>, bool)> = slots
+            .iter()
+            .map(|slot| {
+                let empty = match slot {
+                    Slot::Gap => return (None, false),
+                    Slot::Known(empty) => *empty,
+                    Slot::Unknown(i) => mask & (1 << i) != 0,
+                };
+                if empty {
+                    (Some(CellType::EmptyCell), false)
+                } else {
+                    (Some(CellType::NumberCell(HintType::None)), false)
+                }
+            })
+            .collect();
+        let count = list
+            .iter()
+            .filter(|(ct, _)| *ct == Some(CellType::EmptyCell))
+            .count() as u8;
+        if count != constraint.count {
+            return false;
+        }
+        match constraint.hint_type {
+            HintType::Connected => empty_connected(&list, count, constraint.circular),
+            HintType::Seperated => !empty_connected(&list, count, constraint.circular),
+            _ => true,
+        }
+    }
+
+    /// Enumerates all assignments of the given cells that satisfy every constraint and,
+    /// if given, contain the given number of blue cells.
+    /// Returns for every cell whether it can be blue and whether it can be gray.
+    fn possibilities(
+        &self,
+        cells: &[(usize, usize)],
+        constraints: &[&Constraint],
+        total: Option<u32>,
+    ) -> Vec<(bool, bool)> {
+        let compiled: Vec<(&Constraint, Vec<Slot>)> = constraints
+            .iter()
+            .map(|c| (*c, self.slots(c, cells)))
+            .collect();
+        let mut possible = vec![(false, false); cells.len()];
+        for mask in 0u32..(1 << cells.len()) {
+            if total.is_some_and(|t| mask.count_ones() != t) {
+                continue;
+            }
+            if compiled
+                .iter()
+                .all(|(c, slots)| Self::satisfies(c, slots, mask))
+            {
+                for (i, p) in possible.iter_mut().enumerate() {
+                    if mask & (1 << i) != 0 {
+                        p.0 = true;
+                    } else {
+                        p.1 = true;
+                    }
+                }
+            }
+        }
+        possible
+    }
+
+    /// Finds every cell that can be deduced in the current state.
+    /// Single constraints are tried first, then pairs of overlapping constraints
+    /// and finally the remaining counter.
+    pub fn next_deductions(&self) -> Vec<Deduction> {
+        let constraints = self.constraints();
+        let unknowns: Vec<Vec<(usize, usize)>> =
+            constraints.iter().map(|c| self.unknowns(c)).collect();
+        let mut deductions = Vec::new();
+        let mut found = vec![vec![false; self.config.width]; self.config.height];
+
+        for (c, unknown) in constraints.iter().zip(unknowns.iter()) {
+            if unknown.is_empty() {
+                continue;
+            }
+            let forced = if unknown.len() <= MAX_SINGLE_UNKNOWN {
+                self.possibilities(unknown, &[c], None)
+                    .into_iter()
+                    .zip(unknown.iter())
+                    .filter_map(|((can_empty, can_gray), pos)| match (can_empty, can_gray) {
+                        (true, false) => Some((*pos, true)),
+                        (false, true) => Some((*pos, false)),
+                        _ => None,
+                    })
+                    .collect()
+            } else {
+                self.count_forced(c, unknown)
+            };
+            for ((x, y), empty) in forced {
+                if !found[y][x] {
+                    found[y][x] = true;
+                    deductions.push(Deduction {
+                        x,
+                        y,
+                        empty,
+                        reasons: vec![c.source],
+                    });
+                }
+            }
+        }
+        if !deductions.is_empty() {
+            return deductions;
+        }
+
+        // Map every hidden cell to the constraints containing it, to find overlaps
+        let mut containing: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (i, unknown) in unknowns.iter().enumerate() {
+            for pos in unknown {
+                containing.entry(*pos).or_default().push(i);
+            }
+        }
+        for (i, unknown) in unknowns.iter().enumerate() {
+            if unknown.is_empty() || unknown.len() > MAX_PAIR_UNKNOWN {
+                continue;
+            }
+            let mut partners: Vec<usize> = unknown
+                .iter()
+                .flat_map(|pos| containing[pos].iter().copied())
+                .filter(|j| *j > i)
+                .collect();
+            partners.sort_unstable();
+            partners.dedup();
+            for j in partners {
+                let mut union = unknown.clone();
+                for pos in &unknowns[j] {
+                    if !union.contains(pos) {
+                        union.push(*pos);
+                    }
+                }
+                if union.len() > MAX_PAIR_UNKNOWN {
+                    continue;
+                }
+                let possible =
+                    self.possibilities(&union, &[&constraints[i], &constraints[j]], None);
+                for (pos, p) in union.iter().zip(possible) {
+                    let empty = match p {
+                        (true, false) => true,
+                        (false, true) => false,
+                        _ => continue,
+                    };
+                    if !found[pos.1][pos.0] {
+                        found[pos.1][pos.0] = true;
+                        deductions.push(Deduction {
+                            x: pos.0,
+                            y: pos.1,
+                            empty,
+                            reasons: vec![constraints[i].source, constraints[j].source],
+                        });
+                    }
+                }
+            }
+        }
+        if !deductions.is_empty() {
+            return deductions;
+        }
+
+        self.remaining_deductions(&constraints)
+    }
+
+    /// Cells forced by counting alone: a clue whose blue count is reached or can only
+    /// be reached with all of its hidden cells, and the same for the remaining counter
+    pub fn trivial_deductions(&self) -> Vec<Deduction> {
+        let mut deductions = Vec::new();
+        let mut found = vec![vec![false; self.config.width]; self.config.height];
+        for c in self.constraints() {
+            let unknown = self.unknowns(&c);
+            for ((x, y), empty) in self.count_forced(&c, &unknown) {
+                if !found[y][x] {
+                    found[y][x] = true;
+                    deductions.push(Deduction {
+                        x,
+                        y,
+                        empty,
+                        reasons: vec![c.source],
+                    });
+                }
+            }
+        }
+        let hidden = self.count_hidden();
+        let remaining = (0..self.config.height)
+            .flat_map(|y| (0..self.config.width).map(move |x| (x, y)))
+            .filter(|(x, y)| self.hidden[*y][*x] && self.is_empty(*x, *y))
+            .count();
+        if remaining == 0 || remaining == hidden {
+            for (y, row) in self.hidden.iter().enumerate() {
+                for (x, h) in row.iter().enumerate() {
+                    if *h && !found[y][x] {
+                        found[y][x] = true;
+                        deductions.push(Deduction {
+                            x,
+                            y,
+                            empty: remaining != 0,
+                            reasons: vec![ConstraintSource::Remaining],
+                        });
+                    }
+                }
+            }
+        }
+        deductions
+    }
+
+    /// Deductions for constraints too large to enumerate, only by counting
+    fn count_forced(&self, c: &Constraint, unknown: &[(usize, usize)]) -> Vec<((usize, usize), bool)> {
+        let known_empty = c
+            .scope
+            .iter()
+            .flatten()
+            .filter(|(x, y)| !self.hidden[*y][*x] && self.is_empty(*x, *y))
+            .count();
+        let missing = c.count as usize - known_empty;
+        if missing == 0 {
+            unknown.iter().map(|pos| (*pos, false)).collect()
+        } else if missing == unknown.len() {
+            unknown.iter().map(|pos| (*pos, true)).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Uses the remaining counter. Once only a few cells are hidden, all of them are
+    /// enumerated together with every constraint.
+    fn remaining_deductions(&self, constraints: &[Constraint]) -> Vec<Deduction> {
+        let hidden: Vec<(usize, usize)> = self
+            .hidden
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, h)| **h)
+                    .map(move |(x, _)| (x, y))
+            })
+            .collect();
+        let remaining = hidden.iter().filter(|(x, y)| self.is_empty(*x, *y)).count();
+        if remaining == 0 || remaining == hidden.len() {
+            return hidden
+                .into_iter()
+                .map(|(x, y)| Deduction {
+                    x,
+                    y,
+                    empty: remaining != 0,
+                    reasons: vec![ConstraintSource::Remaining],
+                })
+                .collect();
+        }
+        if hidden.len() > MAX_SINGLE_UNKNOWN {
+            return Vec::new();
+        }
+        let involved: Vec<&Constraint> = constraints
+            .iter()
+            .filter(|c| {
+                c.scope
+                    .iter()
+                    .flatten()
+                    .any(|(x, y)| self.hidden[*y][*x])
+            })
+            .collect();
+        let possible = self.possibilities(&hidden, &involved, Some(remaining as u32));
+        let mut reasons: Vec<ConstraintSource> = involved.iter().map(|c| c.source).collect();
+        reasons.push(ConstraintSource::Remaining);
+        hidden
+            .into_iter()
+            .zip(possible)
+            .filter_map(|((x, y), p)| match p {
+                (true, false) => Some((x, y, true)),
+                (false, true) => Some((x, y, false)),
+                _ => None,
+            })
+            .map(|(x, y, empty)| Deduction {
+                x,
+                y,
+                empty,
+                reasons: reasons.clone(),
+            })
+            .collect()
+    }
+}
+
+/// A position in the scope of a constraint during enumeration
+enum Slot {
+    /// Outside of the board or no cell
+    Gap,
+    /// Uncovered, true if blue
+    Known(bool),
+    /// Index of the hidden cell in the enumeration
+    Unknown(usize),
+}
+
+/// Ways to add information to a puzzle, in the order they are preferred
+#[derive(Clone, Copy)]
+enum Clue {
+    Special(usize, usize),
+    Hint(ColumnHint),
+    Reveal(usize, usize),
+}
+
+/// Works out which cells must be revealed and which special hints and column hints are
+/// needed for the board to be solvable without guessing, keeping as many cells hidden as
+/// possible. Special hints and column hints already set are kept.
+pub fn minimal_reveal(config: &BoardConfig) -> BoardConfig {
+    let mut design = config.clone();
+    for row in design.cells.iter_mut() {
+        for (ct, hidden) in row.iter_mut() {
+            *hidden = ct.is_some();
+        }
+    }
+    let mut clues = Vec::new();
+    loop {
+        let mut solver = Solver::new(&design);
+        if solver.solve() {
+            break;
+        }
+        let stuck = solver.get_hidden().clone();
+        let progress = |clue: Clue| {
+            let mut candidate = design.clone();
+            add_clue(&mut candidate, clue);
+            let mut hidden = stuck.clone();
+            if let Clue::Reveal(x, y) = clue {
+                hidden[y][x] = false;
+            }
+            let mut solver = Solver::with_hidden(&candidate, hidden);
+            solver.solve();
+            solver.count_hidden()
+        };
+        let before = solver.count_hidden();
+        let best = [
+            special_candidates(&design, &stuck),
+            hint_candidates(&design, &stuck),
+            reveal_candidates(&stuck),
+        ]
+        .into_iter()
+        .find_map(|candidates| {
+            candidates
+                .into_iter()
+                .map(|clue| (clue, progress(clue)))
+                .filter(|(_, left)| *left < before)
+                .min_by_key(|(_, left)| *left)
+        });
+        // A reveal always makes progress, so this only happens on an empty board
+        let clue = match best {
+            Some((clue, _)) => clue,
+            None => break,
+        };
+        add_clue(&mut design, clue);
+        clues.push(clue);
+    }
+    // Drop clues that later clues made redundant
+    for clue in clues.into_iter().rev() {
+        let mut candidate = design.clone();
+        remove_clue(&mut candidate, clue);
+        if Solver::new(&candidate).solve() {
+            design = candidate;
+        }
+    }
+    design
+}
+
+fn add_clue(config: &mut BoardConfig, clue: Clue) {
+    match clue {
+        Clue::Special(x, y) => config.cells[y][x].0 = Some(CellType::NumberCell(HintType::Some)),
+        Clue::Hint(hint) => config.hints.push(hint),
+        Clue::Reveal(x, y) => config.cells[y][x].1 = false,
+    }
+}
+
+fn remove_clue(config: &mut BoardConfig, clue: Clue) {
+    match clue {
+        Clue::Special(x, y) => config.cells[y][x].0 = Some(CellType::NumberCell(HintType::None)),
+        Clue::Hint(hint) => config.hints.retain(|h| !same_column(h, &hint)),
+        Clue::Reveal(x, y) => config.cells[y][x].1 = true,
+    }
+}
+
+/// Uncovered number cells without special hint
+fn special_candidates(config: &BoardConfig, hidden: &[Vec<bool>]) -> Vec<Clue> {
+    let mut candidates = Vec::new();
+    for (y, row) in config.cells.iter().enumerate() {
+        for (x, (ct, _)) in row.iter().enumerate() {
+            if *ct == Some(CellType::NumberCell(HintType::None)) && !hidden[y][x] {
+                candidates.push(Clue::Special(x, y));
+            }
+        }
+    }
+    candidates
+}
+
+/// Column hints at the start of a column (or diagonal) that still contains hidden cells
+fn hint_candidates(config: &BoardConfig, hidden: &[Vec<bool>]) -> Vec<Clue> {
+    let (w, h) = (config.width, config.height);
+    let mut candidates = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            if config.cells[y][x].0.is_none() {
+                continue;
+            }
+            for (dir, opposite) in [
+                (HintDirection::Down, HintDirection::Up),
+                (HintDirection::LeftDown, HintDirection::RightUp),
+                (HintDirection::RightDown, HintDirection::LeftUp),
+            ] {
+                // The hint is placed in front of the column, so no cells may be there
+                let free = get_column_positions(x, y, w, h, opposite)
+                    .into_iter()
+                    .filter(|pos| *pos != (x, y))
+                    .all(|(x, y)| config.cells[y][x].0.is_none());
+                let useful = get_column_positions(x, y, w, h, dir)
+                    .into_iter()
+                    .any(|(x, y)| hidden[y][x]);
+                let exists = config
+                    .hints
+                    .iter()
+                    .any(|hint| hint.x == x && hint.y == y && hint.dir == dir);
+                if free && useful && !exists {
+                    for hint_type in [HintType::None, HintType::Some] {
+                        candidates.push(Clue::Hint(ColumnHint {
+                            x,
+                            y,
+                            dir,
+                            hint_type,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// Every hidden cell
+fn reveal_candidates(hidden: &[Vec<bool>]) -> Vec<Clue> {
+    let mut candidates = Vec::new();
+    for (y, row) in hidden.iter().enumerate() {
+        for (x, hidden) in row.iter().enumerate() {
+            if *hidden {
+                candidates.push(Clue::Reveal(x, y));
+            }
+        }
+    }
+    candidates
+}
+
+/// Whether two hints describe the same column
+pub fn same_column(a: &ColumnHint, b: &ColumnHint) -> bool {
+    a.x == b.x && a.y == b.y && a.dir == b.dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::board_from_file;
+
+    /// A level of the campaign that needs no column hints
+    const LEVEL: &str = "assets/levels/2/1.lvl";
+
+    fn is_blue(config: &BoardConfig, x: usize, y: usize) -> bool {
+        config.cells[y][x].0 == Some(CellType::EmptyCell)
+    }
+
+    #[test]
+    fn next_deductions_match_the_solution() {
+        let config = board_from_file(LEVEL);
+        let solver = Solver::new(&config);
+        let deductions = solver.next_deductions();
+        assert!(!deductions.is_empty());
+        for d in deductions {
+            assert!(solver.get_hidden()[d.y][d.x]);
+            assert_eq!(d.empty, is_blue(&config, d.x, d.y));
+            assert!(!d.reasons.is_empty());
+        }
+    }
+
+    #[test]
+    fn campaign_level_is_solvable() {
+        let config = board_from_file(LEVEL);
+        assert!(Solver::new(&config).solve());
+    }
+
+    #[test]
+    fn minimal_reveal_keeps_the_board_solvable() {
+        let config = board_from_file(LEVEL);
+        let design = minimal_reveal(&config);
+        // The solution stays the same, only what is hidden and the hints may change
+        for y in 0..config.height {
+            for x in 0..config.width {
+                assert_eq!(
+                    config.cells[y][x].0.is_some(),
+                    design.cells[y][x].0.is_some()
+                );
+                assert_eq!(is_blue(&config, x, y), is_blue(&design, x, y));
+            }
+        }
+        let mut solver = Solver::new(&design);
+        assert!(solver.count_hidden() > 0);
+        assert!(solver.solve());
+    }
+}
@@ -1,5 +1,6 @@
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum AppState {
+    Achievements,
     AssetLoading,
     Credits,
     Dialog,
@@ -10,7 +11,10 @@ pub enum AppState {
     LevelSelection,
     Options,
     Overlay,
+    ProfileSelection,
+    Replay,
     StateChange,
+    Stats,
     Tutorial,
     Quit,
 }
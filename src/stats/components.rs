@@ -0,0 +1,7 @@
+use bevy::prelude::Component;
+
+#[derive(Component)]
+pub struct ButtonReturn;
+
+#[derive(Component)]
+pub struct StatsButton;
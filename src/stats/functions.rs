@@ -0,0 +1,46 @@
+use crate::{
+    resources::{Campaign, Profile},
+    structs::StageEntry,
+};
+
+/// Play time in hours and minutes
+pub fn format_play_time(seconds: f32) -> String {
+    let minutes = (seconds / 60.) as u32;
+    format!("{}:{:02} h", minutes / 60, minutes % 60)
+}
+
+/// Solved levels of a stage and the mistakes per solve, None until something is solved
+pub fn stage_stats(stage: &StageEntry, profile: &Profile) -> (usize, Option<f32>) {
+    let records: Vec<_> = stage
+        .levels
+        .iter()
+        .filter_map(|level| profile.get_record(&level.id))
+        .collect();
+    let solved = records.iter().filter(|r| r.points.is_some()).count();
+    let solves: u32 = records.iter().map(|r| r.solves).sum();
+    let mistakes: u32 = records.iter().map(|r| r.total_mistakes).sum();
+    let rate = (solves > 0).then(|| mistakes as f32 / solves as f32);
+    (solved, rate)
+}
+
+/// The fastest solves of the campaign and the level packs by level name, the fastest first
+pub fn fastest_solves<'a>(
+    profile: &Profile,
+    campaign: &'a Campaign,
+    count: usize,
+) -> Vec<(&'a str, f32)> {
+    let mut solves: Vec<(&str, f32)> = campaign
+        .stages
+        .iter()
+        .chain(campaign.packs.iter())
+        .flat_map(|stage| stage.levels.iter())
+        .filter_map(|level| {
+            profile
+                .get_time(&level.id)
+                .map(|time| (level.name.as_str(), time))
+        })
+        .collect();
+    solves.sort_by(|a, b| a.1.total_cmp(&b.1));
+    solves.truncate(count);
+    solves
+}
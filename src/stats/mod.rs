@@ -0,0 +1,25 @@
+mod components;
+mod functions;
+mod setup;
+mod systems;
+
+use self::{components::StatsButton, setup::setup, systems::*};
+use crate::{
+    cleanup_system, components::RootComponent, states::AppState, systems::menu_button_hovered,
+};
+use bevy::prelude::{App, ParallelSystemDescriptorCoercion, SystemSet};
+use interactable::InteractLabel;
+
+const STATE: AppState = AppState::Stats;
+
+pub fn prepare_stats(app: &mut App) {
+    app.add_system_set(SystemSet::on_enter(STATE).with_system(setup))
+        .add_system_set(
+            SystemSet::on_update(STATE)
+                .with_system(return_button_click_system.after(InteractLabel::Interact))
+                .with_system(menu_button_hovered::<StatsButton>.after(InteractLabel::Interact))
+                .with_system(hotkey_system)
+                .with_system(window_resize_system),
+        )
+        .add_system_set(SystemSet::on_exit(STATE).with_system(cleanup_system::<RootComponent>));
+}
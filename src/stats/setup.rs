@@ -0,0 +1,208 @@
+use super::{
+    components::{ButtonReturn, StatsButton},
+    functions::{fastest_solves, format_play_time, stage_stats},
+};
+use crate::{
+    assets::LocaleAsset,
+    bundles::MenuButtonBundle,
+    components::RootComponent,
+    constants::{RADIUS, Z_INDEX_TEXT},
+    functions::{format_time, rescale_board},
+    resources::{Achievements, Campaign, GameColors, LocaleAssets, Profile, TextSettings},
+};
+use bevy::{
+    hierarchy::BuildChildren,
+    math::{Vec2, Vec3},
+    prelude::{
+        default, shape::Quad, Assets, Commands, Entity, Mesh, Res, ResMut, SpatialBundle, Transform,
+    },
+    sprite::ColorMesh2dBundle,
+    text::{Text, Text2dBundle},
+    window::Windows,
+};
+
+/// Size of the panels of the statistics
+const STATS_PANEL: (f32, f32) = (540., 760.);
+/// Length of the list of the fastest solves
+const FASTEST_SOLVES: usize = 5;
+
+type StandardResources<'a> = (
+    Res<'a, GameColors>,
+    Res<'a, LocaleAssets>,
+    Res<'a, Profile>,
+    Res<'a, TextSettings>,
+);
+pub fn setup(
+    mut commands: Commands,
+    wnds: Res<Windows>,
+    (game_colors, locale, profile, text_settings): StandardResources,
+    (campaign, achievements): (Res<Campaign>, Res<Achievements>),
+    (mut meshes, locales): (ResMut<Assets<Mesh>>, Res<Assets<LocaleAsset>>),
+) {
+    let get = |key: &str| {
+        locale
+            .get_string(key, &locales, &profile)
+            .unwrap_or(&"String not found".to_string())
+            .clone()
+    };
+    let mut children = vec![commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::from_section(get("stats-title"), text_settings.style_cell_large.clone())
+                .with_alignment(text_settings.alignment),
+            transform: Transform::from_xyz(0., 5.1 * RADIUS, Z_INDEX_TEXT),
+            ..default()
+        })
+        .id()];
+
+    let stats = profile.get_stats();
+    let unlocked = achievements
+        .entries
+        .iter()
+        .filter(|entry| profile.has_achievement(&entry.id))
+        .count();
+    let totals = [
+        format!("{}: {}", get("levels-solved"), profile.count_solved()),
+        format!("{}: {}", get("cells-uncovered"), stats.cells_uncovered),
+        format!("{}: {}", get("mistakes"), stats.mistakes),
+        format!(
+            "{}: {}",
+            get("play-time"),
+            format_play_time(stats.play_time)
+        ),
+        format!(
+            "{}: {}/{}",
+            get("achievements-title"),
+            unlocked,
+            achievements.entries.len()
+        ),
+    ];
+
+    // Only the campaign, the level packs differ from player to player
+    let mut stages = vec![get("stage-columns"), String::new()];
+    for stage in campaign.stages.iter() {
+        let (solved, rate) = stage_stats(stage, &profile);
+        let rate = rate.map_or("–".to_string(), |rate| format!("{:.1}", rate));
+        stages.push(format!(
+            "{} {}: {}/{} – {}",
+            get("stage"),
+            stage.name,
+            solved,
+            stage.levels.len(),
+            rate
+        ));
+    }
+
+    let mut bests = vec![get("fastest")];
+    let fastest = fastest_solves(&profile, &campaign, FASTEST_SOLVES);
+    if fastest.is_empty() {
+        bests.push("–".to_string());
+    }
+    for (name, time) in fastest {
+        bests.push(format!("{}: {}", name, format_time(time)));
+    }
+    let (daily, endless) = (profile.get_daily(), profile.get_endless());
+    bests.push(String::new());
+    bests.push(format!(
+        "{} – {}: {}",
+        get("daily-title"),
+        get("streak"),
+        daily.best_streak
+    ));
+    bests.push(format!(
+        "{} – {}: {}",
+        get("endless"),
+        get("best-run"),
+        endless.best_score
+    ));
+    bests.push(format!(
+        "{} – {}: {}",
+        get("endless"),
+        get("puzzles"),
+        endless.best_solved
+    ));
+
+    for (x, heading, lines) in [
+        (-560., get("totals"), totals.join("\n")),
+        (0., get("stages"), stages.join("\n")),
+        (560., get("personal-bests"), bests.join("\n")),
+    ] {
+        children.push(spawn_panel(
+            &mut commands,
+            (&game_colors, &text_settings),
+            &mut meshes,
+            x,
+            (heading, lines),
+        ));
+    }
+
+    children.push(
+        commands
+            .spawn_bundle(MenuButtonBundle::new(
+                Transform::from_xyz(0., -4.5 * RADIUS, 0.9),
+                (270., 170.),
+                game_colors.menu_button.clone(),
+                &mut meshes,
+            ))
+            .with_children(|parent| {
+                parent.spawn_bundle(Text2dBundle {
+                    text: Text::from_section(get("return"), text_settings.style_menu_dark.clone())
+                        .with_alignment(text_settings.alignment),
+                    transform: Transform::from_xyz(0., -10., 10.)
+                        .with_scale(Vec3::new(0.75, 0.75, 1.)),
+                    ..default()
+                });
+            })
+            .insert(ButtonReturn)
+            .insert(StatsButton)
+            .id(),
+    );
+
+    let mut root_transform = Transform::identity();
+    for wnd in wnds.iter() {
+        // TODO: Remove hard-coded width/height
+        rescale_board(10, 6, 1, wnd.width(), wnd.height(), &mut root_transform);
+    }
+
+    commands
+        .spawn()
+        .push_children(&children)
+        .insert_bundle(SpatialBundle::from_transform(root_transform))
+        .insert(RootComponent);
+}
+
+/// Spawns a panel with a heading and lines of text below it
+fn spawn_panel(
+    commands: &mut Commands,
+    (game_colors, text_settings): (&GameColors, &TextSettings),
+    meshes: &mut Assets<Mesh>,
+    x: f32,
+    (heading, lines): (String, String),
+) -> Entity {
+    commands
+        .spawn_bundle(ColorMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(Quad::new(Vec2::new(
+                    STATS_PANEL.0,
+                    STATS_PANEL.1,
+                ))))
+                .into(),
+            material: game_colors.menu_button.clone(),
+            transform: Transform::from_xyz(x, 0.3 * RADIUS, 0.9),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn_bundle(Text2dBundle {
+                text: Text::from_section(heading, text_settings.style_menu_dark.clone())
+                    .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(0., 310., 10.).with_scale(Vec3::new(0.75, 0.75, 1.)),
+                ..default()
+            });
+            parent.spawn_bundle(Text2dBundle {
+                text: Text::from_section(lines, text_settings.style_menu_dark.clone())
+                    .with_alignment(text_settings.alignment),
+                transform: Transform::from_xyz(0., -40., 10.).with_scale(Vec3::new(0.45, 0.45, 1.)),
+                ..default()
+            });
+        })
+        .id()
+}
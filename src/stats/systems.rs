@@ -0,0 +1,45 @@
+use super::components::ButtonReturn;
+use crate::{
+    components::RootComponent,
+    functions::{rescale_board, switch_state},
+    resources::LoadState,
+    states::AppState,
+};
+use bevy::{
+    input::Input,
+    prelude::{EventReader, KeyCode, Query, ResMut, State, Transform, With},
+    window::WindowResized,
+};
+use interactable::components::ReleasedLeft;
+
+pub fn return_button_click_system(
+    return_query: Query<&ButtonReturn, With<ReleasedLeft>>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if !return_query.is_empty() {
+        switch_state(Some(AppState::Home), &mut app_state, &mut load_state);
+    }
+}
+
+pub fn hotkey_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        keys.clear_just_pressed(KeyCode::Escape);
+        switch_state(Some(AppState::Home), &mut app_state, &mut load_state);
+    }
+}
+
+/// On resizing the window, the statistics are resized too
+pub fn window_resize_system(
+    mut ev_window_resize: EventReader<WindowResized>,
+    mut root_query: Query<&mut Transform, With<RootComponent>>,
+) {
+    for ev in ev_window_resize.iter() {
+        if let Ok(mut root) = root_query.get_single_mut() {
+            // TODO: Remove hard-coded width/height
+            rescale_board(10, 6, 1, ev.width, ev.height, &mut root);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    constants::{PROFILES_DIR, PROFILES_FILE, USER_FILES},
+    functions::{get_base_path, get_user_path},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use native_dialog::MessageDialog;
+use ron::{
+    de::from_reader,
+    ser::{to_string_pretty, PrettyConfig},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Suffixes of the files kept next to a save file
+const TMP_SUFFIX: &str = "tmp";
+const BACKUP_SUFFIX: &str = "bak";
+const CORRUPT_SUFFIX: &str = "corrupt";
+
+/// Seconds between a change and saving it
+const SAVE_DELAY: f64 = 1.0;
+
+/// Result of loading a save file
+pub enum Loaded<T> {
+    /// There is no file yet
+    Missing,
+    Ok(T),
+    /// The file was broken, the value comes from the backup
+    Restored(T),
+    /// Neither the file nor its backup could be read
+    Broken,
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Writes the whole file or nothing. The content goes to a temporary file first,
+/// which then replaces the old file.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let tmp = with_suffix(path, TMP_SUFFIX);
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+pub fn save_ron<T: Serialize>(path: &Path, value: &T, config: PrettyConfig) -> io::Result<()> {
+    let contents = to_string_pretty(value, config).map_err(io::Error::other)?;
+    write_atomic(path, &contents)
+}
+
+/// Loads a save file. Every file that can be read is copied as backup,
+/// a broken one is moved aside and the backup is loaded instead.
+pub fn load_ron<T: DeserializeOwned>(path: &Path) -> Loaded<T> {
+    let read = |path: &Path| -> Option<T> { from_reader(File::open(path).ok()?).ok() };
+    let backup = with_suffix(path, BACKUP_SUFFIX);
+    if !path.exists() {
+        return match read(&backup) {
+            Some(value) => Loaded::Restored(value),
+            None => Loaded::Missing,
+        };
+    }
+    if let Some(value) = read(path) {
+        let _ = fs::copy(path, &backup);
+        return Loaded::Ok(value);
+    }
+    // Kept for inspection, the next save would overwrite it
+    let _ = fs::rename(path, with_suffix(path, CORRUPT_SUFFIX));
+    match read(&backup) {
+        Some(value) => Loaded::Restored(value),
+        None => Loaded::Broken,
+    }
+}
+
+/// Copies the player's files from the executable's directory to the user directory,
+/// where they are kept since. Files that already exist there are never replaced,
+/// so this only has an effect once.
+pub fn migrate_user_files() {
+    let (old, new) = (get_base_path(), get_user_path());
+    // Once there are profiles the files were moved on into the first one,
+    // copying them again would only leave stale files behind
+    if old == new || new.join(PROFILES_FILE).exists() || new.join(PROFILES_DIR).exists() {
+        return;
+    }
+    for file in USER_FILES {
+        let (from, to) = (old.join(file), new.join(file));
+        if from.exists() && !to.exists() && !with_suffix(&to, BACKUP_SUFFIX).exists() {
+            let _ = fs::copy(&from, &to);
+        }
+    }
+}
+
+/// Moves the player's files from the user directory into the directory of a profile.
+/// Files that already exist there are never replaced.
+pub fn move_user_files(dir: &Path) {
+    let root = get_user_path();
+    for file in USER_FILES {
+        for name in [file.to_string(), format!("{}.{}", file, BACKUP_SUFFIX)] {
+            let (from, to) = (root.join(&name), dir.join(&name));
+            if from.exists() && !to.exists() {
+                let _ = fs::rename(&from, &to);
+            }
+        }
+    }
+}
+
+/// Resources that are written to a file, by the save systems and on quitting
+pub trait Saved {
+    fn save(&self);
+}
+
+/// Keeps track of when a changed resource is due to be saved, so that
+/// a burst of changes is written once. Returns true when it should be saved now.
+pub fn save_due(changed: bool, now: f64, due: &mut Option<f64>) -> bool {
+    if changed && due.is_none() {
+        *due = Some(now + SAVE_DELAY);
+    }
+    match *due {
+        Some(time) if now >= time => {
+            *due = None;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Tells the player about a problem that doesn't stop the game
+pub fn show_notice(text: &str) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = MessageDialog::new()
+        .set_type(native_dialog::MessageType::Warning)
+        .set_title("Honeycombs")
+        .set_text(text)
+        .show_alert();
+    eprintln!("{}", text);
+}
@@ -1,17 +1,200 @@
-use crate::{components::ColumnHint, enums::CellType};
+use crate::{
+    components::ColumnHint,
+    enums::{CellType, Goal, Mark},
+};
 use bevy::{
     prelude::Color,
     text::{TextSection, TextStyle},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Type of a cell and whether it is hidden
+pub type GridCell = (Option<CellType>, bool);
+/// The cells of a board by row
+pub type CellGrid = Vec<Vec<GridCell>>;
 
 /// Used to pass configuration from parser to board
+#[derive(Debug, Clone)]
 pub struct BoardConfig {
     pub width: usize,
     pub height: usize,
-    pub cells: Vec<Vec<(Option<CellType>, bool)>>,
+    pub cells: CellGrid,
     pub hints: Vec<ColumnHint>,
-    pub text: Option<(i32, i32, String)>,
+    /// Anchor and content of the text below the board
+    pub text: Option<(i32, i32, LevelText)>,
+    pub meta: LevelMeta,
+}
+
+/// Text of a level, either a key of the text sections in the locale files
+/// or text that is shown as is in every language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LevelText {
+    Key(String),
+    Inline(String),
+}
+
+/// Optional information about a level
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LevelMeta {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub difficulty: Option<u8>,
+}
+
+/// A stage of the campaign manifest, or a level pack of the players
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageEntry {
+    pub name: String,
+    /// Only set for level packs
+    #[serde(default)]
+    pub author: String,
+    /// Points needed to unlock the stage, level packs are always unlocked
+    #[serde(default)]
+    pub unlock_points: u16,
+    pub levels: Vec<LevelEntry>,
+}
+
+/// A level of the campaign manifest or a level pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelEntry {
+    /// Stable identifier, the scores in the profile are saved under it
+    pub id: String,
+    /// Path of the level file, relative to the levels directory or the pack
+    pub file: String,
+    /// Shown in the level selection
+    pub name: String,
+}
+
+/// A player profile in the list of profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    /// Name of the profile's directory, it stays the same on renaming
+    pub id: String,
+    pub name: String,
+}
+
+/// Results of a level in the profile
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LevelRecord {
+    /// Best points, None until the level is solved
+    #[serde(default)]
+    pub points: Option<u16>,
+    /// Fastest solve in seconds
+    #[serde(default)]
+    pub time: Option<f32>,
+    /// Fewest mistakes of a solve
+    #[serde(default)]
+    pub mistakes: Option<u16>,
+    /// Best points in the hardcore mode, tracked apart from the normal ones
+    #[serde(default)]
+    pub hardcore_points: Option<u16>,
+    /// First solve in seconds since the unix epoch
+    #[serde(default)]
+    pub completed: Option<u64>,
+    /// How often the level was started from scratch
+    #[serde(default)]
+    pub attempts: u32,
+    /// How often the level was solved
+    #[serde(default)]
+    pub solves: u32,
+    /// Mistakes of all solves together
+    #[serde(default)]
+    pub total_mistakes: u32,
+}
+
+/// Result of a daily puzzle
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub points: u16,
+    pub max_points: u16,
+    pub mistakes: u16,
+    /// Solve time in seconds
+    pub time: f32,
+}
+
+/// Results of the daily puzzles in the profile
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DailyRecord {
+    /// The first solve of each puzzle, by the days since 1970-01-01
+    #[serde(default)]
+    pub results: BTreeMap<u32, DailyResult>,
+    /// Most days solved in a row
+    #[serde(default)]
+    pub best_streak: u32,
+    #[serde(default)]
+    pub best_points: Option<u16>,
+    /// Fastest solve in seconds
+    #[serde(default)]
+    pub best_time: Option<f32>,
+}
+impl DailyRecord {
+    /// Days solved in a row up to the given day. The streak goes on while
+    /// the puzzle of the day is still unsolved.
+    pub fn streak(&self, today: u32) -> u32 {
+        let mut day = match self.results.contains_key(&today) {
+            true => Some(today),
+            false => today.checked_sub(1),
+        };
+        let mut streak = 0;
+        while let Some(d) = day.filter(|d| self.results.contains_key(d)) {
+            streak += 1;
+            day = d.checked_sub(1);
+        }
+        streak
+    }
+}
+
+/// Best endless runs in the profile
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct EndlessRecord {
+    #[serde(default)]
+    pub runs: u32,
+    #[serde(default)]
+    pub best_score: u32,
+    /// Most puzzles solved in one run
+    #[serde(default)]
+    pub best_solved: u32,
+    /// Highest difficulty reached in a run
+    #[serde(default)]
+    pub best_difficulty: u8,
+}
+
+/// Totals over everything played, in the profile
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StatsRecord {
+    #[serde(default)]
+    pub cells_uncovered: u32,
+    #[serde(default)]
+    pub mistakes: u32,
+    /// Seconds spent in levels
+    #[serde(default)]
+    pub play_time: f32,
+}
+
+/// An achievement of the achievements file.
+/// Its name and description are found in the locale files by the id.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AchievementEntry {
+    pub id: String,
+    pub goal: Goal,
+}
+
+/// Unlocked achievements and what counts towards them, in the profile
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AchievementRecord {
+    /// Unlock time in seconds since the unix epoch, by the id of the achievement
+    #[serde(default)]
+    pub unlocked: BTreeMap<String, u64>,
+    /// Levels solved in a row without a hint
+    #[serde(default)]
+    pub no_hint_streak: u32,
+    /// Levels solved in a row without a mistake
+    #[serde(default)]
+    pub no_mistake_streak: u32,
+    /// Most hidden cells of a solved board
+    #[serde(default)]
+    pub largest_board: u16,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -37,3 +220,50 @@ impl TextSectionConfig {
         TextSection::new(self.text.clone(), ts)
     }
 }
+
+/// State of a level that is not solved yet, saved to resume it later
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelProgress {
+    #[serde(default)]
+    pub uncovered: Vec<(usize, usize)>,
+    #[serde(default)]
+    pub mistakes: u16,
+    #[serde(default)]
+    pub hints_used: u16,
+    /// Seconds spent in the level
+    #[serde(default)]
+    pub elapsed: f32,
+    #[serde(default)]
+    pub marks: Vec<((usize, usize), Mark)>,
+    #[serde(default)]
+    pub done_cells: Vec<(usize, usize)>,
+    /// Indices of the column hints marked as done
+    #[serde(default)]
+    pub done_hints: Vec<usize>,
+    #[serde(default)]
+    pub moves: Vec<Move>,
+}
+
+impl LevelProgress {
+    /// Whether the progress can belong to the level, the cells it uncovered must be hidden there.
+    /// Levels can move, e.g. when level packs are added.
+    pub fn fits(&self, config: &BoardConfig) -> bool {
+        self.uncovered.iter().all(|(x, y)| {
+            config
+                .cells
+                .get(*y)
+                .and_then(|row| row.get(*x))
+                .is_some_and(|(cell_type, hidden)| cell_type.is_some() && *hidden)
+        })
+    }
+}
+
+/// An uncovered cell or a wrong click on it, recorded to replay a solve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Move {
+    pub x: usize,
+    pub y: usize,
+    pub mistake: bool,
+    /// Seconds since the level was started
+    pub time: f32,
+}
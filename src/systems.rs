@@ -1,7 +1,15 @@
-use bevy::prelude::{ColorMaterial, Component, Handle, Query, Res, With, Without};
+use bevy::prelude::{ColorMaterial, Component, Handle, Query, Res, ResMut, State, With, Without};
+use futures_lite::future;
 use interactable::components::{Entered, Exited};
 
-use crate::{components::MenuButton, resources::GameColors};
+use crate::{
+    components::MenuButton,
+    constants::GENERATED_IDS,
+    enums::PlayMode,
+    functions::switch_state,
+    resources::{GameColors, GenerateTask, LoadState},
+    states::AppState,
+};
 
 pub fn menu_button_hovered<T: Component>(
     mut entered_query: Query<
@@ -21,3 +29,26 @@ pub fn menu_button_hovered<T: Component>(
         *h = game_colors.menu_button.clone();
     }
 }
+
+/// Starts the level once its generated board is done, unless the player went elsewhere meanwhile
+pub fn generate_task_system(
+    mut generating: ResMut<GenerateTask>,
+    (mut app_state, mut load_state): (ResMut<State<AppState>>, ResMut<LoadState>),
+) {
+    let board = match generating.running.as_mut() {
+        Some((_, _, task)) => match future::block_on(future::poll_once(task)) {
+            Some(board) => board,
+            None => return,
+        },
+        None => return,
+    };
+    if let Some((generated, from, _)) = generating.running.take() {
+        if *app_state.current() == from {
+            load_state.generated = Some((generated, board));
+            load_state.filename = None;
+            load_state.ids = Some(GENERATED_IDS);
+            load_state.mode = PlayMode::Normal;
+            switch_state(Some(AppState::Level), &mut app_state, &mut load_state);
+        }
+    }
+}